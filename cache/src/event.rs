@@ -8,7 +8,7 @@ use rowifi_models::{
                 ChannelCreate, ChannelDelete, ChannelUpdate, GuildCreate, GuildDelete, GuildUpdate,
                 InteractionCreate, MemberAdd, MemberChunk, MemberRemove, MemberUpdate,
                 MessageCreate, Ready, RoleCreate, RoleDelete, RoleUpdate, UnavailableGuild,
-                UserUpdate,
+                UserUpdate, VoiceStateUpdate,
             },
         },
     },
@@ -32,6 +32,7 @@ impl UpdateCache for Event {
             ChannelCreate, ChannelDelete, ChannelUpdate, GuildCreate, GuildDelete, GuildUpdate,
             InteractionCreate, MemberAdd, MemberChunk, MemberRemove, MemberUpdate, MessageCreate,
             Ready, RoleCreate, RoleDelete, RoleUpdate, UnavailableGuild, UserUpdate,
+            VoiceStateUpdate,
         };
 
         match self {
@@ -53,6 +54,7 @@ impl UpdateCache for Event {
             RoleUpdate(v) => c.update(v.deref()),
             UnavailableGuild(v) => c.update(v),
             UserUpdate(v) => c.update(v),
+            VoiceStateUpdate(v) => c.update(v.deref()),
             _ => Ok(()),
         }
     }
@@ -124,6 +126,7 @@ impl UpdateCache for GuildDelete {
             for id in ids {
                 c.0.channels.remove(&id);
                 c.0.channel_permissions.remove(&id);
+                c.0.voice_channel_members.remove(&id);
             }
         }
         if let Some((_, ids)) = c.0.guild_roles.remove(&guild_id) {
@@ -131,6 +134,7 @@ impl UpdateCache for GuildDelete {
                 c.0.roles.remove(&id);
             }
         }
+        c.0.voice_states.retain(|(g, _), _| *g != guild_id);
         if let Some((_, ids)) = c.0.guild_members.remove(&guild_id) {
             for id in ids {
                 c.0.members.remove(&(guild_id, id));
@@ -359,3 +363,16 @@ impl UpdateCache for UserUpdate {
         Ok(())
     }
 }
+
+impl UpdateCache for VoiceStateUpdate {
+    fn update(&self, c: &Cache) -> Result<(), CacheError> {
+        if let Some(guild_id) = self.0.guild_id {
+            c.cache_voice_state(
+                GuildId(guild_id),
+                UserId(self.0.user_id),
+                self.0.channel_id.map(ChannelId),
+            );
+        }
+        Ok(())
+    }
+}
@@ -81,6 +81,9 @@ pub struct CacheRef {
     guild_members: DashMap<GuildId, HashSet<UserId>>,
     unavailable_guilds: DashSet<GuildId>,
 
+    voice_states: DashMap<(GuildId, UserId), ChannelId>,
+    voice_channel_members: DashMap<ChannelId, HashSet<UserId>>,
+
     current_user: Mutex<Option<Arc<CurrentUser>>>,
 
     guild_permissions: DashMap<GuildId, Permissions>,
@@ -109,6 +112,8 @@ impl Cache {
             guild_channels: DashMap::new(),
             guild_members: DashMap::new(),
             unavailable_guilds: DashSet::new(),
+            voice_states: DashMap::new(),
+            voice_channel_members: DashMap::new(),
             current_user: Mutex::new(None),
             guild_permissions: DashMap::new(),
             channel_permissions: DashMap::new(),
@@ -217,6 +222,22 @@ impl Cache {
         self.0.users.get(&user_id).map(|u| Arc::clone(u.value()))
     }
 
+    /// Get the voice channel a member is currently connected to
+    pub fn voice_channel(&self, guild_id: GuildId, user_id: UserId) -> Option<ChannelId> {
+        self.0
+            .voice_states
+            .get(&(guild_id, user_id))
+            .map(|c| *c.value())
+    }
+
+    /// Get a list of the ids of all members connected to a voice channel
+    pub fn voice_channel_members(&self, channel_id: ChannelId) -> HashSet<UserId> {
+        self.0
+            .voice_channel_members
+            .get(&channel_id)
+            .map_or_else(HashSet::new, |v| v.value().clone())
+    }
+
     /// Update a resource inside a cache
     pub fn update<T: UpdateCache>(&self, value: &T) -> Result<(), CacheError> {
         value.update(self)
@@ -295,6 +316,22 @@ impl Cache {
         cached
     }
 
+    fn cache_voice_state(&self, guild: GuildId, user: UserId, channel: Option<ChannelId>) {
+        if let Some((_, old)) = self.0.voice_states.remove(&(guild, user)) {
+            if let Some(mut members) = self.0.voice_channel_members.get_mut(&old) {
+                members.remove(&user);
+            }
+        }
+        if let Some(channel) = channel {
+            self.0.voice_states.insert((guild, user), channel);
+            self.0
+                .voice_channel_members
+                .entry(channel)
+                .or_default()
+                .insert(user);
+        }
+    }
+
     fn cache_roles(
         &self,
         guild: GuildId,
@@ -432,6 +469,13 @@ impl Cache {
         self.cache_guild_channels(guild_id, guild.channels.into_iter());
         self.cache_roles(guild_id, guild.roles.into_iter());
         self.cache_members(guild_id, guild.members.into_iter());
+        for voice_state in guild.voice_states {
+            self.cache_voice_state(
+                guild_id,
+                UserId(voice_state.user_id),
+                voice_state.channel_id.map(ChannelId),
+            );
+        }
 
         let cached = CachedGuild {
             id: guild_id,
@@ -9,6 +9,21 @@ pub use tokio_postgres::Error as PostgresError;
 #[derive(Debug)]
 pub enum DatabaseError {
     Postgres(PoolError),
+    Crypto(CryptoError),
+}
+
+#[derive(Debug)]
+pub enum CryptoError {
+    /// The key with this id is not 32 bytes long
+    InvalidKey(u8),
+    /// A retired key was given the id of the primary key
+    DuplicateKey(u8),
+    /// The ciphertext was encrypted with a key that is not in the keyring
+    UnknownKey(u8),
+    /// The secret the blind index key is derived from is empty
+    InvalidIndexKey,
+    Encryption,
+    Decryption,
 }
 
 impl From<PoolError> for DatabaseError {
@@ -17,6 +32,12 @@ impl From<PoolError> for DatabaseError {
     }
 }
 
+impl From<CryptoError> for DatabaseError {
+    fn from(err: CryptoError) -> Self {
+        DatabaseError::Crypto(err)
+    }
+}
+
 impl From<PostgresError> for DatabaseError {
     fn from(err: PostgresError) -> Self {
         PoolError::from(err).into()
@@ -27,8 +48,24 @@ impl Display for DatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
             Self::Postgres(err) => Display::fmt(err, f),
+            Self::Crypto(err) => Display::fmt(err, f),
         }
     }
 }
 
 impl StdError for DatabaseError {}
+
+impl Display for CryptoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::InvalidKey(key_id) => write!(f, "Key {} must be 32 bytes long", key_id),
+            Self::DuplicateKey(key_id) => write!(f, "Key {} is already the primary key", key_id),
+            Self::UnknownKey(key_id) => write!(f, "Key {} is not in the keyring", key_id),
+            Self::InvalidIndexKey => f.write_str("The index key must not be empty"),
+            Self::Encryption => f.write_str("Failed to encrypt"),
+            Self::Decryption => f.write_str("Failed to decrypt"),
+        }
+    }
+}
+
+impl StdError for CryptoError {}
@@ -0,0 +1,72 @@
+use aes_gcm::{Aes256Gcm, Key, NewAead};
+use hmac::Mac;
+use std::collections::HashMap;
+
+use crate::{error::CryptoError, HmacSha256};
+
+/// The id of the key that ciphertexts written before key ids existed were encrypted with
+pub const LEGACY_KEY_ID: u8 = 0;
+
+/// The keys that encrypted text is written and read with. New text is always encrypted with the
+/// primary key while retired keys are kept so that older text can still be read and re-encrypted
+pub struct Keyring {
+    keys: HashMap<u8, Aes256Gcm>,
+    primary_id: u8,
+    index_key: Vec<u8>,
+}
+
+impl Keyring {
+    /// Create a keyring with its primary key. The blind index key is derived from a secret of its
+    /// own so that rotating the primary key leaves the indexes intact
+    pub fn new(primary_id: u8, primary_key: &str, index_secret: &str) -> Result<Self, CryptoError> {
+        let mut keys = HashMap::new();
+        keys.insert(primary_id, cipher(primary_id, primary_key)?);
+        if index_secret.is_empty() {
+            return Err(CryptoError::InvalidIndexKey);
+        }
+        let index_key = HmacSha256::new_from_slice(index_secret.as_bytes())
+            .map_err(|_| CryptoError::InvalidIndexKey)?
+            .chain_update(b"rowifi-blind-index")
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        Ok(Self {
+            keys,
+            primary_id,
+            index_key,
+        })
+    }
+
+    /// Add a retired key that is only used to read text encrypted with it
+    pub fn add_key(&mut self, key_id: u8, key: &str) -> Result<(), CryptoError> {
+        if key_id == self.primary_id {
+            return Err(CryptoError::DuplicateKey(key_id));
+        }
+        self.keys.insert(key_id, cipher(key_id, key)?);
+        Ok(())
+    }
+
+    pub fn primary_id(&self) -> u8 {
+        self.primary_id
+    }
+
+    pub fn primary(&self) -> &Aes256Gcm {
+        &self.keys[&self.primary_id]
+    }
+
+    pub fn get(&self, key_id: u8) -> Option<&Aes256Gcm> {
+        self.keys.get(&key_id)
+    }
+
+    /// The key the blind indexes of encrypted text are computed with
+    pub fn index_key(&self) -> &[u8] {
+        &self.index_key
+    }
+}
+
+fn cipher(key_id: u8, key: &str) -> Result<Aes256Gcm, CryptoError> {
+    if key.len() != 32 {
+        return Err(CryptoError::InvalidKey(key_id));
+    }
+    Ok(Aes256Gcm::new(Key::from_slice(key.as_bytes())))
+}
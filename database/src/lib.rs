@@ -1,31 +1,41 @@
 pub mod error;
+mod keyring;
 
-use aes_gcm::{
-    aead::{generic_array::GenericArray, Aead},
-    Aes256Gcm, Key, NewAead,
-};
+use aes_gcm::aead::{generic_array::GenericArray, Aead, Payload};
 use deadpool_postgres::{Manager, Object, Pool, Runtime};
+use hmac::{Hmac, Mac};
 use itertools::Itertools;
+use rand::{rngs::OsRng, RngCore};
 use rowifi_models::{
+    blacklist::SubscribedBlacklist,
     guild::RoGuild,
     id::{GuildId, UserId},
     user::{RoGuildUser, RoUser},
     FromRow,
 };
+use sha2::Sha256;
 use std::{str::FromStr, time::Duration};
 use tokio_postgres::{types::ToSql, Config as TokioPostgresConfig, NoTls};
 
-use error::DatabaseError;
+use error::{CryptoError, DatabaseError};
 
+pub use keyring::{Keyring, LEGACY_KEY_ID};
 pub use tokio_postgres as postgres;
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// The first byte of every ciphertext written in the current format
+pub const CIPHERTEXT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 2;
+const NONCE_LEN: usize = 12;
+
 pub struct Database {
     pool: Pool,
-    pub cipher: Aes256Gcm,
+    pub keyring: Keyring,
 }
 
 impl Database {
-    pub async fn new(connection_string: &str, primary_key: &str) -> Self {
+    pub async fn new(connection_string: &str, keyring: Keyring) -> Self {
         let postgres_config = TokioPostgresConfig::from_str(connection_string).unwrap();
 
         let manager = Manager::new(postgres_config, NoTls);
@@ -41,10 +51,7 @@ impl Database {
         tracing::debug!("Connecting to postgres...");
         let _ = pool.get().await.unwrap();
 
-        let key = Key::from_slice(primary_key.as_bytes());
-        let cipher = Aes256Gcm::new(key);
-
-        Self { pool, cipher }
+        Self { pool, keyring }
     }
 
     pub async fn get(&self) -> Result<Object, DatabaseError> {
@@ -131,6 +138,23 @@ impl Database {
         }
     }
 
+    /// Get the blacklists of the shared lists the guild is subscribed to. The entries come from the
+    /// owning guilds, so their changes apply to every subscriber
+    pub async fn get_subscribed_blacklists(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<SubscribedBlacklist>, DatabaseError> {
+        let blacklists = self.query::<SubscribedBlacklist>(
+            "SELECT s.guild_id, s.name, unnest(g.blacklists) AS blacklist FROM blacklist_subscriptions bs INNER JOIN shared_blacklists s ON s.list_id = bs.list_id INNER JOIN guilds g ON g.guild_id = s.guild_id WHERE bs.guild_id = $1",
+            &[&guild_id],
+        )
+        .await?;
+        Ok(blacklists
+            .into_iter()
+            .filter(|s| s.blacklist.is_shareable())
+            .collect())
+    }
+
     pub async fn get_user(&self, user_id: i64) -> Result<Option<RoUser>, DatabaseError> {
         let client = self.get().await?;
         let statement = client
@@ -185,40 +209,235 @@ pub fn dynamic_args_with_start(size: usize, start: usize) -> String {
     (0..size).map(|i| format!("${}", i + start)).join(", ")
 }
 
+/// Encrypt with the primary key of the keyring. The ciphertext is laid out as the format version,
+/// the key id, a random 96-bit nonce and the sealed text. The header and the guild id are
+/// authenticated so a ciphertext cannot be moved to another guild or relabelled with another key
 pub fn encrypt_bytes(
     plaintext: &[u8],
-    aaed: &Aes256Gcm,
+    keyring: &Keyring,
     guild_id: u64,
-    host_id: u64,
-    timestamp: u64,
-) -> Vec<u8> {
-    let mut nonce = [0u8; 12];
-    let guild_id_bytes = guild_id.to_le_bytes();
-    nonce[..4].copy_from_slice(&guild_id_bytes[4..]);
-    let timestamp_bytes = timestamp.to_be_bytes();
-    nonce[4..8].copy_from_slice(&timestamp_bytes[4..]);
-    let host_id_bytes = host_id.to_be_bytes();
-    nonce[8..].copy_from_slice(&host_id_bytes[4..]);
+) -> Result<Vec<u8>, CryptoError> {
+    let header = [CIPHERTEXT_VERSION, keyring.primary_id()];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
 
-    let nonce = GenericArray::from_slice(&nonce);
-    aaed.encrypt(nonce, plaintext).unwrap()
+    let aad = associated_data(header, guild_id);
+    let sealed = keyring
+        .primary()
+        .encrypt(
+            GenericArray::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| CryptoError::Encryption)?;
+
+    let mut ciphertext = Vec::with_capacity(HEADER_LEN + NONCE_LEN + sealed.len());
+    ciphertext.extend_from_slice(&header);
+    ciphertext.extend_from_slice(&nonce);
+    ciphertext.extend_from_slice(&sealed);
+    Ok(ciphertext)
 }
 
+/// Decrypt a ciphertext written by [`encrypt_bytes`]. Ciphertexts from before the versioned format
+/// are read with the legacy key, whose nonce was derived from the host id and timestamp
 pub fn decrypt_bytes(
     ciphertext: &[u8],
-    aaed: &Aes256Gcm,
+    keyring: &Keyring,
     guild_id: u64,
     host_id: u64,
     timestamp: u64,
-) -> Vec<u8> {
-    let mut nonce = [0u8; 12];
+) -> Result<Vec<u8>, CryptoError> {
+    let mut error = CryptoError::Decryption;
+    if ciphertext.len() > HEADER_LEN + NONCE_LEN && ciphertext[0] == CIPHERTEXT_VERSION {
+        let key_id = ciphertext[1];
+        match keyring.get(key_id) {
+            Some(cipher) => {
+                let aad = associated_data([ciphertext[0], key_id], guild_id);
+                let nonce =
+                    GenericArray::from_slice(&ciphertext[HEADER_LEN..HEADER_LEN + NONCE_LEN]);
+                let payload = Payload {
+                    msg: &ciphertext[HEADER_LEN + NONCE_LEN..],
+                    aad: &aad,
+                };
+                if let Ok(plaintext) = cipher.decrypt(nonce, payload) {
+                    return Ok(plaintext);
+                }
+            }
+            None => error = CryptoError::UnknownKey(key_id),
+        }
+    }
+
+    // A legacy ciphertext may start with the version byte by chance, so fall back to it
+    let cipher = match keyring.get(LEGACY_KEY_ID) {
+        Some(c) => c,
+        None => return Err(error),
+    };
+    let nonce = legacy_nonce(guild_id, host_id, timestamp);
+    let nonce = GenericArray::from_slice(&nonce);
+    cipher.decrypt(nonce, ciphertext).map_err(|_| error)
+}
+
+/// The nonce that ciphertexts from before the versioned format were encrypted with
+fn legacy_nonce(guild_id: u64, host_id: u64, timestamp: u64) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
     let guild_id_bytes = guild_id.to_le_bytes();
     nonce[..4].copy_from_slice(&guild_id_bytes[4..]);
     let timestamp_bytes = timestamp.to_be_bytes();
     nonce[4..8].copy_from_slice(&timestamp_bytes[4..]);
     let host_id_bytes = host_id.to_be_bytes();
     nonce[8..].copy_from_slice(&host_id_bytes[4..]);
+    nonce
+}
 
-    let nonce = GenericArray::from_slice(&nonce);
-    aaed.decrypt(nonce, ciphertext).unwrap()
+/// Whether a ciphertext is in the current format and encrypted with the primary key
+pub fn is_current_ciphertext(ciphertext: &[u8], keyring: &Keyring) -> bool {
+    ciphertext.len() > HEADER_LEN + NONCE_LEN
+        && ciphertext[0] == CIPHERTEXT_VERSION
+        && ciphertext[1] == keyring.primary_id()
+}
+
+fn associated_data(header: [u8; HEADER_LEN], guild_id: u64) -> Vec<u8> {
+    let mut aad = header.to_vec();
+    aad.extend_from_slice(&guild_id.to_be_bytes());
+    aad
+}
+
+/// Split text into the lowercase words that can be searched for in a blind index
+pub fn index_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .unique()
+        .collect()
+}
+
+/// Compute the blind index of some text. Every word is hashed with a key that is unique to the
+/// guild so the index can be searched without storing the plaintext or relating words across guilds
+pub fn blind_index(text: &str, key: &[u8], guild_id: u64) -> Vec<Vec<u8>> {
+    index_terms(text)
+        .iter()
+        .map(|term| {
+            let mut mac = HmacSha256::new_from_slice(key).unwrap();
+            mac.update(&guild_id.to_be_bytes());
+            mac.update(term.as_bytes());
+            mac.finalize().into_bytes()[..16].to_vec()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "0123456789abcdef0123456789abcdef";
+    const KEY_B: &str = "fedcba9876543210fedcba9876543210";
+    const INDEX_SECRET: &str = "index-secret";
+    const GUILD_ID: u64 = 286_203_598_162_952_193;
+    const HOST_ID: u64 = 1_234_567;
+    const TIMESTAMP: u64 = 1_640_995_200;
+
+    fn keyring(primary_id: u8, primary_key: &str) -> Keyring {
+        Keyring::new(primary_id, primary_key, INDEX_SECRET).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let keyring = keyring(1, KEY_A);
+        let ciphertext = encrypt_bytes(b"Training at the docks", &keyring, GUILD_ID).unwrap();
+
+        assert_eq!(ciphertext[0], CIPHERTEXT_VERSION);
+        assert_eq!(ciphertext[1], 1);
+        assert!(is_current_ciphertext(&ciphertext, &keyring));
+        let plaintext = decrypt_bytes(&ciphertext, &keyring, GUILD_ID, HOST_ID, TIMESTAMP).unwrap();
+        assert_eq!(plaintext, b"Training at the docks");
+    }
+
+    #[test]
+    fn nonces_are_random() {
+        let keyring = keyring(1, KEY_A);
+        let first = encrypt_bytes(b"notes", &keyring, GUILD_ID).unwrap();
+        let second = encrypt_bytes(b"notes", &keyring, GUILD_ID).unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn retired_key_round_trip() {
+        let old = keyring(1, KEY_A);
+        let ciphertext = encrypt_bytes(b"notes", &old, GUILD_ID).unwrap();
+
+        let mut rotated = keyring(2, KEY_B);
+        rotated.add_key(1, KEY_A).unwrap();
+        assert!(!is_current_ciphertext(&ciphertext, &rotated));
+        let plaintext = decrypt_bytes(&ciphertext, &rotated, GUILD_ID, HOST_ID, TIMESTAMP).unwrap();
+        assert_eq!(plaintext, b"notes");
+    }
+
+    #[test]
+    fn legacy_fallback() {
+        let keyring = keyring(LEGACY_KEY_ID, KEY_A);
+        let nonce = legacy_nonce(GUILD_ID, HOST_ID, TIMESTAMP);
+        let ciphertext = keyring
+            .get(LEGACY_KEY_ID)
+            .unwrap()
+            .encrypt(GenericArray::from_slice(&nonce), b"legacy notes".as_ref())
+            .unwrap();
+
+        let plaintext = decrypt_bytes(&ciphertext, &keyring, GUILD_ID, HOST_ID, TIMESTAMP).unwrap();
+        assert_eq!(plaintext, b"legacy notes");
+        assert!(decrypt_bytes(&ciphertext, &keyring, GUILD_ID, HOST_ID, TIMESTAMP + 1).is_err());
+    }
+
+    #[test]
+    fn wrong_guild_is_rejected() {
+        let keyring = keyring(1, KEY_A);
+        let ciphertext = encrypt_bytes(b"notes", &keyring, GUILD_ID).unwrap();
+
+        let result = decrypt_bytes(&ciphertext, &keyring, GUILD_ID + 1, HOST_ID, TIMESTAMP);
+        assert!(matches!(result, Err(CryptoError::Decryption)));
+    }
+
+    #[test]
+    fn unknown_key_is_rejected() {
+        let old = keyring(1, KEY_A);
+        let ciphertext = encrypt_bytes(b"notes", &old, GUILD_ID).unwrap();
+
+        let rotated = keyring(2, KEY_B);
+        let result = decrypt_bytes(&ciphertext, &rotated, GUILD_ID, HOST_ID, TIMESTAMP);
+        assert!(matches!(result, Err(CryptoError::UnknownKey(1))));
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        assert!(matches!(
+            Keyring::new(1, "short", INDEX_SECRET),
+            Err(CryptoError::InvalidKey(1))
+        ));
+        assert!(matches!(
+            Keyring::new(1, KEY_A, ""),
+            Err(CryptoError::InvalidIndexKey)
+        ));
+        assert!(matches!(
+            keyring(1, KEY_A).add_key(1, KEY_B),
+            Err(CryptoError::DuplicateKey(1))
+        ));
+    }
+
+    #[test]
+    fn index_key_survives_rotation() {
+        let old = keyring(1, KEY_A);
+        let rotated = keyring(2, KEY_B);
+
+        assert_eq!(old.index_key(), rotated.index_key());
+        assert_eq!(
+            blind_index("Training", old.index_key(), GUILD_ID),
+            blind_index("training", rotated.index_key(), GUILD_ID)
+        );
+        assert_ne!(
+            blind_index("training", old.index_key(), GUILD_ID),
+            blind_index("training", old.index_key(), GUILD_ID + 1)
+        );
+    }
 }
@@ -3,7 +3,8 @@ use rowifi_models::{
     discord::application::interaction::application_command::{
         CommandDataOption, CommandOptionValue,
     },
-    guild::BlacklistActionType,
+    events::{EventFieldKind, QuotaKind},
+    guild::{BlacklistActionType, InvalidAccountAction},
     id::{ChannelId, RoleId, UserId},
 };
 use std::{num::ParseIntError, str::FromStr};
@@ -316,7 +317,9 @@ impl FromArg for BlacklistActionType {
     fn from_arg(arg: &str) -> Result<Self, Self::Error> {
         match BlacklistActionType::from_str(arg) {
             Ok(a) => Ok(a),
-            Err(_) => Err(ParseError("one of `None` `Kick` `Ban`")),
+            Err(_) => Err(ParseError(
+                "one of `None` `Kick` `Ban` `Quarantine` `Timeout` `DM-Kick`",
+            )),
         }
     }
 
@@ -331,6 +334,69 @@ impl FromArg for BlacklistActionType {
     }
 }
 
+impl FromArg for QuotaKind {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match QuotaKind::from_str(arg) {
+            Ok(k) => Ok(k),
+            Err(_) => Err(ParseError("one of `Host` `Attend`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.clone(),
+            CommandOptionValue::Integer(value) => value.to_string(),
+            _ => unreachable!("QuotaKind unreached"),
+        };
+
+        Self::from_arg(&arg)
+    }
+}
+
+impl FromArg for EventFieldKind {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match EventFieldKind::from_str(arg) {
+            Ok(k) => Ok(k),
+            Err(_) => Err(ParseError("one of `Text` `Duration` `Choice`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.clone(),
+            CommandOptionValue::Integer(value) => value.to_string(),
+            _ => unreachable!("EventFieldKind unreached"),
+        };
+
+        Self::from_arg(&arg)
+    }
+}
+
+impl FromArg for InvalidAccountAction {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match InvalidAccountAction::from_str(arg) {
+            Ok(a) => Ok(a),
+            Err(_) => Err(ParseError("one of `None` `Notify` `Remove-Roles` `Unlink`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.clone(),
+            CommandOptionValue::Integer(value) => value.to_string(),
+            _ => unreachable!("InvalidAccountAction unreached"),
+        };
+
+        Self::from_arg(&arg)
+    }
+}
+
 impl From<ParseIntError> for ParseError {
     fn from(_err: ParseIntError) -> Self {
         ParseError("a number")
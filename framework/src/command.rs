@@ -310,6 +310,16 @@ async fn handle_error(err: &RoError, ctx: CommandContext, master_name: &str) {
                             .unwrap();
                         let _ = ctx.respond().embeds(&[embed]).unwrap().exec().await;
                     }
+                    CommandError::Attachment(reason) => {
+                        let embed = EmbedBuilder::new()
+                            .default_data()
+                            .title("Command Failure")
+                            .color(Color::Red as u32)
+                            .description(reason)
+                            .build()
+                            .unwrap();
+                        let _ = ctx.respond().embeds(&[embed]).unwrap().exec().await;
+                    }
                     CommandError::Message(_) => todo!(),
                     CommandError::Timeout => {
                         let embed = EmbedBuilder::new()
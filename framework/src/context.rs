@@ -53,6 +53,14 @@ pub struct BotContextRef {
     pub log_channels: DashMap<GuildId, ChannelId>,
     /// The array containing the message ids wit active components
     pub ignore_message_components: DashSet<Id<MessageMarker>>,
+    /// The set containing all servers that have the `Update On Role Change` setting enabled
+    pub role_update_guilds: DashSet<GuildId>,
+    /// The map containing the roles the bot last set on members of servers in `role_update_guilds`.
+    /// This is used to ignore the member updates caused by the bot itself
+    pub bot_role_updates: DashMap<(GuildId, UserId), Vec<RoleId>>,
+    /// The lowest rank a member must hold in a group to register it for analytics. The owner of a
+    /// group always holds rank 255
+    pub analytics_min_rank: u8,
 
     // Twilight Components
     /// The module used to make requests to discord
@@ -123,6 +131,7 @@ impl BotContext {
         roblox: Roblox,
         stats: Arc<BotStats>,
         webhooks: HashMap<&'static str, &str>,
+        analytics_min_rank: u8,
         cluster_id: u64,
         total_shards: u64,
         shards_per_cluster: u64,
@@ -148,6 +157,9 @@ impl BotContext {
             nickname_bypass_roles: DashMap::new(),
             log_channels: DashMap::new(),
             ignore_message_components: DashSet::new(),
+            role_update_guilds: DashSet::new(),
+            bot_role_updates: DashMap::new(),
+            analytics_min_rank,
             http,
             cache,
             cluster,
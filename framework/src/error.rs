@@ -1,5 +1,5 @@
 use roblox::error::Error as RobloxError;
-use rowifi_database::error::{DatabaseError, PostgresError};
+use rowifi_database::error::{CryptoError, DatabaseError, PostgresError};
 use std::{
     error::Error as StdError,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
@@ -69,6 +69,7 @@ pub enum ErrorKind {
 #[derive(Debug)]
 pub enum CommandError {
     Argument(ArgumentError),
+    Attachment(String),
     Cancelled,
     Message(MessageError),
     Timeout,
@@ -79,6 +80,7 @@ impl Display for CommandError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
             Self::Argument(arg_err) => write!(f, "argument error: {:?}", arg_err),
+            Self::Attachment(reason) => write!(f, "attachment error: {}", reason),
             Self::Cancelled => write!(f, "command cancelled."),
             Self::Message(msg_err) => write!(f, "message error: {}", msg_err),
             Self::Timeout => write!(f, "command timed out."),
@@ -139,6 +141,12 @@ impl From<PostgresError> for RoError {
     }
 }
 
+impl From<CryptoError> for RoError {
+    fn from(err: CryptoError) -> Self {
+        DatabaseError::from(err).into()
+    }
+}
+
 impl From<DatabaseError> for RoError {
     fn from(err: DatabaseError) -> Self {
         Self {
@@ -1,4 +1,9 @@
-use rowifi_models::{discord::datetime::Timestamp, id::RoleId};
+use rowifi_models::{
+    blacklist::{Blacklist, BlacklistData},
+    discord::datetime::Timestamp,
+    guild::BlacklistActionType,
+    id::RoleId,
+};
 use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder};
 
 use crate::utils::Color;
@@ -8,6 +13,8 @@ pub trait EmbedExtensions {
     fn default_data(self) -> Self;
     #[must_use]
     fn update_log(self, added_roles: &[RoleId], removed_roles: &[RoleId], disc_nick: &str) -> Self;
+    #[must_use]
+    fn blacklist_log(self, blacklist: &Blacklist, action: BlacklistActionType) -> Self;
 }
 
 impl EmbedExtensions for EmbedBuilder {
@@ -37,4 +44,25 @@ impl EmbedExtensions for EmbedBuilder {
             .field(EmbedFieldBuilder::new("Added Roles", added_str))
             .field(EmbedFieldBuilder::new("Removed Roles", removed_str))
     }
+
+    fn blacklist_log(self, blacklist: &Blacklist, action: BlacklistActionType) -> Self {
+        let target = match &blacklist.data {
+            BlacklistData::User(user) => format!("User Id: {}", user),
+            BlacklistData::Group(group) => format!("Group Id: {}", group),
+            BlacklistData::Custom(code) => format!("Code: {}", code),
+            BlacklistData::Discord(user) => format!("Discord User: <@{}>", user),
+        };
+        let desc = format!(
+            "Type: {}\n{}\nReason: {}",
+            blacklist.kind(),
+            target,
+            blacklist.reason
+        );
+
+        self.field(EmbedFieldBuilder::new(
+            format!("Blacklist Id: {}", blacklist.blacklist_id),
+            desc,
+        ))
+        .field(EmbedFieldBuilder::new("Action", action.to_string()))
+    }
 }
@@ -396,7 +396,7 @@ fn run_checks(bot: &BotContext, cmd: &Command, guild_id: Option<GuildId>, author
     false
 }
 
-fn get_perm_level(bot: &BotContext, guild: &CachedGuild, member: &CachedMember) -> RoLevel {
+pub fn get_perm_level(bot: &BotContext, guild: &CachedGuild, member: &CachedMember) -> RoLevel {
     if bot.owners.contains(&UserId(member.user.id)) {
         return RoLevel::Creator;
     }
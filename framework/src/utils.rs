@@ -1,9 +1,11 @@
 use crate::{
     context::CommandContext,
-    error::{CommandError, RoError},
-    extensions::StandbyExtensions,
+    error::{CommandError, ErrorKind, RoError},
+    extensions::{EmbedExtensions, StandbyExtensions},
 };
 
+use hyper::{body, Body, Client as HyperClient, Uri};
+
 use rowifi_models::{
     bind::Template,
     discord::{
@@ -23,6 +25,7 @@ use rowifi_models::{
 };
 use std::{cmp::min, num::ParseIntError, str::FromStr, time::Duration};
 use tokio_stream::StreamExt;
+use twilight_embed_builder::EmbedBuilder;
 
 pub enum Color {
     Red = 0x00E7_4C3C,
@@ -387,6 +390,163 @@ pub async fn await_reply(question: &str, ctx: &CommandContext) -> Result<String,
     Err(CommandError::Timeout.into())
 }
 
+/// The largest attachment we are willing to download for an `await_file_reply`
+const MAX_ATTACHMENT_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Wait for the author to reply with either an attachment or the raw contents of one.
+/// The attachment takes precedence if both are present in the reply
+pub async fn await_file_reply(question: &str, ctx: &CommandContext) -> Result<Vec<u8>, RoError> {
+    let message = ctx
+        .respond()
+        .content(question)?
+        .components(&[Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some("reply-cancel".into()),
+                disabled: false,
+                emoji: None,
+                label: Some("Cancel".into()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        })])?
+        .exec()
+        .await?
+        .model()
+        .await?;
+    let message_id = message.id;
+    let author_id = ctx.author.id;
+    let application_id = ctx.bot.application_id;
+
+    let stream = ctx
+        .bot
+        .standby
+        .wait_for_event_stream(move |event: &Event| {
+            if let Event::InteractionCreate(interaction) = &event {
+                if let Interaction::MessageComponent(message_component) = &interaction.0 {
+                    if message_component.message.id == message_id {
+                        return true;
+                    }
+                }
+            } else if let Event::MessageCreate(msg) = &event {
+                if msg.author.id == author_id
+                    && (!msg.content.is_empty() || !msg.attachments.is_empty())
+                {
+                    return true;
+                }
+            }
+            false
+        })
+        .timeout(Duration::from_secs(300));
+    tokio::pin!(stream);
+
+    ctx.bot.ignore_message_components.insert(message_id);
+    while let Some(Ok(event)) = stream.next().await {
+        if let Event::InteractionCreate(interaction) = &event {
+            if let Interaction::MessageComponent(message_component) = &interaction.0 {
+                let component_interaction_author = message_component.author_id().unwrap();
+                if component_interaction_author == author_id
+                    && message_component.data.custom_id == "reply-cancel"
+                {
+                    ctx.bot
+                        .http
+                        .interaction(application_id)
+                        .interaction_callback(
+                            message_component.id,
+                            &message_component.token,
+                            &InteractionResponse::UpdateMessage(CallbackData {
+                                allowed_mentions: None,
+                                content: None,
+                                components: Some(Vec::new()),
+                                embeds: None,
+                                flags: None,
+                                tts: None,
+                            }),
+                        )
+                        .exec()
+                        .await?;
+                    ctx.bot
+                        .http
+                        .interaction(application_id)
+                        .create_followup_message(&message_component.token)
+                        .content("Command has been cancelled")
+                        .unwrap()
+                        .exec()
+                        .await?;
+                    ctx.bot.ignore_message_components.remove(&message_id);
+                    return Err(CommandError::Cancelled.into());
+                }
+                let _ = ctx
+                    .bot
+                    .http
+                    .interaction(application_id)
+                    .interaction_callback(
+                        message_component.id,
+                        &message_component.token,
+                        &InteractionResponse::DeferredUpdateMessage,
+                    )
+                    .exec()
+                    .await;
+                let _ = ctx
+                    .bot
+                    .http
+                    .interaction(application_id)
+                    .create_followup_message(&message_component.token)
+                    .ephemeral(true)
+                    .content("This component is only interactable by the original command invoker")
+                    .unwrap()
+                    .exec()
+                    .await;
+            }
+        } else if let Event::MessageCreate(msg) = &event {
+            ctx.bot.ignore_message_components.remove(&message_id);
+            if let Some(attachment) = msg.attachments.first() {
+                if attachment.size > MAX_ATTACHMENT_SIZE {
+                    return Err(CommandError::Attachment(format!(
+                        "`{}` is larger than the 2 MB limit",
+                        attachment.filename
+                    ))
+                    .into());
+                }
+                return download_attachment(&attachment.url).await;
+            }
+            return Ok(msg.content.clone().into_bytes());
+        }
+    }
+
+    ctx.bot.ignore_message_components.remove(&message_id);
+    Err(CommandError::Timeout.into())
+}
+
+async fn download_attachment(url: &str) -> Result<Vec<u8>, RoError> {
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_only()
+        .enable_http1()
+        .build();
+    let client = HyperClient::builder().build::<_, Body>(connector);
+    let uri = url
+        .parse::<Uri>()
+        .map_err(|_| CommandError::Attachment("The attachment url is invalid".into()))?;
+    let res = client.get(uri).await.map_err(|source| RoError {
+        source: Some(Box::new(source)),
+        kind: ErrorKind::Discord,
+    })?;
+    if !res.status().is_success() {
+        return Err(CommandError::Attachment(format!(
+            "Discord responded with {} while downloading the attachment",
+            res.status()
+        ))
+        .into());
+    }
+    let bytes = body::to_bytes(res.into_body())
+        .await
+        .map_err(|source| RoError {
+            source: Some(Box::new(source)),
+            kind: ErrorKind::Discord,
+        })?;
+    Ok(bytes.to_vec())
+}
+
 pub async fn paginate_embed(
     ctx: &CommandContext,
     pages: Vec<Embed>,
@@ -529,6 +689,33 @@ pub async fn paginate_embed(
     Ok(())
 }
 
+/// Respond with an embed explaining why the command failed
+pub async fn respond_failure(
+    ctx: &CommandContext,
+    title: &str,
+    description: &str,
+) -> Result<(), RoError> {
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::Red as u32)
+        .title(title)
+        .description(description)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
+
+/// Respond that the command belongs to a module only available in Beta Tier servers
+pub async fn beta_only(ctx: &CommandContext) -> Result<(), RoError> {
+    respond_failure(
+        ctx,
+        "Command Failed",
+        "This module may only be used in Beta Tier Servers",
+    )
+    .await
+}
+
 pub fn parse_username(mention: impl AsRef<str>) -> Option<UserId> {
     let mention = mention.as_ref();
 
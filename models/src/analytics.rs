@@ -2,7 +2,15 @@ use chrono::{DateTime, Utc};
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
 
-use crate::FromRow;
+use crate::{
+    id::{ChannelId, GuildId, UserId},
+    FromRow,
+};
+
+/// The days raw snapshots are kept before they are rolled up into hourly snapshots
+pub const RAW_RETENTION_DAYS: i64 = 7;
+/// The days hourly snapshots are kept before they are rolled up into daily snapshots
+pub const HOURLY_RETENTION_DAYS: i64 = 90;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Group {
@@ -20,6 +28,171 @@ pub struct Role {
     pub member_count: i64,
 }
 
+impl Group {
+    /// The member count of a rank of the group, or of the whole group if no rank is given
+    #[must_use]
+    pub fn member_count_of(&self, rank_id: Option<i64>) -> i64 {
+        match rank_id {
+            Some(rank_id) => self
+                .roles
+                .iter()
+                .find(|r| r.rank == rank_id)
+                .map(|r| r.member_count)
+                .unwrap_or_default(),
+            None => self.member_count,
+        }
+    }
+}
+
+/// The link a server proved to a group when registering it. The member who registered the group
+/// must keep a rank in it for the registration to stay
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AnalyticsRegistration {
+    pub guild_id: GuildId,
+    pub group_id: i64,
+    pub discord_id: UserId,
+    pub roblox_id: i64,
+    pub registered_at: DateTime<Utc>,
+}
+
+/// An alert posted when the member count of a group or one of its ranks changes by more than a
+/// threshold within a window
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AnalyticsAlert {
+    pub alert_id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub group_id: i64,
+    /// The rank whose member count is watched. The whole group is watched if this is `None`
+    pub rank_id: Option<i64>,
+    pub threshold: i64,
+    /// Whether the threshold is a percentage of the member count at the start of the window
+    pub percentage: bool,
+    pub window_hours: i32,
+    pub last_triggered: Option<DateTime<Utc>>,
+}
+
+impl AnalyticsAlert {
+    /// Whether a change from `before` to `after` members crosses the threshold
+    #[must_use]
+    pub fn is_triggered(&self, before: i64, after: i64) -> bool {
+        let change = (after - before).abs();
+        if self.percentage {
+            before > 0 && change * 100 >= self.threshold * before
+        } else {
+            change >= self.threshold
+        }
+    }
+
+    /// The group or rank the alert watches
+    #[must_use]
+    pub fn target(&self) -> String {
+        match self.rank_id {
+            Some(rank_id) => format!("Rank {} of Group {}", rank_id, self.group_id),
+            None => format!("Group {}", self.group_id),
+        }
+    }
+
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let target = self.target();
+        let threshold = if self.percentage {
+            format!("{}%", self.threshold)
+        } else {
+            format!("{} members", self.threshold)
+        };
+        format!(
+            "{} changes by {} within {} hours in <#{}>",
+            target, threshold, self.window_hours, self.channel_id
+        )
+    }
+}
+
+/// The resolution group analytics are stored and charted at. Each resolution has its own table
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Resolution {
+    Raw,
+    Hourly,
+    Daily,
+}
+
+impl Resolution {
+    /// The resolution a window of this many days is charted at
+    #[must_use]
+    pub fn for_window(days: i64) -> Self {
+        if days <= RAW_RETENTION_DAYS {
+            Self::Raw
+        } else if days <= HOURLY_RETENTION_DAYS {
+            Self::Hourly
+        } else {
+            Self::Daily
+        }
+    }
+
+    #[must_use]
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Raw => "group_analytics",
+            Self::Hourly => "group_analytics_hourly",
+            Self::Daily => "group_analytics_daily",
+        }
+    }
+
+    /// The `date_trunc` unit snapshots are bucketed by
+    #[must_use]
+    pub fn unit(self) -> Option<&'static str> {
+        match self {
+            Self::Raw => None,
+            Self::Hourly => Some("hour"),
+            Self::Daily => Some("day"),
+        }
+    }
+}
+
+impl FromRow for AnalyticsAlert {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let alert_id = row.try_get("alert_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let channel_id = row.try_get("channel_id")?;
+        let group_id = row.try_get("group_id")?;
+        let rank_id = row.try_get("rank_id")?;
+        let threshold = row.try_get("threshold")?;
+        let percentage = row.try_get("percentage")?;
+        let window_hours = row.try_get("window_hours")?;
+        let last_triggered = row.try_get("last_triggered")?;
+
+        Ok(Self {
+            alert_id,
+            guild_id,
+            channel_id,
+            group_id,
+            rank_id,
+            threshold,
+            percentage,
+            window_hours,
+            last_triggered,
+        })
+    }
+}
+
+impl FromRow for AnalyticsRegistration {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let guild_id = row.try_get("guild_id")?;
+        let group_id = row.try_get("group_id")?;
+        let discord_id = row.try_get("discord_id")?;
+        let roblox_id = row.try_get("roblox_id")?;
+        let registered_at = row.try_get("registered_at")?;
+
+        Ok(Self {
+            guild_id,
+            group_id,
+            discord_id,
+            roblox_id,
+            registered_at,
+        })
+    }
+}
+
 impl FromRow for Group {
     fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
         let group_id = row.try_get("group_id")?;
@@ -1,12 +1,14 @@
 mod asset;
 mod custom;
 mod group;
+mod premium;
 mod rank;
 mod template;
 
 pub use asset::{AssetType, Assetbind, AssetbindBackup};
 pub use custom::{Custombind, CustombindBackup};
 pub use group::{Groupbind, GroupbindBackup};
+pub use premium::{Premiumbind, PremiumbindBackup};
 pub use rank::{Rankbind, RankbindBackup};
 pub use template::Template;
 
@@ -23,6 +25,7 @@ pub enum Bind {
     Group(Groupbind),
     Custom(Custombind),
     Asset(Assetbind),
+    Premium(Premiumbind),
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -32,6 +35,7 @@ pub enum BindBackup {
     Group(GroupbindBackup),
     Custom(CustombindBackup),
     Asset(AssetbindBackup),
+    Premium(PremiumbindBackup),
 }
 
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -41,6 +45,7 @@ pub enum BindType {
     Group = 1,
     Custom = 2,
     Asset = 3,
+    Premium = 4,
 }
 
 impl Bind {
@@ -51,6 +56,7 @@ impl Bind {
             Bind::Group(g) => g.priority,
             Bind::Custom(c) => c.priority,
             Bind::Asset(a) => a.priority,
+            Bind::Premium(p) => p.priority,
         }
     }
 
@@ -66,6 +72,7 @@ impl Bind {
             Bind::Group(g) => g.template.nickname(roblox_user, user, discord_username),
             Bind::Custom(c) => c.template.nickname(roblox_user, user, discord_username),
             Bind::Asset(a) => a.template.nickname(roblox_user, user, discord_username),
+            Bind::Premium(p) => p.template.nickname(roblox_user, user, discord_username),
         }
     }
 
@@ -76,6 +83,7 @@ impl Bind {
             Bind::Group(g) => &g.discord_roles,
             Bind::Custom(c) => &c.discord_roles,
             Bind::Asset(a) => &a.discord_roles,
+            Bind::Premium(p) => &p.discord_roles,
         }
     }
 
@@ -86,6 +94,7 @@ impl Bind {
             Self::Group(_) => BindType::Group,
             Self::Custom(_) => BindType::Custom,
             Self::Asset(_) => BindType::Asset,
+            Self::Premium(_) => BindType::Premium,
         }
     }
 }
@@ -98,6 +107,7 @@ impl BindBackup {
             Self::Group(_) => BindType::Group,
             Self::Custom(_) => BindType::Custom,
             Self::Asset(_) => BindType::Asset,
+            Self::Premium(_) => BindType::Premium,
         }
     }
 
@@ -108,6 +118,7 @@ impl BindBackup {
             Self::Group(g) => &g.discord_roles,
             Self::Custom(c) => &c.discord_roles,
             Self::Asset(a) => &a.discord_roles,
+            Self::Premium(p) => &p.discord_roles,
         }
     }
 }
@@ -120,6 +131,7 @@ impl FromRow for Bind {
             BindType::Group => Ok(Bind::Group(Groupbind::from_row(row)?)),
             BindType::Custom => Ok(Bind::Custom(Custombind::from_row(row)?)),
             BindType::Asset => Ok(Bind::Asset(Assetbind::from_row(row)?)),
+            BindType::Premium => Ok(Bind::Premium(Premiumbind::from_row(row)?)),
         }
     }
 }
@@ -151,6 +163,7 @@ impl<'a> FromSql<'a> for BindType {
             1 => Ok(BindType::Group),
             2 => Ok(BindType::Custom),
             3 => Ok(BindType::Asset),
+            4 => Ok(BindType::Premium),
             _ => unreachable!(),
         }
     }
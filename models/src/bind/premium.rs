@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::Template;
+
+use crate::{
+    id::{BindId, RoleId},
+    FromRow,
+};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Premiumbind {
+    /// The global id of the bind
+    pub bind_id: BindId,
+    /// The discord roles bound to a Roblox Premium membership
+    pub discord_roles: Vec<RoleId>,
+    /// The number that decides whether this bind is chosen for the nickname
+    pub priority: i32,
+    /// The format of the nickname if this bind is chosen
+    pub template: Template,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PremiumbindBackup {
+    pub discord_roles: Vec<String>,
+    pub priority: i32,
+    pub template: Template,
+}
+
+impl FromRow for Premiumbind {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let bind_id = row.try_get("bind_id")?;
+        let discord_roles = row.try_get("discord_roles")?;
+        let priority = row.try_get("priority")?;
+        let template = row.try_get("template")?;
+
+        Ok(Self {
+            bind_id,
+            discord_roles,
+            priority,
+            template,
+        })
+    }
+}
@@ -84,6 +84,15 @@ impl Template {
         }
         false
     }
+
+    /// Fill in the template for a member that has not linked a Roblox account. Only the
+    /// discord slugs are replaced
+    #[must_use]
+    pub fn unverified_nickname(&self, discord_id: &str, discord_username: &str) -> String {
+        self.0
+            .replace("{discord-id}", discord_id)
+            .replace("{discord-name}", discord_username)
+    }
 }
 
 impl Default for Template {
@@ -1,16 +1,30 @@
 use bytes::BytesMut;
+use chrono::{DateTime, Utc};
 use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-use crate::rolang::{RoCommand, RoCommandUser};
+use crate::{
+    guild::BlacklistActionType,
+    id::{GuildId, UserId},
+    rolang::{RoCommand, RoCommandUser},
+    FromRow,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Blacklist {
     pub blacklist_id: i64,
     pub reason: String,
     pub data: BlacklistData,
+    /// The time after which the blacklist is lifted. Permanent if this is `None`
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The discord user who created the blacklist
+    pub created_by: Option<UserId>,
+    /// The action taken on a match. Falls back to the guild's blacklist action if this is `None`
+    pub action: Option<BlacklistActionType>,
+    /// The message sent to members before they are kicked by the `DmKick` action
+    pub message: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -18,6 +32,46 @@ pub enum BlacklistData {
     User(i64),
     Group(i64),
     Custom(RoCommand),
+    Discord(UserId),
+}
+
+/// The blacklists of a guild published under a name for other guilds to subscribe to
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SharedBlacklist {
+    pub list_id: i64,
+    /// The guild whose blacklists make up the list
+    pub guild_id: GuildId,
+    pub name: String,
+}
+
+/// A blacklist from a shared list that a guild is subscribed to
+#[derive(Clone, Debug)]
+pub struct SubscribedBlacklist {
+    /// The guild whose blacklists make up the shared list
+    pub guild_id: GuildId,
+    /// The name of the shared list the blacklist belongs to
+    pub list_name: String,
+    pub blacklist: Blacklist,
+}
+
+impl SubscribedBlacklist {
+    /// The blacklist as it applies to a subscribing guild. The list's name is added to the reason
+    /// while the owner's expiry, action and message are kept
+    #[must_use]
+    pub fn to_match(&self) -> Blacklist {
+        Blacklist {
+            blacklist_id: self.blacklist.blacklist_id,
+            reason: format!(
+                "{} (Shared List: {})",
+                self.blacklist.reason, self.list_name
+            ),
+            data: self.blacklist.data.clone(),
+            expires_at: self.blacklist.expires_at,
+            created_by: self.blacklist.created_by,
+            action: self.blacklist.action,
+            message: self.blacklist.message.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize_repr, Eq, Ord, PartialEq, PartialOrd, Serialize_repr)]
@@ -27,6 +81,7 @@ pub enum BlacklistType {
     User = 0,
     Group = 1,
     Custom = 2,
+    Discord = 3,
 }
 
 #[derive(Debug, Deserialize, FromSql, Serialize, ToSql)]
@@ -38,6 +93,16 @@ struct BlacklistIntermediary {
     pub user_id: Option<i64>,
     pub group_id: Option<i64>,
     pub code: Option<String>,
+    #[serde(default)]
+    pub discord_id: Option<UserId>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub created_by: Option<UserId>,
+    #[serde(default)]
+    pub action: Option<BlacklistActionType>,
+    #[serde(default)]
+    pub message: Option<String>,
 }
 
 impl Blacklist {
@@ -47,14 +112,45 @@ impl Blacklist {
             BlacklistData::User(_) => BlacklistType::User,
             BlacklistData::Group(_) => BlacklistType::Group,
             BlacklistData::Custom(_) => BlacklistType::Custom,
+            BlacklistData::Discord(_) => BlacklistType::Discord,
         }
     }
 
+    /// Whether the blacklist has expired and is waiting to be swept
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map_or(false, |e| e <= Utc::now())
+    }
+
+    /// Whether this is a discord blacklist targeting the given user. These can be checked without
+    /// looking up the member's Roblox account
+    #[must_use]
+    pub fn matches_discord(&self, user_id: UserId) -> bool {
+        matches!(self.data, BlacklistData::Discord(d) if d == user_id)
+    }
+
+    /// Whether the blacklist means the same thing in other guilds. Custom blacklists checking for
+    /// discord roles refer to roles of the guild they were made in
+    #[must_use]
+    pub fn is_shareable(&self) -> bool {
+        match &self.data {
+            BlacklistData::Custom(cmd) => cmd.roles().is_empty(),
+            _ => true,
+        }
+    }
+
+    /// The action to take on a match, given the guild's blacklist action
+    #[must_use]
+    pub fn effective_action(&self, guild_action: BlacklistActionType) -> BlacklistActionType {
+        self.action.unwrap_or(guild_action)
+    }
+
     pub fn evaluate(&self, user: &RoCommandUser) -> Result<bool, String> {
         match &self.data {
             BlacklistData::User(u) => Ok(user.user.roblox_id == *u),
             BlacklistData::Group(id) => Ok(user.ranks.contains_key(id)),
             BlacklistData::Custom(cmd) => Ok(cmd.evaluate(user)?),
+            BlacklistData::Discord(d) => Ok(user.user.discord_id == *d),
         }
     }
 }
@@ -65,10 +161,11 @@ impl ToSql for Blacklist {
         ty: &Type,
         out: &mut BytesMut,
     ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
-        let (user_id, group_id, code) = match &self.data {
-            BlacklistData::User(u) => (Some(*u), None, None),
-            BlacklistData::Group(g) => (None, Some(*g), None),
-            BlacklistData::Custom(c) => (None, None, Some(c.code.clone())),
+        let (user_id, group_id, code, discord_id) = match &self.data {
+            BlacklistData::User(u) => (Some(*u), None, None, None),
+            BlacklistData::Group(g) => (None, Some(*g), None, None),
+            BlacklistData::Custom(c) => (None, None, Some(c.code.clone()), None),
+            BlacklistData::Discord(d) => (None, None, None, Some(*d)),
         };
         let intermediary = BlacklistIntermediary {
             blacklist_id: self.blacklist_id,
@@ -77,6 +174,11 @@ impl ToSql for Blacklist {
             user_id,
             group_id,
             code,
+            discord_id,
+            expires_at: self.expires_at,
+            created_by: self.created_by,
+            action: self.action,
+            message: self.message.clone(),
         };
         BlacklistIntermediary::to_sql(&intermediary, ty, out)
     }
@@ -100,11 +202,18 @@ impl<'a> FromSql<'a> for Blacklist {
             BlacklistType::Custom => BlacklistData::Custom(
                 RoCommand::new(&blacklist_intermediary.code.unwrap()).unwrap(),
             ),
+            BlacklistType::Discord => {
+                BlacklistData::Discord(blacklist_intermediary.discord_id.unwrap())
+            }
         };
         Ok(Blacklist {
             blacklist_id: blacklist_intermediary.blacklist_id,
             reason: blacklist_intermediary.reason,
             data,
+            expires_at: blacklist_intermediary.expires_at,
+            created_by: blacklist_intermediary.created_by,
+            action: blacklist_intermediary.action,
+            message: blacklist_intermediary.message,
         })
     }
 
@@ -119,6 +228,7 @@ impl Display for BlacklistType {
             BlacklistType::User => f.write_str("User"),
             BlacklistType::Group => f.write_str("Group"),
             BlacklistType::Custom => f.write_str("Custom"),
+            BlacklistType::Discord => f.write_str("Discord"),
         }
     }
 }
@@ -149,6 +259,7 @@ impl<'a> FromSql<'a> for BlacklistType {
             0 => Ok(BlacklistType::User),
             1 => Ok(BlacklistType::Group),
             2 => Ok(BlacklistType::Custom),
+            3 => Ok(BlacklistType::Discord),
             _ => unreachable!(),
         }
     }
@@ -167,21 +278,27 @@ impl<'de> Deserialize<'de> for Blacklist {
             BlacklistType::Custom => {
                 BlacklistData::Custom(RoCommand::new(&intermediary.code.unwrap()).unwrap())
             }
+            BlacklistType::Discord => BlacklistData::Discord(intermediary.discord_id.unwrap()),
         };
         Ok(Blacklist {
             blacklist_id: intermediary.blacklist_id,
             reason: intermediary.reason,
             data,
+            expires_at: intermediary.expires_at,
+            created_by: intermediary.created_by,
+            action: intermediary.action,
+            message: intermediary.message,
         })
     }
 }
 
 impl Serialize for Blacklist {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let (user_id, group_id, code) = match &self.data {
-            BlacklistData::User(u) => (Some(*u), None, None),
-            BlacklistData::Group(g) => (None, Some(*g), None),
-            BlacklistData::Custom(c) => (None, None, Some(c.code.clone())),
+        let (user_id, group_id, code, discord_id) = match &self.data {
+            BlacklistData::User(u) => (Some(*u), None, None, None),
+            BlacklistData::Group(g) => (None, Some(*g), None, None),
+            BlacklistData::Custom(c) => (None, None, Some(c.code.clone()), None),
+            BlacklistData::Discord(d) => (None, None, None, Some(*d)),
         };
         let intermediary = BlacklistIntermediary {
             blacklist_id: self.blacklist_id,
@@ -190,7 +307,40 @@ impl Serialize for Blacklist {
             user_id,
             group_id,
             code,
+            discord_id,
+            expires_at: self.expires_at,
+            created_by: self.created_by,
+            action: self.action,
+            message: self.message.clone(),
         };
         intermediary.serialize(serializer)
     }
 }
+
+impl FromRow for SharedBlacklist {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let list_id = row.try_get("list_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let name = row.try_get("name")?;
+
+        Ok(Self {
+            list_id,
+            guild_id,
+            name,
+        })
+    }
+}
+
+impl FromRow for SubscribedBlacklist {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let guild_id = row.try_get("guild_id")?;
+        let list_name = row.try_get("name")?;
+        let blacklist = row.try_get("blacklist")?;
+
+        Ok(Self {
+            guild_id,
+            list_name,
+            blacklist,
+        })
+    }
+}
@@ -1,8 +1,15 @@
-use chrono::{DateTime, Utc};
+use bytes::BytesMut;
+use chrono::{DateTime, Duration, Utc};
+use postgres_types::{to_sql_checked, FromSql, IsNull, Json, ToSql, Type};
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
 
 use crate::{
-    id::{EventId, EventTypeId, GuildId},
+    id::{ChannelId, EventId, EventTypeId, GuildId, RoleId, UserId},
     FromRow,
 };
 
@@ -16,6 +23,14 @@ pub struct EventLog {
     pub timestamp: DateTime<Utc>,
     pub attendees: Vec<i64>,
     pub notes: Option<Vec<u8>>,
+    /// When the notes were last edited. Notes edited before ciphertexts carried their own nonce
+    /// were encrypted against this time rather than the time of the event
+    pub notes_updated_at: Option<DateTime<Utc>>,
+    /// When the event was voided. Voided events are kept for auditing but excluded from all counts
+    pub voided_at: Option<DateTime<Utc>>,
+    pub void_reason: Option<String>,
+    /// The answers to the custom fields of the event type
+    pub fields: Vec<EventFieldValue>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -25,6 +40,40 @@ pub struct EventType {
     pub guild_id: GuildId,
     pub name: String,
     pub disabled: bool,
+    /// The points awarded to the host of an event of this type
+    pub host_points: i32,
+    /// The points awarded to each attendee of an event of this type
+    pub attend_points: i32,
+    /// The roles allowed to log events of this type. Any trainer may log them if this is empty
+    pub host_roles: Vec<RoleId>,
+    /// The extra fields asked for when logging an event of this type
+    pub fields: Vec<EventFieldSchema>,
+}
+
+/// A custom field of an event type
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventFieldSchema {
+    pub name: String,
+    pub kind: EventFieldKind,
+    /// The allowed answers of a `Choice` field
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EventFieldKind {
+    Text,
+    /// A number of minutes
+    Duration,
+    /// One of a fixed list of answers
+    Choice,
+}
+
+/// The answer to a custom field of an event
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventFieldValue {
+    pub name: String,
+    pub value: String,
 }
 
 impl FromRow for EventLog {
@@ -37,6 +86,10 @@ impl FromRow for EventLog {
         let timestamp = row.try_get("timestamp")?;
         let attendees = row.try_get("attendees")?;
         let notes = row.try_get("notes").ok();
+        let notes_updated_at = row.try_get("notes_updated_at").ok();
+        let voided_at = row.try_get("voided_at").ok();
+        let void_reason = row.try_get("void_reason").ok();
+        let fields = row.try_get::<_, Json<Vec<EventFieldValue>>>("fields")?.0;
 
         Ok(Self {
             event_id,
@@ -47,6 +100,10 @@ impl FromRow for EventLog {
             timestamp,
             attendees,
             notes,
+            notes_updated_at,
+            voided_at,
+            void_reason,
+            fields,
         })
     }
 }
@@ -58,6 +115,10 @@ impl FromRow for EventType {
         let guild_id = row.try_get("guild_id")?;
         let name = row.try_get("name")?;
         let disabled = row.try_get("disabled")?;
+        let host_points = row.try_get("host_points")?;
+        let attend_points = row.try_get("attend_points")?;
+        let host_roles = row.try_get("host_roles")?;
+        let fields = row.try_get::<_, Json<Vec<EventFieldSchema>>>("fields")?.0;
 
         Ok(Self {
             event_type_id,
@@ -65,6 +126,387 @@ impl FromRow for EventType {
             guild_id,
             name,
             disabled,
+            host_points,
+            attend_points,
+            host_roles,
+            fields,
+        })
+    }
+}
+
+/// A single row of an event leaderboard
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLeaderboardEntry {
+    pub roblox_id: i64,
+    pub count: i64,
+}
+
+/// The attendance and hosting totals of a member for one event type
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventMemberSummary {
+    pub event_type: i32,
+    pub attended: i64,
+    pub hosted: i64,
+    pub last_attended: Option<DateTime<Utc>>,
+}
+
+/// A run of consecutive weeks in which a member attended at least one event
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AttendanceStreak {
+    pub length: i64,
+    pub last_week: DateTime<Utc>,
+}
+
+impl FromRow for EventLeaderboardEntry {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let roblox_id = row.try_get("roblox_id")?;
+        let count = row.try_get("count")?;
+
+        Ok(Self { roblox_id, count })
+    }
+}
+
+impl FromRow for EventMemberSummary {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let event_type = row.try_get("event_type")?;
+        let attended = row.try_get("attended")?;
+        let hosted = row.try_get("hosted")?;
+        let last_attended = row.try_get("last_attended")?;
+
+        Ok(Self {
+            event_type,
+            attended,
+            hosted,
+            last_attended,
+        })
+    }
+}
+
+impl FromRow for AttendanceStreak {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let length = row.try_get("length")?;
+        let last_week = row.try_get("last_week")?;
+
+        Ok(Self { length, last_week })
+    }
+}
+
+/// A minimum number of events members of a role must host or attend over a rolling window
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventQuota {
+    pub quota_id: i64,
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+    /// The event type counted towards the quota. All event types are counted if this is `None`
+    pub event_type: Option<i32>,
+    pub kind: QuotaKind,
+    pub required: i32,
+    /// The number of days the rolling window spans
+    pub window_days: i32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize_repr, Eq, PartialEq, Serialize_repr)]
+#[repr(u8)]
+pub enum QuotaKind {
+    Host = 0,
+    Attend = 1,
+}
+
+impl FromRow for EventQuota {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let quota_id = row.try_get("quota_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let role_id = row.try_get("role_id")?;
+        let event_type = row.try_get("event_type")?;
+        let kind = row.try_get("kind")?;
+        let required = row.try_get("required")?;
+        let window_days = row.try_get("window_days")?;
+
+        Ok(Self {
+            quota_id,
+            guild_id,
+            role_id,
+            event_type,
+            kind,
+            required,
+            window_days,
+        })
+    }
+}
+
+impl Display for QuotaKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            QuotaKind::Host => f.write_str("Host"),
+            QuotaKind::Attend => f.write_str("Attend"),
+        }
+    }
+}
+
+impl FromStr for QuotaKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "host" | "hosts" => Ok(QuotaKind::Host),
+            "attend" | "attends" | "attendee" => Ok(QuotaKind::Attend),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for EventFieldKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            EventFieldKind::Text => f.write_str("Text"),
+            EventFieldKind::Duration => f.write_str("Duration"),
+            EventFieldKind::Choice => f.write_str("Choice"),
+        }
+    }
+}
+
+impl FromStr for EventFieldKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(EventFieldKind::Text),
+            "duration" => Ok(EventFieldKind::Duration),
+            "choice" | "enum" => Ok(EventFieldKind::Choice),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ToSql for QuotaKind {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        i32::to_sql(&(*self as i32), ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for QuotaKind {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let kind = i32::from_sql(ty, raw)?;
+        match kind {
+            0 => Ok(QuotaKind::Host),
+            1 => Ok(QuotaKind::Attend),
+            _ => unreachable!(),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as FromSql>::accepts(ty)
+    }
+}
+
+/// An event scheduled ahead of time that members RSVP to and check in to when it starts
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventSession {
+    pub session_id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    /// The id of the message holding the RSVP and check-in buttons
+    pub message_id: i64,
+    pub event_type: i32,
+    pub host_id: UserId,
+    pub host_roblox_id: i64,
+    pub start_time: DateTime<Utc>,
+    /// The number of minutes check-in stays open after the start time
+    pub checkin_minutes: i32,
+    pub status: SessionStatus,
+    pub rsvps: Vec<UserId>,
+    pub checked_in: Vec<UserId>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize_repr, Eq, PartialEq, Serialize_repr)]
+#[repr(u8)]
+pub enum SessionStatus {
+    Scheduled = 0,
+    CheckIn = 1,
+    Closed = 2,
+    Cancelled = 3,
+}
+
+impl EventSession {
+    #[must_use]
+    pub fn checkin_closes_at(&self) -> DateTime<Utc> {
+        self.start_time + Duration::minutes(i64::from(self.checkin_minutes))
+    }
+}
+
+impl FromRow for EventSession {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let session_id = row.try_get("session_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let channel_id = row.try_get("channel_id")?;
+        let message_id = row.try_get("message_id")?;
+        let event_type = row.try_get("event_type")?;
+        let host_id = row.try_get("host_id")?;
+        let host_roblox_id = row.try_get("host_roblox_id")?;
+        let start_time = row.try_get("start_time")?;
+        let checkin_minutes = row.try_get("checkin_minutes")?;
+        let status = row.try_get("status")?;
+        let rsvps = row.try_get("rsvps")?;
+        let checked_in = row.try_get("checked_in")?;
+
+        Ok(Self {
+            session_id,
+            guild_id,
+            channel_id,
+            message_id,
+            event_type,
+            host_id,
+            host_roblox_id,
+            start_time,
+            checkin_minutes,
+            status,
+            rsvps,
+            checked_in,
         })
     }
 }
+
+impl Display for SessionStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SessionStatus::Scheduled => f.write_str("Scheduled"),
+            SessionStatus::CheckIn => f.write_str("Check-In Open"),
+            SessionStatus::Closed => f.write_str("Closed"),
+            SessionStatus::Cancelled => f.write_str("Cancelled"),
+        }
+    }
+}
+
+impl ToSql for SessionStatus {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        i32::to_sql(&(*self as i32), ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for SessionStatus {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let status = i32::from_sql(ty, raw)?;
+        match status {
+            0 => Ok(SessionStatus::Scheduled),
+            1 => Ok(SessionStatus::CheckIn),
+            2 => Ok(SessionStatus::Closed),
+            3 => Ok(SessionStatus::Cancelled),
+            _ => unreachable!(),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as FromSql>::accepts(ty)
+    }
+}
+
+/// A change made to a logged event after it was created
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventEdit {
+    pub edit_id: i64,
+    pub guild_id: GuildId,
+    pub guild_event_id: i64,
+    pub editor_id: UserId,
+    pub timestamp: DateTime<Utc>,
+    /// The human readable description of the change
+    pub change: String,
+}
+
+impl EventLog {
+    /// The time legacy notes of the event were encrypted against
+    #[must_use]
+    pub fn notes_timestamp(&self) -> DateTime<Utc> {
+        self.notes_updated_at.unwrap_or(self.timestamp)
+    }
+}
+
+impl FromRow for EventEdit {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let edit_id = row.try_get("edit_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let guild_event_id = row.try_get("guild_event_id")?;
+        let editor_id = row.try_get("editor_id")?;
+        let timestamp = row.try_get("timestamp")?;
+        let change = row.try_get("change")?;
+
+        Ok(Self {
+            edit_id,
+            guild_id,
+            guild_event_id,
+            editor_id,
+            timestamp,
+            change,
+        })
+    }
+}
+
+/// The number of event points a member of a roblox group needs to be recommended for a rank
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventRankThreshold {
+    pub threshold_id: i64,
+    pub guild_id: GuildId,
+    pub group_id: i64,
+    /// The rank number (1-255) in the group
+    pub rank: i32,
+    pub points: i64,
+}
+
+/// The total event points of a member
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventPoints {
+    pub roblox_id: i64,
+    pub points: i64,
+}
+
+impl FromRow for EventRankThreshold {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let threshold_id = row.try_get("threshold_id")?;
+        let guild_id = row.try_get("guild_id")?;
+        let group_id = row.try_get("group_id")?;
+        let rank = row.try_get("rank")?;
+        let points = row.try_get("points")?;
+
+        Ok(Self {
+            threshold_id,
+            guild_id,
+            group_id,
+            rank,
+            points,
+        })
+    }
+}
+
+impl FromRow for EventPoints {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let roblox_id = row.try_get("roblox_id")?;
+        let points = row.try_get("points")?;
+
+        Ok(Self { roblox_id, points })
+    }
+}
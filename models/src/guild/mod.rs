@@ -1,15 +1,17 @@
 pub mod backup;
 mod types;
+pub mod unverified;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    bind::Template,
     blacklist::Blacklist,
     id::{ChannelId, GuildId, RoleId, UserId},
     serialize_vec_as_string, FromRow,
 };
 
-pub use types::{BlacklistActionType, GuildType};
+pub use types::{BlacklistActionType, GuildType, InvalidAccountAction};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RoGuild {
@@ -43,8 +45,17 @@ pub struct RoGuild {
 
     pub blacklist_action: BlacklistActionType,
 
+    /// The role given to members quarantined by a blacklist
+    pub quarantine_role: Option<RoleId>,
+
+    /// The number of hours members are timed out for by a blacklist
+    pub blacklist_timeout: Option<i32>,
+
     pub update_on_join: bool,
 
+    /// Whether members are updated when their discord roles checked by binds or blacklists change
+    pub update_on_role_change: bool,
+
     pub admin_roles: Vec<RoleId>,
 
     pub trainer_roles: Vec<RoleId>,
@@ -54,6 +65,24 @@ pub struct RoGuild {
     pub nickname_bypass_roles: Vec<RoleId>,
 
     pub log_channel: Option<ChannelId>,
+
+    /// The nickname given to members who join without being verified
+    pub unverified_template: Option<Template>,
+
+    /// The number of hours between the verification reminders sent to unverified members
+    pub verification_reminder: Option<i32>,
+
+    /// The number of hours after which unverified members are kicked
+    pub unverified_kick: Option<i32>,
+
+    /// The action taken on members whose linked Roblox account is banned or deleted
+    pub invalid_account_action: InvalidAccountAction,
+
+    /// The channel event quota compliance reports are posted to
+    pub quota_channel: Option<ChannelId>,
+
+    /// The roles whose members are exempt from event quotas
+    pub quota_exempt_roles: Vec<RoleId>,
 }
 
 impl RoGuild {
@@ -71,12 +100,21 @@ impl RoGuild {
             kind: GuildType::Free,
             premium_owner: None,
             blacklist_action: BlacklistActionType::None,
+            quarantine_role: None,
+            blacklist_timeout: None,
             update_on_join: false,
+            update_on_role_change: false,
             admin_roles: Vec::new(),
             trainer_roles: Vec::new(),
             bypass_roles: Vec::new(),
             nickname_bypass_roles: Vec::new(),
             log_channel: None,
+            unverified_template: None,
+            verification_reminder: None,
+            unverified_kick: None,
+            invalid_account_action: InvalidAccountAction::None,
+            quota_channel: None,
+            quota_exempt_roles: Vec::new(),
         }
     }
 }
@@ -94,12 +132,21 @@ impl FromRow for RoGuild {
         let kind = row.try_get("kind")?;
         let premium_owner = row.try_get("premium_owner").ok();
         let blacklist_action = row.try_get("blacklist_action")?;
+        let quarantine_role = row.try_get("quarantine_role").ok();
+        let blacklist_timeout = row.try_get("blacklist_timeout").ok();
         let update_on_join = row.try_get("update_on_join")?;
+        let update_on_role_change = row.try_get("update_on_role_change")?;
         let admin_roles = row.try_get("admin_roles")?;
         let trainer_roles = row.try_get("trainer_roles")?;
         let bypass_roles = row.try_get("bypass_roles")?;
         let nickname_bypass_roles = row.try_get("nickname_bypass_roles")?;
         let log_channel = row.try_get("log_channel").ok();
+        let unverified_template = row.try_get("unverified_template").ok();
+        let verification_reminder = row.try_get("verification_reminder").ok();
+        let unverified_kick = row.try_get("unverified_kick").ok();
+        let invalid_account_action = row.try_get("invalid_account_action")?;
+        let quota_channel = row.try_get("quota_channel").ok();
+        let quota_exempt_roles = row.try_get("quota_exempt_roles").unwrap_or_default();
 
         Ok(Self {
             guild_id,
@@ -113,12 +160,21 @@ impl FromRow for RoGuild {
             kind,
             premium_owner,
             blacklist_action,
+            quarantine_role,
+            blacklist_timeout,
             update_on_join,
+            update_on_role_change,
             admin_roles,
             trainer_roles,
             bypass_roles,
             nickname_bypass_roles,
             log_channel,
+            unverified_template,
+            verification_reminder,
+            unverified_kick,
+            invalid_account_action,
+            quota_channel,
+            quota_exempt_roles,
         })
     }
 }
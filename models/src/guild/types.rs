@@ -20,6 +20,22 @@ pub enum BlacklistActionType {
     None = 0,
     Kick = 1,
     Ban = 2,
+    /// Strip the bind roles of the member and give them the quarantine role of the guild
+    Quarantine = 3,
+    Timeout = 4,
+    /// Send the blacklist's message to the member before kicking them
+    DmKick = 5,
+}
+
+/// The action taken on members whose linked Roblox account is banned or no longer exists.
+/// `None` disables the periodic re-validation of linked accounts
+#[derive(Clone, Copy, Debug, Deserialize_repr, Eq, Ord, PartialEq, PartialOrd, Serialize_repr)]
+#[repr(u8)]
+pub enum InvalidAccountAction {
+    None = 0,
+    Notify = 1,
+    RemoveRoles = 2,
+    Unlink = 3,
 }
 
 impl Default for GuildType {
@@ -34,6 +50,12 @@ impl Default for BlacklistActionType {
     }
 }
 
+impl Default for InvalidAccountAction {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
 impl Display for GuildType {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
@@ -50,6 +72,9 @@ impl Display for BlacklistActionType {
             BlacklistActionType::None => f.write_str("None"),
             BlacklistActionType::Kick => f.write_str("Kick"),
             BlacklistActionType::Ban => f.write_str("Ban"),
+            BlacklistActionType::Quarantine => f.write_str("Quarantine"),
+            BlacklistActionType::Timeout => f.write_str("Timeout"),
+            BlacklistActionType::DmKick => f.write_str("DM & Kick"),
         }
     }
 }
@@ -62,6 +87,34 @@ impl FromStr for BlacklistActionType {
             "none" => Ok(BlacklistActionType::None),
             "kick" => Ok(BlacklistActionType::Kick),
             "ban" => Ok(BlacklistActionType::Ban),
+            "quarantine" => Ok(BlacklistActionType::Quarantine),
+            "timeout" => Ok(BlacklistActionType::Timeout),
+            "dm-kick" | "dmkick" => Ok(BlacklistActionType::DmKick),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for InvalidAccountAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            InvalidAccountAction::None => f.write_str("None"),
+            InvalidAccountAction::Notify => f.write_str("Notify"),
+            InvalidAccountAction::RemoveRoles => f.write_str("Remove Roles"),
+            InvalidAccountAction::Unlink => f.write_str("Unlink"),
+        }
+    }
+}
+
+impl FromStr for InvalidAccountAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(InvalidAccountAction::None),
+            "notify" => Ok(InvalidAccountAction::Notify),
+            "remove-roles" | "removeroles" => Ok(InvalidAccountAction::RemoveRoles),
+            "unlink" => Ok(InvalidAccountAction::Unlink),
             _ => Err(()),
         }
     }
@@ -128,6 +181,45 @@ impl<'a> FromSql<'a> for BlacklistActionType {
             0 => Ok(BlacklistActionType::None),
             1 => Ok(BlacklistActionType::Kick),
             2 => Ok(BlacklistActionType::Ban),
+            3 => Ok(BlacklistActionType::Quarantine),
+            4 => Ok(BlacklistActionType::Timeout),
+            5 => Ok(BlacklistActionType::DmKick),
+            _ => unreachable!(),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as FromSql>::accepts(ty)
+    }
+}
+
+impl ToSql for InvalidAccountAction {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        i32::to_sql(&(*self as i32), ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for InvalidAccountAction {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let action = i32::from_sql(ty, raw)?;
+        match action {
+            0 => Ok(InvalidAccountAction::None),
+            1 => Ok(InvalidAccountAction::Notify),
+            2 => Ok(InvalidAccountAction::RemoveRoles),
+            3 => Ok(InvalidAccountAction::Unlink),
             _ => unreachable!(),
         }
     }
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    id::{GuildId, UserId},
+    FromRow,
+};
+
+/// A member who joined a guild without being verified and has pending reminders or a kick
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UnverifiedMember {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub joined_at: DateTime<Utc>,
+    /// The time at which the next verification reminder is sent
+    pub next_reminder: Option<DateTime<Utc>>,
+    /// The time at which the member is kicked if still unverified
+    pub kick_at: Option<DateTime<Utc>>,
+}
+
+impl FromRow for UnverifiedMember {
+    fn from_row(row: tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let guild_id = row.try_get("guild_id")?;
+        let user_id = row.try_get("user_id")?;
+        let joined_at = row.try_get("joined_at")?;
+        let next_reminder = row.try_get("next_reminder").ok();
+        let kick_at = row.try_get("kick_at").ok();
+
+        Ok(Self {
+            guild_id,
+            user_id,
+            joined_at,
+            next_reminder,
+            kick_at,
+        })
+    }
+}
@@ -20,4 +20,12 @@ pub struct PartialUser {
     pub name: String,
     #[serde(rename = "displayName")]
     pub display_name: Option<String>,
+    /// The username the user was looked up by. Only set by username lookups, where it may be a
+    /// previous name of the user
+    #[serde(
+        rename = "requestedUsername",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub requested_username: Option<String>,
 }
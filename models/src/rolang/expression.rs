@@ -169,4 +169,23 @@ impl Expression {
         }
         Ok(())
     }
+
+    /// Collect the ids of the roles checked for by any `HasRole` call in the expression
+    pub fn roles(&self, roles: &mut Vec<RoleId>) {
+        match self {
+            Expression::Literal(_) => {}
+            Expression::Unary(_, e) | Expression::Grouping(e) => e.roles(roles),
+            Expression::Binary(left, _, right) => {
+                left.roles(roles);
+                right.roles(roles);
+            }
+            Expression::Function(token, args) => {
+                if token.token_type == TokenType::HasRole {
+                    if let Some(Literal::Number(num)) = args.get(0) {
+                        roles.push(RoleId::new(*num as u64));
+                    }
+                }
+            }
+        }
+    }
 }
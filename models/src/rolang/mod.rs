@@ -48,6 +48,14 @@ impl RoCommand {
         };
         Ok(success)
     }
+
+    /// Get the ids of the roles referenced by the command through `HasRole`
+    #[must_use]
+    pub fn roles(&self) -> Vec<RoleId> {
+        let mut roles = Vec::new();
+        self.expr.roles(&mut roles);
+        roles
+    }
 }
 
 impl Display for RoCommand {
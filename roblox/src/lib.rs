@@ -38,6 +38,8 @@ pub struct Client {
     client: HyperClient<HttpsConnector<HttpConnector>>,
     redis_pool: Pool,
     proxy: Option<String>,
+    /// The url requests are sent to instead of Roblox, such as a local stand-in in tests
+    base_url: Option<String>,
 }
 
 impl Client {
@@ -56,6 +58,17 @@ impl Client {
             client,
             redis_pool,
             proxy,
+            base_url: None,
+        }
+    }
+
+    /// Create an instance of the Roblox Client that sends every request to `base_url`
+    #[must_use]
+    pub fn with_base_url(redis_pool: Pool, base_url: impl Into<String>) -> Self {
+        Self {
+            proxy: None,
+            base_url: Some(base_url.into()),
+            ..Self::new(redis_pool)
         }
     }
 
@@ -66,7 +79,7 @@ impl Client {
         method: Method,
         body: Option<Vec<u8>>,
     ) -> Result<T> {
-        let route = route.to_string();
+        let route = route.url(self.base_url.as_deref());
         let builder = match &self.proxy {
             Some(p) => Request::builder()
                 .uri(format!("{}?url={}", p, urlencoding::encode(&route)))
@@ -135,17 +148,9 @@ impl Client {
 
     /// Get a [`PartialUser`] from the username
     pub async fn get_user_from_username(&self, username: &str) -> Result<Option<PartialUser>> {
-        let route = Route::UsersByUsername;
-        let usernames = vec![username];
-        let json = serde_json::json!({ "usernames": usernames });
-        let body = serde_json::to_vec(&json).map_err(|source| Error {
-            source: Some(Box::new(source)),
-            kind: ErrorKind::BuildingRequest,
-        })?;
         let mut ids = self
-            .request::<VecWrapper<PartialUser>>(route, Method::POST, Some(body))
+            .get_users_from_usernames(&[username])
             .await?
-            .data
             .into_iter();
         match ids.next() {
             Some(u) => Ok(Some(u)),
@@ -153,6 +158,21 @@ impl Client {
         }
     }
 
+    /// Get multiple [`PartialUser`] from their usernames. Usernames that do not belong to any user
+    /// are left out of the result
+    pub async fn get_users_from_usernames(&self, usernames: &[&str]) -> Result<Vec<PartialUser>> {
+        let route = Route::UsersByUsername;
+        let json = serde_json::json!({ "usernames": usernames });
+        let body = serde_json::to_vec(&json).map_err(|source| Error {
+            source: Some(Box::new(source)),
+            kind: ErrorKind::BuildingRequest,
+        })?;
+        let users = self
+            .request::<VecWrapper<PartialUser>>(route, Method::POST, Some(body))
+            .await?;
+        Ok(users.data)
+    }
+
     pub async fn get_user_profile(&self, user_id: UserId) -> Result<User> {
         let route = Route::UserById { user_id: user_id.0 };
         let user = self.request::<User>(route, Method::GET, None).await?;
@@ -174,7 +194,7 @@ impl Client {
                     kind: ErrorKind::Response {
                         body: vec![],
                         status: StatusCode::NOT_FOUND,
-                        route: Route::UsersById.to_string(),
+                        route: Route::UsersById.url(self.base_url.as_deref()),
                     },
                 })?;
             let _: () = conn
@@ -197,7 +217,7 @@ impl Client {
                         kind: ErrorKind::Response {
                             body: vec![],
                             status: StatusCode::NOT_FOUND,
-                            route: Route::UsersById.to_string(),
+                            route: Route::UsersById.url(self.base_url.as_deref()),
                         },
                     })?;
                 let _: () = conn
@@ -208,11 +228,43 @@ impl Client {
         }
     }
 
+    /// Check whether the user holds a Roblox Premium membership
+    pub async fn get_premium(&self, user_id: UserId, bypass_cache: bool) -> Result<bool> {
+        let mut conn = self.redis_pool.get().await?;
+        let key = format!("roblox:p:{}", user_id.0);
+        if !bypass_cache {
+            let bytes: Option<Vec<u8>> = conn.get(&key).await?;
+            if let Some(Ok(premium)) = bytes.map(|b| serde_cbor::from_slice(&b)) {
+                return Ok(premium);
+            }
+        }
+
+        let route = Route::PremiumMembership { user_id: user_id.0 };
+        let premium = self.request::<bool>(route, Method::GET, None).await?;
+        let _: () = conn
+            .set_ex(key, serde_cbor::to_vec(&premium)?, 24 * 3600)
+            .await?;
+        Ok(premium)
+    }
+
     /// Get multiple [`PartialUser`] from their ids
     pub async fn get_users(&self, user_ids: &[UserId]) -> Result<Vec<PartialUser>> {
+        self.fetch_users(user_ids, false).await
+    }
+
+    /// Get multiple [`PartialUser`] from their ids, leaving out the users that are banned
+    pub async fn get_unbanned_users(&self, user_ids: &[UserId]) -> Result<Vec<PartialUser>> {
+        self.fetch_users(user_ids, true).await
+    }
+
+    async fn fetch_users(
+        &self,
+        user_ids: &[UserId],
+        exclude_banned: bool,
+    ) -> Result<Vec<PartialUser>> {
         let mut conn = self.redis_pool.get().await?;
         let route = Route::UsersById;
-        let json = serde_json::json!({ "userIds": user_ids });
+        let json = serde_json::json!({ "userIds": user_ids, "excludeBannedUsers": exclude_banned });
         let body = serde_json::to_vec(&json).map_err(|source| Error {
             source: Some(Box::new(source)),
             kind: ErrorKind::BuildingRequest,
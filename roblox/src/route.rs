@@ -9,6 +9,9 @@ pub enum Route<'a> {
         asset_id: u64,
         asset_type: &'a str,
     },
+    PremiumMembership {
+        user_id: u64,
+    },
     UserById {
         user_id: u64,
     },
@@ -19,31 +22,46 @@ pub enum Route<'a> {
     },
 }
 
-impl Display for Route<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+impl Route<'_> {
+    /// The subdomain of `roblox.com` the route is served from
+    fn host(&self) -> &'static str {
         match self {
-            Route::GroupRoles { group_id } => {
-                write!(f, "https://groups.roblox.com/v1/groups/{}/roles", group_id)
-            }
+            Route::GroupRoles { .. } | Route::UserGroupRoles { .. } => "groups",
+            Route::UserInventoryAsset { .. } => "inventory",
+            Route::PremiumMembership { .. } => "premiumfeatures",
+            Route::UserById { .. } | Route::UsersById | Route::UsersByUsername => "users",
+        }
+    }
+
+    /// The full url of the route. Every route is sent to `base_url` instead of Roblox if one is given
+    pub fn url(&self, base_url: Option<&str>) -> String {
+        match base_url {
+            Some(base_url) => format!("{}{}", base_url.trim_end_matches('/'), self.path()),
+            None => format!("https://{}.roblox.com{}", self.host(), self.path()),
+        }
+    }
+
+    fn path(&self) -> String {
+        match self {
+            Route::GroupRoles { group_id } => format!("/v1/groups/{}/roles", group_id),
             Route::UserInventoryAsset {
                 user_id,
                 asset_id,
                 asset_type,
-            } => write!(
-                f,
-                "https://inventory.roblox.com/v1/users/{}/items/{}/{}",
-                user_id, asset_type, asset_id
-            ),
-            Route::UserById { user_id } => {
-                write!(f, "https://users.roblox.com/v1/users/{}", user_id)
+            } => format!("/v1/users/{}/items/{}/{}", user_id, asset_type, asset_id),
+            Route::PremiumMembership { user_id } => {
+                format!("/v1/users/{}/validate-membership", user_id)
             }
-            Route::UsersById => write!(f, "https://users.roblox.com/v1/users"),
-            Route::UsersByUsername => write!(f, "https://users.roblox.com/v1/usernames/users"),
-            Route::UserGroupRoles { user_id } => write!(
-                f,
-                "https://groups.roblox.com/v2/users/{}/groups/roles",
-                user_id
-            ),
+            Route::UserById { user_id } => format!("/v1/users/{}", user_id),
+            Route::UsersById => "/v1/users".into(),
+            Route::UsersByUsername => "/v1/usernames/users".into(),
+            Route::UserGroupRoles { user_id } => format!("/v2/users/{}/groups/roles", user_id),
         }
     }
 }
+
+impl Display for Route<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.url(None))
+    }
+}
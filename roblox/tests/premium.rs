@@ -0,0 +1,125 @@
+use deadpool_redis::{redis::AsyncCommands, Manager, Pool, Runtime};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use roblox::Client;
+use rowifi_models::roblox::id::UserId;
+use std::{
+    convert::Infallible,
+    env,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Serve the Premium membership endpoint locally, where users with even ids have Premium. Returns
+/// the url of the stand-in and the number of requests it has served
+fn stand_in() -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let served = hits.clone();
+    let make_service = make_service_fn(move |_| {
+        let hits = hits.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                hits.fetch_add(1, Ordering::SeqCst);
+                let user_id = req
+                    .uri()
+                    .path()
+                    .trim_start_matches("/v1/users/")
+                    .trim_end_matches("/validate-membership")
+                    .parse::<u64>()
+                    .expect("Expected the premium membership route");
+                let body = if user_id % 2 == 0 { "true" } else { "false" };
+                async move { Ok::<_, Infallible>(Response::new(Body::from(body))) }
+            }))
+        }
+    });
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    (url, served)
+}
+
+/// Connect to the Redis the client caches in, or `None` when there is none to connect to so that
+/// the tests are skipped rather than failed on machines without Redis
+async fn redis_pool() -> Option<Pool> {
+    let redis_conn = env::var("REDIS_CONN").unwrap_or_else(|_| "redis://127.0.0.1:6379".into());
+    let pool = Pool::builder(Manager::new(redis_conn).ok()?)
+        .max_size(2)
+        .runtime(Runtime::Tokio1)
+        .build()
+        .ok()?;
+    if let Err(err) = pool.get().await {
+        eprintln!("Skipping the test since Redis is not available: {}", err);
+        return None;
+    }
+    Some(pool)
+}
+
+/// Remove the cached Premium status of a user so that the tests leave nothing behind
+async fn clear_cache(pool: &Pool, user_id: UserId) {
+    let _: () = pool
+        .get()
+        .await
+        .unwrap()
+        .del(format!("roblox:p:{}", user_id.0))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn premium_member() {
+    let pool = match redis_pool().await {
+        Some(pool) => pool,
+        None => return,
+    };
+    let (url, hits) = stand_in();
+    let user_id = UserId(900_000_002);
+    let client = Client::with_base_url(pool.clone(), url);
+
+    assert!(client.get_premium(user_id, true).await.unwrap());
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    clear_cache(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn not_premium_member() {
+    let pool = match redis_pool().await {
+        Some(pool) => pool,
+        None => return,
+    };
+    let (url, hits) = stand_in();
+    let user_id = UserId(900_000_003);
+    let client = Client::with_base_url(pool.clone(), url);
+
+    assert!(!client.get_premium(user_id, true).await.unwrap());
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    clear_cache(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn premium_cache_hit() {
+    let pool = match redis_pool().await {
+        Some(pool) => pool,
+        None => return,
+    };
+    let (url, hits) = stand_in();
+    let user_id = UserId(900_000_004);
+    clear_cache(&pool, user_id).await;
+    let client = Client::with_base_url(pool.clone(), url);
+
+    assert!(client.get_premium(user_id, false).await.unwrap());
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // The second lookup is answered from the cache
+    assert!(client.get_premium(user_id, false).await.unwrap());
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // Bypassing the cache always asks the endpoint
+    assert!(client.get_premium(user_id, true).await.unwrap());
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    clear_cache(&pool, user_id).await;
+}
@@ -0,0 +1,261 @@
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    analytics::{AnalyticsAlert, RAW_RETENTION_DAYS},
+    guild::GuildType,
+    id::ChannelId,
+};
+
+use super::{parse_group_rank, view::ViewDuration};
+
+/// The most alerts a server may have
+const MAX_ALERTS: usize = 25;
+
+pub struct AlertThreshold {
+    pub amount: i64,
+    pub percentage: bool,
+}
+
+pub async fn analytics_alerts(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let alerts = ctx
+        .bot
+        .database
+        .query::<AnalyticsAlert>(
+            "SELECT * FROM analytics_alerts WHERE guild_id = $1 ORDER BY alert_id",
+            &[&guild_id],
+        )
+        .await?;
+    let mut desc = alerts
+        .iter()
+        .map(|a| format!("`{}` {}", a.alert_id, a.describe()))
+        .join("\n");
+    if desc.is_empty() {
+        desc = "This server has no analytics alerts".into();
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title("Analytics Alerts")
+        .description(desc)
+        .build()?;
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct AlertNewArguments {
+    #[arg(help = "The channel the alerts are posted to")]
+    pub channel: ChannelId,
+    #[arg(
+        help = "The group to watch. Use `GroupId` for a whole group or `GroupId:RankId` for a rank"
+    )]
+    pub group: String,
+    #[arg(
+        help = "The change that triggers the alert, either a number of members such as `50` or a percentage such as `10%`"
+    )]
+    pub threshold: AlertThreshold,
+    #[arg(help = "The window the change is measured over such as `6h` or `2d`")]
+    pub window: ViewDuration,
+}
+
+pub async fn analytics_alert_new(ctx: CommandContext, args: AlertNewArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let (group_id, rank_id) = match parse_group_rank(&args.group) {
+        Some(g) => g,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Analytics Alert Failed",
+                &format!(
+                    "`{}` is not of the form `GroupId` or `GroupId:RankId`",
+                    args.group
+                ),
+            )
+            .await
+        }
+    };
+    if !guild.registered_groups.contains(&group_id) {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            "You may only set alerts for groups that are registered with this server",
+        )
+        .await;
+    }
+    if !ctx
+        .bot
+        .cache
+        .guild_channels(guild_id)
+        .contains(&args.channel)
+    {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            "This channel cannot be set as the alert channel or does not exist",
+        )
+        .await;
+    }
+    if args.threshold.amount < 1 {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            "The threshold must be at least 1",
+        )
+        .await;
+    }
+    // Alerts are measured against raw snapshots, so the window cannot outlast them
+    let window_hours = args.window.0.num_hours();
+    if !(2..=RAW_RETENTION_DAYS * 24).contains(&window_hours) {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            &format!(
+                "The window must be between 2 hours and {} days",
+                RAW_RETENTION_DAYS
+            ),
+        )
+        .await;
+    }
+
+    let alert_count = ctx
+        .bot
+        .database
+        .query::<AnalyticsAlert>(
+            "SELECT * FROM analytics_alerts WHERE guild_id = $1",
+            &[&guild_id],
+        )
+        .await?
+        .len();
+    if alert_count >= MAX_ALERTS {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            &format!("A server may have at most {} analytics alerts", MAX_ALERTS),
+        )
+        .await;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let window_hours = window_hours as i32;
+    let alert = ctx
+        .bot
+        .database
+        .query_one::<AnalyticsAlert>(
+            "INSERT INTO analytics_alerts(guild_id, channel_id, group_id, rank_id, threshold, percentage, window_hours) VALUES($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+            &[&guild_id, &args.channel, &group_id, &rank_id, &args.threshold.amount, &args.threshold.percentage, &window_hours],
+        )
+        .await?;
+
+    let name = format!("Alert Id: {}", alert.alert_id);
+    let desc = alert.describe();
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Analytics Alert Addition Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Analytics Alert Added")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct AlertDeleteArguments {
+    #[arg(help = "The id of the alert to delete")]
+    pub id: i64,
+}
+
+pub async fn analytics_alert_delete(
+    ctx: CommandContext,
+    args: AlertDeleteArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let alert = ctx
+        .bot
+        .database
+        .query_opt::<AnalyticsAlert>(
+            "DELETE FROM analytics_alerts WHERE guild_id = $1 AND alert_id = $2 RETURNING *",
+            &[&guild_id, &args.id],
+        )
+        .await?;
+    if alert.is_none() {
+        return respond_failure(
+            &ctx,
+            "Analytics Alert Failed",
+            "An alert with the given id was not found",
+        )
+        .await;
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Analytics Alert Deletion Successful")
+        .description(format!("Alert `{}` has been deleted", args.id))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Analytics Alert Deleted: Id {}", args.id))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+impl FromArg for AlertThreshold {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        let (amount, percentage) = match arg.strip_suffix('%') {
+            Some(amount) => (amount, true),
+            None => (arg, false),
+        };
+        match amount.parse::<i64>() {
+            Ok(amount) => Ok(AlertThreshold { amount, percentage }),
+            Err(_) => Err(ParseError(
+                "a number of members such as `50` or a percentage such as `10%`",
+            )),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::Integer(value) => value.to_string(),
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("AlertThreshold unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
@@ -0,0 +1,352 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use image::{png::PngEncoder, ColorType};
+use itertools::Itertools;
+use plotters::prelude::{Color, *};
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    analytics::{Group, Resolution},
+    guild::GuildType,
+};
+use std::{collections::BTreeMap, io::Cursor};
+use twilight_http::request::AttachmentFile;
+
+use super::{
+    parse_group_rank,
+    view::{analytics_query, ViewDuration},
+};
+use crate::utils::{csv_field, ExportFormat};
+
+/// The most series that can be drawn on one chart
+const MAX_SERIES: usize = 8;
+
+#[derive(FromArgs)]
+pub struct CompareArguments {
+    #[arg(help = "The Duration of the graph")]
+    pub duration: ViewDuration,
+    #[arg(
+        help = "The groups to compare. Use `GroupId` for a whole group or `GroupId:RankId` for a rank",
+        rest
+    )]
+    pub series: String,
+}
+
+#[derive(FromArgs)]
+pub struct ExportArguments {
+    #[arg(help = "The format of the export. One of `CSV` `JSON`")]
+    pub format: ExportFormat,
+    #[arg(help = "The Duration of the export")]
+    pub duration: ViewDuration,
+    #[arg(
+        help = "The groups to export. Use `GroupId` for a whole group or `GroupId:RankId` for a rank",
+        rest
+    )]
+    pub series: String,
+}
+
+/// The member counts of a group or a rank of a group over time
+struct Series {
+    group_id: i64,
+    rank_id: Option<i64>,
+    points: Vec<(DateTime<Utc>, i64)>,
+}
+
+impl Series {
+    fn label(&self) -> String {
+        match self.rank_id {
+            Some(rank_id) => format!("{} (Rank {})", self.group_id, rank_id),
+            None => self.group_id.to_string(),
+        }
+    }
+
+    /// The members gained and lost on each day. Only the net change between two snapshots is known,
+    /// so these are the least members that could have joined and left
+    fn daily_changes(&self) -> BTreeMap<NaiveDate, (i64, i64)> {
+        let mut changes = BTreeMap::new();
+        for ((_, before), (timestamp, after)) in self.points.iter().tuple_windows() {
+            let entry = changes
+                .entry(timestamp.date().naive_utc())
+                .or_insert((0, 0));
+            if after > before {
+                entry.0 += after - before;
+            } else {
+                entry.1 += before - after;
+            }
+        }
+        changes
+    }
+}
+
+pub async fn analytics_compare(ctx: CommandContext, args: CompareArguments) -> CommandResult {
+    let series = match load_series(&ctx, &args.duration, &args.series).await? {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let lines = series
+        .iter()
+        .map(|s| (s.label(), s.points.clone()))
+        .collect::<Vec<_>>();
+    let bytes = draw_chart("Member Count", &lines);
+    ctx.respond()
+        .files(&[AttachmentFile::from_bytes("analytics.png", &bytes)])
+        .exec()
+        .await?;
+    Ok(())
+}
+
+pub async fn analytics_deltas(ctx: CommandContext, args: CompareArguments) -> CommandResult {
+    let series = match load_series(&ctx, &args.duration, &args.series).await? {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title("Analytics Deltas")
+        .description("The members gained and lost by each group over the window");
+    let mut lines = Vec::new();
+    for s in &series {
+        let changes = s.daily_changes();
+        let joins = changes.values().map(|c| c.0).sum::<i64>();
+        let leaves = changes.values().map(|c| c.1).sum::<i64>();
+        embed = embed.field(
+            EmbedFieldBuilder::new(
+                s.label(),
+                format!(
+                    "Joins: {}\nLeaves: {}\nNet: {}",
+                    joins,
+                    leaves,
+                    joins - leaves
+                ),
+            )
+            .inline(),
+        );
+        let points = changes
+            .into_iter()
+            .map(|(day, (joins, leaves))| {
+                (Utc.from_utc_date(&day).and_hms(0, 0, 0), joins - leaves)
+            })
+            .collect::<Vec<_>>();
+        lines.push((s.label(), points));
+    }
+
+    let bytes = draw_chart("Net Members per Day", &lines);
+    ctx.respond()
+        .embeds(&[embed.build()?])?
+        .files(&[AttachmentFile::from_bytes("deltas.png", &bytes)])
+        .exec()
+        .await?;
+    Ok(())
+}
+
+pub async fn analytics_export(ctx: CommandContext, args: ExportArguments) -> CommandResult {
+    let series = match load_series(&ctx, &args.duration, &args.series).await? {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let (file_name, contents) = match args.format {
+        ExportFormat::Csv => {
+            let mut csv = String::from("series,group_id,rank_id,timestamp,member_count\n");
+            for s in &series {
+                let rank_id = s.rank_id.map_or_else(String::new, |r| r.to_string());
+                for (timestamp, member_count) in &s.points {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{}\n",
+                        csv_field(&s.label()),
+                        s.group_id,
+                        rank_id,
+                        timestamp.to_rfc3339(),
+                        member_count
+                    ));
+                }
+            }
+            ("analytics.csv", csv)
+        }
+        ExportFormat::Json => {
+            let rows = series
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "group_id": s.group_id,
+                        "rank_id": s.rank_id,
+                        "points": s
+                            .points
+                            .iter()
+                            .map(|(timestamp, member_count)| serde_json::json!({
+                                "timestamp": timestamp.to_rfc3339(),
+                                "member_count": member_count,
+                            }))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            (
+                "analytics.json",
+                serde_json::to_string_pretty(&rows).unwrap_or_default(),
+            )
+        }
+    };
+
+    ctx.respond()
+        .files(&[AttachmentFile::from_bytes(file_name, contents.as_bytes())])
+        .exec()
+        .await?;
+    Ok(())
+}
+
+/// Parse and load the series given to a command. Returns `None` after responding if any of them
+/// are invalid
+async fn load_series(
+    ctx: &CommandContext,
+    duration: &ViewDuration,
+    input: &str,
+) -> Result<Option<Vec<Series>>, RoError> {
+    let guild = ctx.bot.database.get_guild(ctx.guild_id.unwrap()).await?;
+
+    if guild.kind != GuildType::Beta {
+        beta_only(ctx).await?;
+        return Ok(None);
+    }
+
+    let mut series = Vec::new();
+    for token in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if token.is_empty() {
+            continue;
+        }
+        match parse_group_rank(token) {
+            Some((group_id, rank_id)) => series.push(Series {
+                group_id,
+                rank_id,
+                points: Vec::new(),
+            }),
+            None => {
+                respond_failure(
+                    ctx,
+                    "Analytics Viewing Failed",
+                    &format!(
+                        "`{}` is not of the form `GroupId` or `GroupId:RankId`",
+                        token
+                    ),
+                )
+                .await?;
+                return Ok(None);
+            }
+        }
+    }
+    if series.is_empty() || series.len() > MAX_SERIES {
+        respond_failure(
+            ctx,
+            "Analytics Viewing Failed",
+            &format!(
+                "You may compare between 1 and {} groups or ranks",
+                MAX_SERIES
+            ),
+        )
+        .await?;
+        return Ok(None);
+    }
+    if let Some(s) = series
+        .iter()
+        .find(|s| !guild.registered_groups.contains(&s.group_id))
+    {
+        respond_failure(
+            ctx, "Analytics Viewing Failed",
+            &format!(
+                "Group {} is not registered with this server. You may only view groups that are registered with this server",
+                s.group_id
+            ),
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    let start_time = Utc.timestamp_millis(Utc::now().timestamp_millis()) - duration.0;
+    let query = analytics_query(Resolution::for_window(duration.0.num_days()));
+    for group_id in series
+        .iter()
+        .map(|s| s.group_id)
+        .unique()
+        .collect::<Vec<_>>()
+    {
+        let mut group_data = ctx
+            .bot
+            .database
+            .query::<Group>(&query, &[&group_id, &start_time])
+            .await?;
+        group_data.sort_by_key(|g| g.timestamp);
+        for s in series.iter_mut().filter(|s| s.group_id == group_id) {
+            s.points = group_data
+                .iter()
+                .map(|g| (g.timestamp, g.member_count_of(s.rank_id)))
+                .collect();
+        }
+    }
+
+    if series.iter().all(|s| s.points.len() <= 2) {
+        respond_failure(ctx, "Analytics Viewing Failed", "There is not enough usable data to generate data. Please give the bot 24 hours to collect enough data or use another timeframe").await?;
+        return Ok(None);
+    }
+    Ok(Some(series))
+}
+
+/// Draw a line for each series with a legend of their labels
+fn draw_chart(caption: &str, lines: &[(String, Vec<(DateTime<Utc>, i64)>)]) -> Vec<u8> {
+    let points = lines.iter().flat_map(|(_, p)| p.iter());
+    let min_timestamp = points.clone().map(|p| p.0).min().unwrap_or_else(Utc::now);
+    let mut max_timestamp = points.clone().map(|p| p.0).max().unwrap_or_else(Utc::now);
+    if max_timestamp <= min_timestamp {
+        max_timestamp = min_timestamp + Duration::days(1);
+    }
+    let mut min_members = points.clone().map(|p| p.1).min().unwrap_or_default();
+    let mut max_members = points.map(|p| p.1).max().unwrap_or_default();
+    let diff = (max_members - min_members).max(10);
+    min_members -= diff / 10;
+    max_members += diff / 10;
+
+    let mut buffer = vec![0_u8; 1024 * 768 * 3];
+    {
+        let root_drawing_area =
+            BitMapBackend::with_buffer(&mut buffer, (1024, 768)).into_drawing_area();
+        root_drawing_area.fill(&WHITE).unwrap();
+        let mut chart = ChartBuilder::on(&root_drawing_area)
+            .caption(caption, ("Arial", 30))
+            .margin(10)
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .build_cartesian_2d(min_timestamp..max_timestamp, min_members..max_members)
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .x_label_formatter(&|x: &DateTime<Utc>| x.date().naive_utc().to_string())
+            .draw()
+            .unwrap();
+
+        for (index, (label, points)) in lines.iter().enumerate() {
+            let color = Palette99::pick(index).to_rgba();
+            chart
+                .draw_series(LineSeries::new(
+                    points.iter().copied(),
+                    color.stroke_width(2),
+                ))
+                .unwrap()
+                .label(label)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(&WHITE.mix(0.8))
+            .border_style(&BLACK)
+            .draw()
+            .unwrap();
+    }
+
+    let mut bytes = Vec::new();
+    let img = PngEncoder::new(Cursor::new(&mut bytes));
+    img.encode(&buffer, 1024, 768, ColorType::Rgb8).unwrap();
+    bytes
+}
@@ -1,3 +1,5 @@
+mod alerts;
+mod compare;
 mod register;
 mod view;
 
@@ -6,6 +8,8 @@ use rowifi_framework::prelude::*;
 use rowifi_models::guild::GuildType;
 use std::string::ToString;
 
+use alerts::{analytics_alert_delete, analytics_alert_new, analytics_alerts};
+use compare::{analytics_compare, analytics_deltas, analytics_export};
 use register::{analytics_register, analytics_unregister};
 use view::analytics_view;
 
@@ -28,6 +32,46 @@ pub fn analytics_config(cmds: &mut Vec<Command>) {
         .description("Command to view the membercount analytics of a group")
         .handler(analytics_view);
 
+    let analytics_compare_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["compare"])
+        .description("Command to compare the membercount analytics of several groups or ranks")
+        .handler(analytics_compare);
+
+    let analytics_deltas_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["deltas"])
+        .description(
+            "Command to view the members gained and lost each day by several groups or ranks",
+        )
+        .handler(analytics_deltas);
+
+    let analytics_export_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["export"])
+        .description("Command to export the membercount analytics of several groups or ranks")
+        .handler(analytics_export);
+
+    let analytics_alert_new_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["new"])
+        .description("Command to add an alert on the membercount of a group or rank")
+        .handler(analytics_alert_new);
+
+    let analytics_alert_delete_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["delete"])
+        .description("Command to delete an analytics alert")
+        .handler(analytics_alert_delete);
+
+    let analytics_alert_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["alert"])
+        .description("Command to view the analytics alerts of the server")
+        .sub_command(analytics_alert_new_cmd)
+        .sub_command(analytics_alert_delete_cmd)
+        .handler(analytics_alerts);
+
     let analytics_list_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["list"])
@@ -42,24 +86,28 @@ pub fn analytics_config(cmds: &mut Vec<Command>) {
         .sub_command(analytics_register_cmd)
         .sub_command(analytics_unregister_cmd)
         .sub_command(analytics_view_cmd)
+        .sub_command(analytics_compare_cmd)
+        .sub_command(analytics_deltas_cmd)
+        .sub_command(analytics_export_cmd)
+        .sub_command(analytics_alert_cmd)
         .sub_command(analytics_list_cmd)
         .handler(analytics_config_view);
     cmds.push(analytics);
 }
 
+/// Parse a `GroupId` or `GroupId:RankId` argument
+pub fn parse_group_rank(arg: &str) -> Option<(i64, Option<i64>)> {
+    match arg.split_once(':') {
+        Some((group_id, rank_id)) => Some((group_id.parse().ok()?, Some(rank_id.parse().ok()?))),
+        None => Some((arg.parse().ok()?, None)),
+    }
+}
+
 pub async fn analytics_config_view(ctx: CommandContext) -> CommandResult {
     let guild = ctx.bot.database.get_guild(ctx.guild_id.unwrap()).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     if guild.registered_groups.is_empty() {
@@ -1,5 +1,8 @@
+use chrono::Utc;
 use rowifi_framework::prelude::*;
-use rowifi_models::guild::GuildType;
+use rowifi_models::{analytics::AnalyticsRegistration, guild::GuildType, id::UserId};
+
+use crate::utils::{group_link, GroupLink};
 
 #[derive(FromArgs)]
 pub struct RegisterArguments {
@@ -24,7 +27,18 @@ pub async fn analytics_register(ctx: CommandContext, args: RegisterArguments) ->
     }
 
     let group_id = args.group_id;
-    if guild.registered_groups.iter().any(|g| g == &group_id) {
+    // Groups registered before registrations required a link have no registration and may be
+    // registered again to keep them
+    let registered = guild.registered_groups.iter().any(|g| g == &group_id);
+    let registration = ctx
+        .bot
+        .database
+        .query_opt::<AnalyticsRegistration>(
+            "SELECT * FROM analytics_registrations WHERE guild_id = $1 AND group_id = $2",
+            &[&guild_id, &group_id],
+        )
+        .await?;
+    if registered && registration.is_some() {
         let embed = EmbedBuilder::new()
             .default_data()
             .title("Group Registration Already Exists")
@@ -35,7 +49,67 @@ pub async fn analytics_register(ctx: CommandContext, args: RegisterArguments) ->
         return Ok(());
     }
 
-    ctx.bot.database.execute("UPDATE guilds SET registered_groups = array_append(registered_groups, $1) WHERE guild_id = $2", &[&group_id, &guild.guild_id]).await?;
+    // Registering a group requires a link to it so that servers cannot watch any group they like
+    let author_id = UserId(ctx.author.id);
+    let roblox_id = match group_link(&ctx.bot, author_id, guild_id, group_id).await? {
+        GroupLink::Linked { roblox_id } => roblox_id,
+        GroupLink::Unverified => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Group Registration Failed")
+                .description("You must be verified to register a group")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+        GroupLink::Missing => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Group Registration Failed")
+                .description(format!(
+                    "Your linked account must own this group or hold a rank of at least {} in it",
+                    ctx.bot.analytics_min_rank
+                ))
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    };
+
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    if !registered {
+        let update_guild = transaction.prepare_cached("UPDATE guilds SET registered_groups = array_append(registered_groups, $1) WHERE guild_id = $2").await?;
+        transaction
+            .execute(&update_guild, &[&group_id, &guild.guild_id])
+            .await?;
+    }
+    let insert_registration = transaction.prepare_cached("INSERT INTO analytics_registrations(guild_id, group_id, discord_id, roblox_id, registered_at) VALUES($1, $2, $3, $4, $5) ON CONFLICT (guild_id, group_id) DO UPDATE SET discord_id = $3, roblox_id = $4, registered_at = $5").await?;
+    transaction
+        .execute(
+            &insert_registration,
+            &[
+                &guild.guild_id,
+                &group_id,
+                &author_id,
+                &roblox_id,
+                &Utc::now(),
+            ],
+        )
+        .await?;
+    let delete_pending = transaction
+        .prepare_cached(
+            "DELETE FROM analytics_pending_registrations WHERE guild_id = $1 AND group_id = $2",
+        )
+        .await?;
+    transaction
+        .execute(&delete_pending, &[&guild.guild_id, &group_id])
+        .await?;
+    transaction.commit().await?;
 
     let embed = EmbedBuilder::new()
         .default_data()
@@ -81,7 +155,33 @@ pub async fn analytics_unregister(ctx: CommandContext, args: UnregisterArguments
         return Ok(());
     }
 
-    ctx.bot.database.execute("UPDATE guilds SET registered_groups = array_remove(registered_groups, $1) WHERE guild_id = $2", &[&group_id, &guild.guild_id]).await?;
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    let update_guild = transaction.prepare_cached("UPDATE guilds SET registered_groups = array_remove(registered_groups, $1) WHERE guild_id = $2").await?;
+    transaction
+        .execute(&update_guild, &[&group_id, &guild.guild_id])
+        .await?;
+    let delete_registration = transaction
+        .prepare_cached("DELETE FROM analytics_registrations WHERE guild_id = $1 AND group_id = $2")
+        .await?;
+    transaction
+        .execute(&delete_registration, &[&guild.guild_id, &group_id])
+        .await?;
+    let delete_alerts = transaction
+        .prepare_cached("DELETE FROM analytics_alerts WHERE guild_id = $1 AND group_id = $2")
+        .await?;
+    transaction
+        .execute(&delete_alerts, &[&guild.guild_id, &group_id])
+        .await?;
+    let delete_pending = transaction
+        .prepare_cached(
+            "DELETE FROM analytics_pending_registrations WHERE guild_id = $1 AND group_id = $2",
+        )
+        .await?;
+    transaction
+        .execute(&delete_pending, &[&guild.guild_id, &group_id])
+        .await?;
+    transaction.commit().await?;
 
     let embed = EmbedBuilder::new()
         .default_data()
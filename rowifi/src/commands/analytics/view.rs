@@ -2,7 +2,10 @@ use chrono::{DateTime, Duration, TimeZone, Utc};
 use image::{png::PngEncoder, ColorType};
 use plotters::prelude::*;
 use rowifi_framework::prelude::{Color as DiscordColor, *};
-use rowifi_models::{analytics::Group, guild::GuildType};
+use rowifi_models::{
+    analytics::{Group, Resolution},
+    guild::GuildType,
+};
 use std::io::Cursor;
 use twilight_http::request::AttachmentFile;
 
@@ -53,13 +56,11 @@ pub async fn analytics_view(ctx: CommandContext, args: ViewArguments) -> Command
         .duration
         .unwrap_or_else(|| ViewDuration(Duration::days(7)));
     let start_time = Utc.timestamp_millis(Utc::now().timestamp_millis()) - view_duration.0;
+    let resolution = Resolution::for_window(view_duration.0.num_days());
     let group_data = ctx
         .bot
         .database
-        .query::<Group>(
-            "SELECT * FROM group_analytics WHERE group_id = $1 and timestamp > $2",
-            &[&group_id, &start_time],
-        )
+        .query::<Group>(&analytics_query(resolution), &[&group_id, &start_time])
         .await?;
 
     if group_data.len() <= 2 {
@@ -164,6 +165,33 @@ pub async fn analytics_view(ctx: CommandContext, args: ViewArguments) -> Command
     Ok(())
 }
 
+/// Build the query for the snapshots of a group at a resolution. The snapshots that have not been
+/// rolled up yet are bucketed on the fly so that the whole window is charted at the same resolution
+pub fn analytics_query(resolution: Resolution) -> String {
+    let unit = match resolution.unit() {
+        Some(u) => u,
+        None => {
+            return "SELECT * FROM group_analytics WHERE group_id = $1 and timestamp > $2".into()
+        }
+    };
+    let sources = [Resolution::Raw, Resolution::Hourly, Resolution::Daily]
+        .iter()
+        .take_while(|r| **r != resolution)
+        .chain(std::iter::once(&resolution))
+        .map(|r| {
+            format!(
+                "SELECT group_id, roles, member_count, timestamp FROM {} WHERE group_id = $1 AND timestamp > $2",
+                r.table()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    format!(
+        "SELECT DISTINCT ON (date_trunc('{0}', g.timestamp)) g.group_id, g.roles, g.member_count, date_trunc('{0}', g.timestamp) AS timestamp FROM ({1}) AS g ORDER BY date_trunc('{0}', g.timestamp), g.timestamp DESC",
+        unit, sources
+    )
+}
+
 impl FromArg for ViewDuration {
     type Error = ParseError;
 
@@ -0,0 +1,226 @@
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    bind::{AssetType, AssetbindBackup, BindBackup, GroupbindBackup, Template},
+    guild::backup::GuildBackupData,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{
+    deserialize_id, fetch_groups, merge_binds, rank_bind, resolve_roles, translate_template,
+    ImportOutcome,
+};
+
+/// The placeholders of Bloxlink's nickname templates. Anything not listed here that is still
+/// wrapped in braces is dropped by [`translate_template`]
+const TOKENS: &[(&str, Option<&str>)] = &[
+    ("{roblox-name}", Some("{roblox-username}")),
+    ("{smart-name}", Some("{display-name} (@{roblox-username})")),
+    ("{discord-nick}", Some("{discord-name}")),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BloxlinkExport {
+    #[serde(default)]
+    verified_role: Option<String>,
+    #[serde(default)]
+    unverified_role: Option<String>,
+    #[serde(default)]
+    nickname_template: Option<String>,
+    #[serde(default)]
+    role_binds: BloxlinkRoleBinds,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BloxlinkRoleBinds {
+    #[serde(default)]
+    groups: HashMap<String, BloxlinkGroup>,
+    #[serde(default)]
+    assets: HashMap<String, BloxlinkBind>,
+    #[serde(default)]
+    badges: HashMap<String, BloxlinkBind>,
+    #[serde(default)]
+    game_passes: HashMap<String, BloxlinkBind>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BloxlinkGroup {
+    #[serde(default)]
+    nickname: Option<String>,
+    /// The roles given to every member of the group
+    #[serde(default)]
+    roles: Vec<String>,
+    /// The binds keyed by the rank (0-255), `all` or `guest`
+    #[serde(default)]
+    binds: HashMap<String, BloxlinkBind>,
+    #[serde(default)]
+    ranges: Vec<BloxlinkRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BloxlinkBind {
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BloxlinkRange {
+    #[serde(deserialize_with = "deserialize_id")]
+    low: i64,
+    #[serde(deserialize_with = "deserialize_id")]
+    high: i64,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+impl BloxlinkExport {
+    pub async fn into_backup(
+        self,
+        ctx: &CommandContext,
+        mut data: GuildBackupData,
+    ) -> Result<ImportOutcome, RoError> {
+        let mut warnings = Vec::new();
+        let mut binds = Vec::new();
+
+        let default_template = match &self.nickname_template {
+            Some(n) => translate_template(n, TOKENS, &mut warnings),
+            None => Template::default(),
+        };
+        if let Some(verified_role) = self.verified_role {
+            data.verified_roles = resolve_roles(ctx, &[verified_role], &mut warnings);
+        }
+        if let Some(unverified_role) = self.unverified_role {
+            data.verification_roles = resolve_roles(ctx, &[unverified_role], &mut warnings);
+        }
+
+        let mut groups = Vec::new();
+        for (group_id, group) in self.role_binds.groups {
+            match group_id.trim().parse::<i64>() {
+                Ok(group_id) => groups.push((group_id, group)),
+                Err(_) => warnings.push(format!("{} is not a valid group id", group_id)),
+            }
+        }
+        let roblox_groups =
+            fetch_groups(ctx, groups.iter().map(|(id, _)| *id), &mut warnings).await?;
+
+        for (group_id, group) in groups {
+            let roblox_group = match roblox_groups.get(&group_id) {
+                Some(g) => g,
+                None => continue,
+            };
+            let group_template = match &group.nickname {
+                Some(n) => translate_template(n, TOKENS, &mut warnings),
+                None => default_template.clone(),
+            };
+
+            if !group.roles.is_empty() {
+                binds.push(BindBackup::Group(GroupbindBackup {
+                    group_id,
+                    discord_roles: resolve_roles(ctx, &group.roles, &mut warnings),
+                    priority: 0,
+                    template: group_template.clone(),
+                }));
+            }
+
+            for (rank, bind) in group.binds {
+                let template = match &bind.nickname {
+                    Some(n) => translate_template(n, TOKENS, &mut warnings),
+                    None => group_template.clone(),
+                };
+                let discord_roles = resolve_roles(ctx, &bind.roles, &mut warnings);
+                let rank = match rank.trim().to_ascii_lowercase().as_str() {
+                    "all" => {
+                        binds.push(BindBackup::Group(GroupbindBackup {
+                            group_id,
+                            discord_roles,
+                            priority: 0,
+                            template,
+                        }));
+                        continue;
+                    }
+                    "guest" => 0,
+                    r => match r.parse::<i64>() {
+                        Ok(r) => r,
+                        Err(_) => {
+                            warnings.push(format!(
+                                "{} is not a valid rank of the group with id {}",
+                                rank, group_id
+                            ));
+                            continue;
+                        }
+                    },
+                };
+                if let Some(bind) = rank_bind(
+                    roblox_group,
+                    group_id,
+                    rank,
+                    discord_roles,
+                    template,
+                    &mut warnings,
+                ) {
+                    binds.push(bind);
+                }
+            }
+
+            for range in group.ranges {
+                let template = match &range.nickname {
+                    Some(n) => translate_template(n, TOKENS, &mut warnings),
+                    None => group_template.clone(),
+                };
+                let discord_roles = resolve_roles(ctx, &range.roles, &mut warnings);
+                for roblox_rank in roblox_group
+                    .roles
+                    .iter()
+                    .filter(|r| i64::from(r.rank) >= range.low && i64::from(r.rank) <= range.high)
+                {
+                    if let Some(bind) = rank_bind(
+                        roblox_group,
+                        group_id,
+                        i64::from(roblox_rank.rank),
+                        discord_roles.clone(),
+                        template.clone(),
+                        &mut warnings,
+                    ) {
+                        binds.push(bind);
+                    }
+                }
+            }
+        }
+
+        for (asset_type, assets) in [
+            (AssetType::Asset, self.role_binds.assets),
+            (AssetType::Badge, self.role_binds.badges),
+            (AssetType::Gamepass, self.role_binds.game_passes),
+        ] {
+            for (asset_id, bind) in assets {
+                let asset_id = match asset_id.trim().parse::<i64>() {
+                    Ok(a) => a,
+                    Err(_) => {
+                        warnings.push(format!("{} is not a valid {} id", asset_id, asset_type));
+                        continue;
+                    }
+                };
+                let template = match &bind.nickname {
+                    Some(n) => translate_template(n, TOKENS, &mut warnings),
+                    None => default_template.clone(),
+                };
+                binds.push(BindBackup::Asset(AssetbindBackup {
+                    asset_id,
+                    asset_type,
+                    discord_roles: resolve_roles(ctx, &bind.roles, &mut warnings),
+                    priority: 0,
+                    template,
+                }));
+            }
+        }
+
+        data.binds = merge_binds(binds);
+        Ok(ImportOutcome { data, warnings })
+    }
+}
@@ -0,0 +1,491 @@
+mod bloxlink;
+mod rover;
+
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    bind::{BindBackup, BindType, RankbindBackup, Template},
+    discord::channel::embed::Embed,
+    guild::backup::GuildBackupData,
+    id::RoleId,
+    roblox::{group::Group, id::GroupId},
+    user::{RoUser, UserFlags},
+};
+use serde::{de::Error as DeError, Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::restore_backup;
+use crate::commands::ToggleOption;
+use bloxlink::BloxlinkExport;
+use rover::RoverExport;
+
+#[derive(FromArgs)]
+pub struct BackupImportArguments {
+    #[arg(help = "The bot the configuration was exported from. One of `bloxlink` `rover`")]
+    pub format: ImportFormat,
+    #[arg(
+        help = "Whether the verification and verified roles that are missing from this server should be created. One of `enable` `disable`"
+    )]
+    pub create_roles: Option<ToggleOption>,
+}
+
+pub enum ImportFormat {
+    Bloxlink,
+    Rover,
+}
+
+/// The result of converting another bot's export into our backup format
+pub struct ImportOutcome {
+    pub data: GuildBackupData,
+    /// Everything in the export we were unable to carry over
+    pub warnings: Vec<String>,
+}
+
+pub async fn backup_import(ctx: CommandContext, args: BackupImportArguments) -> CommandResult {
+    match ctx
+        .bot
+        .database
+        .query_opt::<RoUser>(
+            "SELECT * FROM users WHERE discord_id = $1",
+            &[&(ctx.author.id.get() as i64)],
+        )
+        .await?
+    {
+        Some(u) if u.flags.contains(UserFlags::BETA) => {}
+        _ => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Backup Failed")
+                .description("This module may only be used by a Beta Tier user")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let file = await_file_reply(
+        "Upload the exported configuration file or paste its contents",
+        &ctx,
+    )
+    .await?;
+
+    // The imported binds replace ours, but everything the other bots don't know about is kept as is
+    let data = GuildBackupData {
+        command_prefix: guild.command_prefix,
+        verification_roles: Vec::new(),
+        verified_roles: Vec::new(),
+        blacklists: guild.blacklists,
+        blacklist_action: guild.blacklist_action,
+        update_on_join: guild.update_on_join,
+        binds: Vec::new(),
+    };
+
+    let mut outcome = match args.format {
+        ImportFormat::Bloxlink => match serde_json::from_slice::<BloxlinkExport>(&file) {
+            Ok(export) => export.into_backup(&ctx, data).await?,
+            Err(err) => {
+                return respond_failure(
+                    &ctx,
+                    "Backup Import Failed",
+                    &format!("The export could not be read: {}", err),
+                )
+                .await
+            }
+        },
+        ImportFormat::Rover => match serde_json::from_slice::<RoverExport>(&file) {
+            Ok(export) => export.into_backup(&ctx, data).await?,
+            Err(err) => {
+                return respond_failure(
+                    &ctx,
+                    "Backup Import Failed",
+                    &format!("The export could not be read: {}", err),
+                )
+                .await
+            }
+        },
+    };
+
+    let create_roles = matches!(args.create_roles, Some(ToggleOption::Enable));
+    if !create_roles {
+        let server_roles = ctx
+            .bot
+            .cache
+            .guild_roles(guild_id)
+            .iter()
+            .map(|r| r.name.clone())
+            .collect::<Vec<_>>();
+        let data = &mut outcome.data;
+        for roles in [&mut data.verification_roles, &mut data.verified_roles] {
+            for role in roles.iter().filter(|r| !server_roles.contains(r)) {
+                outcome.warnings.push(format!(
+                    "The role {} does not exist in this server and will not be created. Use the `create_roles` option to create it",
+                    role
+                ));
+            }
+            roles.retain(|r| server_roles.contains(r));
+        }
+    }
+
+    let preview = preview_embed(&outcome)?;
+    ctx.respond().embeds(&[preview])?.exec().await?;
+
+    let confirmation = await_confirmation(
+        "This will replace the verification roles, verified roles and all binds of this server. Do you want to continue?",
+        &ctx,
+    )
+    .await?;
+    if !confirmation {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .color(Color::Red as u32)
+            .title("Backup Import Cancelled")
+            .build()
+            .unwrap();
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+
+    let bind_count = outcome.data.binds.len();
+    restore_backup(&ctx, outcome.data, create_roles).await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Backup Import Successful")
+        .description(format!("{} binds were imported", bind_count))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Configuration imported from {}. {} binds were imported",
+            args.format, bind_count
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+
+    Ok(())
+}
+
+fn preview_embed(outcome: &ImportOutcome) -> Result<Embed, RoError> {
+    let data = &outcome.data;
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::Blue as u32)
+        .title("Backup Import Preview");
+
+    let verification_roles = if data.verification_roles.is_empty() {
+        "None".into()
+    } else {
+        data.verification_roles.join(", ")
+    };
+    let verified_roles = if data.verified_roles.is_empty() {
+        "None".into()
+    } else {
+        data.verified_roles.join(", ")
+    };
+    embed = embed
+        .field(EmbedFieldBuilder::new("Verification Roles", verification_roles).inline())
+        .field(EmbedFieldBuilder::new("Verified Roles", verified_roles).inline());
+
+    let mut rankbinds = String::new();
+    let mut groupbinds = String::new();
+    let mut assetbinds = String::new();
+    for bind in &data.binds {
+        let roles = bind.discord_roles().join(", ");
+        match bind {
+            BindBackup::Rank(r) => rankbinds.push_str(&format!(
+                "Group {} Rank {} - `{}` -> {}\n",
+                r.group_id, r.group_rank_id, r.template, roles
+            )),
+            BindBackup::Group(g) => groupbinds.push_str(&format!(
+                "Group {} - `{}` -> {}\n",
+                g.group_id, g.template, roles
+            )),
+            BindBackup::Asset(a) => assetbinds.push_str(&format!(
+                "{} {} - `{}` -> {}\n",
+                a.asset_type, a.asset_id, a.template, roles
+            )),
+            BindBackup::Custom(_) | BindBackup::Premium(_) => {}
+        }
+    }
+    for (name, kind, value) in [
+        ("Rankbinds", BindType::Rank, rankbinds),
+        ("Groupbinds", BindType::Group, groupbinds),
+        ("Assetbinds", BindType::Asset, assetbinds),
+    ] {
+        if value.is_empty() {
+            continue;
+        }
+        let count = data.binds.iter().filter(|b| b.kind() == kind).count();
+        embed = embed.field(EmbedFieldBuilder::new(
+            format!("{} ({})", name, count),
+            truncate(&value),
+        ));
+    }
+
+    if !outcome.warnings.is_empty() {
+        let warnings = outcome
+            .warnings
+            .iter()
+            .map(|w| format!("- {}", w))
+            .join("\n");
+        embed = embed.field(EmbedFieldBuilder::new(
+            format!("Skipped ({})", outcome.warnings.len()),
+            truncate(&warnings),
+        ));
+    }
+
+    Ok(embed.build()?)
+}
+
+/// Embed field values are capped at 1024 characters
+fn truncate(value: &str) -> String {
+    if value.chars().count() <= 1024 {
+        return value.to_string();
+    }
+    let mut truncated = value.chars().take(1020).collect::<String>();
+    truncated.push_str("...");
+    truncated
+}
+
+/// The slugs understood by [`Template`]
+const SLUGS: [&str; 5] = [
+    "roblox-username",
+    "roblox-id",
+    "discord-id",
+    "discord-name",
+    "display-name",
+];
+
+/// Translate a nickname format from another bot into a [`Template`]. `tokens` maps each of the
+/// bot's placeholders to our slug, or to `None` if we have no equivalent. Unsupported placeholders
+/// are dropped from the template and reported in `warnings`
+fn translate_template(
+    format: &str,
+    tokens: &[(&str, Option<&str>)],
+    warnings: &mut Vec<String>,
+) -> Template {
+    let mut template = format.to_string();
+    for (token, slug) in tokens {
+        if !template.contains(token) {
+            continue;
+        }
+        if let Some(slug) = slug {
+            template = template.replace(token, slug);
+        } else {
+            template = template.replace(token, "");
+            warnings.push(format!(
+                "The nickname placeholder `{}` in `{}` has no equivalent",
+                token, format
+            ));
+        }
+    }
+
+    // Strip any brace placeholders left over that we don't know of
+    let mut cleaned = String::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        cleaned.push_str(&rest[..start]);
+        let slug = &rest[start + 1..end];
+        if SLUGS.contains(&slug) {
+            cleaned.push_str(&rest[start..=end]);
+        } else {
+            warnings.push(format!(
+                "The nickname placeholder `{{{}}}` in `{}` has no equivalent",
+                slug, format
+            ));
+        }
+        rest = &rest[end + 1..];
+    }
+    cleaned.push_str(rest);
+
+    let template = cleaned.split_whitespace().join(" ");
+    if template.is_empty() {
+        Template::default()
+    } else {
+        Template(template)
+    }
+}
+
+/// Map the roles of an export to their names, since that's how backups refer to roles.
+/// Exports may either hold role ids or role names
+fn resolve_roles(
+    ctx: &CommandContext,
+    roles: &[String],
+    warnings: &mut Vec<String>,
+) -> Vec<String> {
+    // The cache holds the roles of every server, so ids are checked against this server's roles
+    let server_roles = ctx.bot.cache.roles(ctx.guild_id.unwrap());
+    let mut names = Vec::new();
+    for role in roles {
+        let role = role.trim();
+        if role.is_empty() {
+            continue;
+        }
+        match role.parse::<u64>() {
+            Ok(id) => match ctx
+                .bot
+                .cache
+                .role(RoleId::new(id))
+                .filter(|r| server_roles.contains(&r.id))
+            {
+                Some(cached) => names.push(cached.name.clone()),
+                None => warnings.push(format!(
+                    "The role with id {} does not exist in this server",
+                    id
+                )),
+            },
+            Err(_) => names.push(role.to_string()),
+        }
+    }
+    names.into_iter().unique().collect()
+}
+
+/// Fetch the ranks of every group referenced by the export once
+async fn fetch_groups(
+    ctx: &CommandContext,
+    group_ids: impl IntoIterator<Item = i64>,
+    warnings: &mut Vec<String>,
+) -> Result<HashMap<i64, Group>, RoError> {
+    let mut groups = HashMap::new();
+    for group_id in group_ids.into_iter().unique() {
+        if group_id <= 0 {
+            warnings.push(format!("{} is not a valid group id", group_id));
+            continue;
+        }
+        match ctx
+            .bot
+            .roblox
+            .get_group_ranks(GroupId(group_id as u64))
+            .await?
+        {
+            Some(group) => {
+                groups.insert(group_id, group);
+            }
+            None => warnings.push(format!("The group with id {} does not exist", group_id)),
+        }
+    }
+    Ok(groups)
+}
+
+/// Build a rankbind, looking up the global id of the rank from the group
+fn rank_bind(
+    group: &Group,
+    group_id: i64,
+    rank: i64,
+    discord_roles: Vec<String>,
+    template: Template,
+    warnings: &mut Vec<String>,
+) -> Option<BindBackup> {
+    match group.roles.iter().find(|r| i64::from(r.rank) == rank) {
+        Some(roblox_rank) => Some(BindBackup::Rank(RankbindBackup {
+            group_id,
+            discord_roles,
+            group_rank_id: rank,
+            roblox_rank_id: roblox_rank.id.0 as i64,
+            priority: 0,
+            template,
+        })),
+        None => {
+            warnings.push(format!(
+                "The group with id {} has no rank {}",
+                group_id, rank
+            ));
+            None
+        }
+    }
+}
+
+/// Other bots allow several binds on the same target, which we represent as a single bind
+fn merge_binds(binds: Vec<BindBackup>) -> Vec<BindBackup> {
+    let mut merged: Vec<BindBackup> = Vec::new();
+    for bind in binds {
+        let existing = merged.iter_mut().find(|b| match (&**b, &bind) {
+            (BindBackup::Rank(a), BindBackup::Rank(b)) => {
+                a.group_id == b.group_id && a.group_rank_id == b.group_rank_id
+            }
+            (BindBackup::Group(a), BindBackup::Group(b)) => a.group_id == b.group_id,
+            (BindBackup::Asset(a), BindBackup::Asset(b)) => {
+                a.asset_id == b.asset_id && a.asset_type == b.asset_type
+            }
+            _ => false,
+        });
+        match existing {
+            Some(existing) => {
+                let roles = match existing {
+                    BindBackup::Rank(r) => &mut r.discord_roles,
+                    BindBackup::Group(g) => &mut g.discord_roles,
+                    BindBackup::Custom(c) => &mut c.discord_roles,
+                    BindBackup::Asset(a) => &mut a.discord_roles,
+                    BindBackup::Premium(p) => &mut p.discord_roles,
+                };
+                for role in bind.discord_roles() {
+                    if !roles.contains(role) {
+                        roles.push(role.clone());
+                    }
+                }
+            }
+            None => merged.push(bind),
+        }
+    }
+    merged
+}
+
+/// Exports aren't consistent about whether ids are strings or numbers
+fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_i64()
+            .ok_or_else(|| DeError::custom(format!("{} is not a valid id", n))),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| DeError::custom(format!("{} is not a valid id", s))),
+        other => Err(DeError::custom(format!("{} is not a valid id", other))),
+    }
+}
+
+impl FromArg for ImportFormat {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match arg.to_ascii_lowercase().as_str() {
+            "bloxlink" => Ok(ImportFormat::Bloxlink),
+            "rover" => Ok(ImportFormat::Rover),
+            _ => Err(ParseError("one of `bloxlink` `rover`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("ImportFormat unreached"),
+        };
+
+        Self::from_arg(&arg)
+    }
+}
+
+impl std::fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportFormat::Bloxlink => f.write_str("Bloxlink"),
+            ImportFormat::Rover => f.write_str("RoVer"),
+        }
+    }
+}
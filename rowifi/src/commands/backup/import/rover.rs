@@ -0,0 +1,119 @@
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    bind::{BindBackup, GroupbindBackup, Template},
+    guild::backup::GuildBackupData,
+};
+use serde::Deserialize;
+
+use super::{
+    deserialize_id, fetch_groups, merge_binds, rank_bind, resolve_roles, translate_template,
+    ImportOutcome,
+};
+
+/// The placeholders of RoVer's nickname format
+const TOKENS: &[(&str, Option<&str>)] = &[
+    ("%USERNAME%", Some("{roblox-username}")),
+    ("%USERID%", Some("{roblox-id}")),
+    ("%DISPLAYNAME%", Some("{display-name}")),
+    ("%DISCORDNAME%", Some("{discord-name}")),
+    ("%DISCORDID%", Some("{discord-id}")),
+    ("%RANK%", None),
+    ("%GROUPRANK%", None),
+    ("%SERVER%", None),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoverExport {
+    #[serde(default)]
+    verified_role: Option<String>,
+    #[serde(default)]
+    unverified_role: Option<String>,
+    #[serde(default)]
+    nickname_format: Option<String>,
+    #[serde(default)]
+    group_rank_bindings: Vec<RoverBinding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoverBinding {
+    role: String,
+    #[serde(default)]
+    groups: Vec<RoverGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoverGroup {
+    #[serde(deserialize_with = "deserialize_id")]
+    id: i64,
+    /// The ranks (0-255) the binding applies to. Every member of the group if this is empty
+    #[serde(default)]
+    ranks: Vec<i64>,
+}
+
+impl RoverExport {
+    pub async fn into_backup(
+        self,
+        ctx: &CommandContext,
+        mut data: GuildBackupData,
+    ) -> Result<ImportOutcome, RoError> {
+        let mut warnings = Vec::new();
+        let mut binds = Vec::new();
+
+        // RoVer only has a single nickname format for the whole server
+        let template = match &self.nickname_format {
+            Some(n) => translate_template(n, TOKENS, &mut warnings),
+            None => Template::default(),
+        };
+        if let Some(verified_role) = self.verified_role {
+            data.verified_roles = resolve_roles(ctx, &[verified_role], &mut warnings);
+        }
+        if let Some(unverified_role) = self.unverified_role {
+            data.verification_roles = resolve_roles(ctx, &[unverified_role], &mut warnings);
+        }
+
+        let group_ids = self
+            .group_rank_bindings
+            .iter()
+            .flat_map(|b| b.groups.iter().map(|g| g.id))
+            .collect::<Vec<_>>();
+        let roblox_groups = fetch_groups(ctx, group_ids, &mut warnings).await?;
+
+        for binding in self.group_rank_bindings {
+            let discord_roles = resolve_roles(ctx, &[binding.role], &mut warnings);
+            if discord_roles.is_empty() {
+                continue;
+            }
+            for group in binding.groups {
+                let roblox_group = match roblox_groups.get(&group.id) {
+                    Some(g) => g,
+                    None => continue,
+                };
+                if group.ranks.is_empty() {
+                    binds.push(BindBackup::Group(GroupbindBackup {
+                        group_id: group.id,
+                        discord_roles: discord_roles.clone(),
+                        priority: 0,
+                        template: template.clone(),
+                    }));
+                    continue;
+                }
+                for rank in group.ranks {
+                    if let Some(bind) = rank_bind(
+                        roblox_group,
+                        group.id,
+                        rank,
+                        discord_roles.clone(),
+                        template.clone(),
+                        &mut warnings,
+                    ) {
+                        binds.push(bind);
+                    }
+                }
+            }
+        }
+
+        data.binds = merge_binds(binds);
+        Ok(ImportOutcome { data, warnings })
+    }
+}
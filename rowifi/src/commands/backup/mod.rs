@@ -1,3 +1,4 @@
+mod import;
 mod new;
 mod restore;
 
@@ -8,6 +9,7 @@ use rowifi_models::{
     user::{RoUser, UserFlags},
 };
 
+pub use import::*;
 pub use new::*;
 pub use restore::*;
 
@@ -24,6 +26,12 @@ pub fn backup_config(cmds: &mut Vec<Command>) {
         .description("Command to apply the backup to the server")
         .handler(backup_restore);
 
+    let backup_import_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["import"])
+        .description("Command to import the configuration exported from another verification bot")
+        .handler(backup_import);
+
     let backup_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["backup"])
@@ -31,6 +39,7 @@ pub fn backup_config(cmds: &mut Vec<Command>) {
         .group("Premium")
         .sub_command(backup_new_cmd)
         .sub_command(backup_restore_cmd)
+        .sub_command(backup_import_cmd)
         .handler(backup);
     cmds.push(backup_cmd);
 }
@@ -96,9 +105,14 @@ pub async fn backup(ctx: CommandContext) -> CommandResult {
             .iter()
             .filter(|b| b.kind() == BindType::Asset)
             .count();
-        let val = format!("Prefix: {}\nVerification: {:?}\nVerified: {:?}\nRankbinds: {}\nGroupbinds: {}\nCustombinds: {}\nAssetbinds: {}",
+        let p = data
+            .binds
+            .iter()
+            .filter(|b| b.kind() == BindType::Premium)
+            .count();
+        let val = format!("Prefix: {}\nVerification: {:?}\nVerified: {:?}\nRankbinds: {}\nGroupbinds: {}\nCustombinds: {}\nAssetbinds: {}\nPremiumbinds: {}",
             data.command_prefix, data.verification_roles.join(", "), data.verified_roles.join(", "),
-            r, g, c, a
+            r, g, c, a, p
         );
         embed = embed.field(EmbedFieldBuilder::new(backup.name, val));
     }
@@ -1,7 +1,10 @@
 use rowifi_database::postgres::types::Json;
 use rowifi_framework::prelude::*;
 use rowifi_models::{
-    bind::{AssetbindBackup, Bind, BindBackup, CustombindBackup, GroupbindBackup, RankbindBackup},
+    bind::{
+        AssetbindBackup, Bind, BindBackup, CustombindBackup, GroupbindBackup, PremiumbindBackup,
+        RankbindBackup,
+    },
     guild::backup::{GuildBackup, GuildBackupData},
     id::{BackupId, UserId},
     user::{RoUser, UserFlags},
@@ -109,6 +112,11 @@ pub async fn backup_new(ctx: CommandContext, args: BackupArguments) -> CommandRe
                     template: a.template,
                     priority: a.priority,
                 }),
+                Bind::Premium(p) => BindBackup::Premium(PremiumbindBackup {
+                    discord_roles,
+                    template: p.template,
+                    priority: p.priority,
+                }),
             }
         })
         .collect();
@@ -1,8 +1,11 @@
 use itertools::Itertools;
 use rowifi_framework::prelude::*;
 use rowifi_models::{
-    bind::{Assetbind, Bind, BindBackup, BindType, Custombind, Groupbind, Rankbind},
-    guild::{backup::GuildBackup, GuildType, RoGuild},
+    bind::{Assetbind, Bind, BindBackup, BindType, Custombind, Groupbind, Premiumbind, Rankbind},
+    guild::{
+        backup::{GuildBackup, GuildBackupData},
+        GuildType, InvalidAccountAction, RoGuild,
+    },
     id::{BindId, RoleId},
     rolang::RoCommand,
     user::{RoUser, UserFlags},
@@ -65,13 +68,36 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
         }
     };
 
-    let all_roles = backup
-        .data
-        .0
+    restore_backup(&ctx, backup.data.0, false).await?;
+
+    ctx.respond()
+        .content("Backup successfully restored")?
+        .exec()
+        .await?;
+    Ok(())
+}
+
+/// Apply the given backup data to the server the command was invoked in, creating any bind roles
+/// that don't already exist and replacing all existing settings and binds. Verification and
+/// verified roles that don't exist are only created if `create_setting_roles` is set
+pub async fn restore_backup(
+    ctx: &CommandContext,
+    data: GuildBackupData,
+    create_setting_roles: bool,
+) -> Result<(), RoError> {
+    let guild_id = ctx.guild_id.unwrap();
+    let bind_roles = data
         .binds
         .iter()
         .flat_map(|b| b.discord_roles())
         .cloned()
+        .collect::<Vec<_>>();
+    let all_roles = data
+        .verification_roles
+        .iter()
+        .chain(data.verified_roles.iter())
+        .chain(bind_roles.iter())
+        .cloned()
         .unique()
         .collect::<Vec<_>>();
 
@@ -88,7 +114,7 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
     for r in all_roles {
         if let Some(existing) = existing_roles.get(&r) {
             roles_map.insert(r, *existing);
-        } else {
+        } else if create_setting_roles || bind_roles.contains(&r) {
             let role = ctx
                 .bot
                 .http
@@ -111,8 +137,6 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
         }
     }
 
-    let data = backup.data.0;
-
     let verification_roles = data
         .verification_roles
         .iter()
@@ -168,12 +192,18 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
                     template: a.template,
                     priority: a.priority,
                 }),
+                BindBackup::Premium(p) => Bind::Premium(Premiumbind {
+                    bind_id: BindId::default(),
+                    discord_roles,
+                    template: p.template,
+                    priority: p.priority,
+                }),
             }
         })
         .collect::<Vec<_>>();
 
     let guild = RoGuild {
-        guild_id: ctx.guild_id.unwrap(),
+        guild_id,
         command_prefix: data.command_prefix,
         verification_roles,
         verified_roles,
@@ -184,7 +214,14 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
         kind: GuildType::Free,
         premium_owner: None,
         blacklist_action: data.blacklist_action,
+        quarantine_role: None,
+        blacklist_timeout: None,
         update_on_join: data.update_on_join,
+        update_on_role_change: false,
+        unverified_template: None,
+        verification_reminder: None,
+        unverified_kick: None,
+        invalid_account_action: InvalidAccountAction::None,
         admin_roles: Vec::new(),
         trainer_roles: Vec::new(),
         bypass_roles: Vec::new(),
@@ -195,7 +232,7 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
     let mut db = ctx.bot.database.get().await?;
     let transaction = db.transaction().await?;
 
-    let insert_guild = transaction.prepare_cached("UPDATE guilds SET kind = $2, premium_owner = $3, command_prefix = $4, verification_roles = $5, verified_roles = $6, blacklists = $7, disabled_channels = $8, registered_groups = $9, auto_detection = $10, blacklist_action = $11, update_on_join = $12, admin_roles = $13, trainer_roles = $14, bypass_roles = $15, nickname_bypass_roles = $16 WHERE guild_id = $1").await?;
+    let insert_guild = transaction.prepare_cached("UPDATE guilds SET kind = $2, premium_owner = $3, command_prefix = $4, verification_roles = $5, verified_roles = $6, blacklists = $7, disabled_channels = $8, registered_groups = $9, auto_detection = $10, blacklist_action = $11, update_on_join = $12, admin_roles = $13, trainer_roles = $14, bypass_roles = $15, nickname_bypass_roles = $16, update_on_role_change = $17, quarantine_role = $18, blacklist_timeout = $19 WHERE guild_id = $1").await?;
     transaction
         .execute(
             &insert_guild,
@@ -216,6 +253,9 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
                 &guild.trainer_roles,
                 &guild.bypass_roles,
                 &guild.nickname_bypass_roles,
+                &guild.update_on_role_change,
+                &guild.quarantine_role,
+                &guild.blacklist_timeout,
             ],
         )
         .await?;
@@ -231,6 +271,7 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
     let add_group = transaction.prepare_cached("INSERT INTO binds(bind_type, guild_id, group_id, discord_roles, priority, template) VALUES($1, $2, $3, $4, $5, $6)").await?;
     let add_custom = transaction.prepare_cached("INSERT INTO binds(bind_type, guild_id, custom_bind_id, discord_roles, code, priority, template) VALUES($1, $2, $3, $4, $5, $6, $7)").await?;
     let add_asset = transaction.prepare_cached("INSERT INTO binds(bind_type, guild_id, asset_id, asset_type, discord_roles, priority, template) VALUES($1, $2, $3, $4, $5, $6, $7)").await?;
+    let add_premium = transaction.prepare_cached("INSERT INTO binds(bind_type, guild_id, discord_roles, priority, template) VALUES($1, $2, $3, $4, $5)").await?;
     for bind in binds {
         match bind {
             Bind::Rank(r) => {
@@ -297,6 +338,20 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
                     )
                     .await?
             }
+            Bind::Premium(p) => {
+                transaction
+                    .execute(
+                        &add_premium,
+                        &[
+                            &BindType::Premium,
+                            &guild.guild_id,
+                            &p.discord_roles,
+                            &p.priority,
+                            &p.template,
+                        ],
+                    )
+                    .await?
+            }
         };
     }
     transaction.commit().await?;
@@ -307,11 +362,8 @@ pub async fn backup_restore(ctx: CommandContext, args: BackupArguments) -> Comma
     ctx.bot.nickname_bypass_roles.insert(guild_id, Vec::new());
 
     ctx.bot.log_channels.remove(&guild_id);
+    ctx.bot.role_update_guilds.remove(&guild_id);
     ctx.bot.prefixes.insert(guild_id, guild.command_prefix);
 
-    ctx.respond()
-        .content("Backup successfully restored")?
-        .exec()
-        .await?;
     Ok(())
 }
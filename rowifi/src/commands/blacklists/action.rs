@@ -0,0 +1,208 @@
+use rowifi_framework::prelude::*;
+use rowifi_models::guild::BlacklistActionType;
+
+#[derive(FromArgs)]
+pub struct BlacklistActionArguments {
+    #[arg(help = "The ID of the blacklist to modify")]
+    pub id: i64,
+    #[arg(
+        help = "The action to take when this blacklist is matched. Use `default` to use the server's blacklist action"
+    )]
+    pub action: BlacklistActionChoice,
+}
+
+/// The action of a blacklist, where `None` falls back to the server's blacklist action. The
+/// argument is required so that a mistyped action is reported rather than read as the default
+pub struct BlacklistActionChoice(pub Option<BlacklistActionType>);
+
+pub async fn blacklist_action(
+    ctx: CommandContext,
+    args: BlacklistActionArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let blacklist = match guild.blacklists.iter().find(|b| b.blacklist_id == args.id) {
+        Some(b) => b,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Modification Failed",
+                "A blacklist with the given id was not found",
+            )
+            .await
+        }
+    };
+
+    let action = args.action.0;
+    let mut new_blacklist = blacklist.clone();
+    new_blacklist.action = action;
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET blacklists = array_append(array_remove(blacklists, $1), $2) WHERE guild_id = $3",
+            &[blacklist, &new_blacklist, &guild_id],
+        )
+        .await?;
+
+    let desc = match action {
+        Some(action) => format!(
+            "Blacklist `{}` will now use the {} action",
+            blacklist.blacklist_id, action
+        ),
+        None => format!(
+            "Blacklist `{}` will now use the server's blacklist action",
+            blacklist.blacklist_id
+        ),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Blacklist Modification: Id {} - Action - {} -> {}",
+            blacklist.blacklist_id,
+            display_action(blacklist.action),
+            display_action(action)
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+/// The longest message that may be sent to kicked members. Discord allows 2000 characters and the
+/// old and new messages must both fit in the log embed
+const MAX_MESSAGE_LENGTH: usize = 1500;
+
+#[derive(FromArgs)]
+pub struct BlacklistMessageArguments {
+    #[arg(help = "The ID of the blacklist to modify")]
+    pub id: i64,
+    #[arg(
+        help = "The message sent to members before they are kicked by the `DM-Kick` action. Use `disable` to send the default message",
+        rest
+    )]
+    pub message: String,
+}
+
+pub async fn blacklist_message(
+    ctx: CommandContext,
+    args: BlacklistMessageArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let blacklist = match guild.blacklists.iter().find(|b| b.blacklist_id == args.id) {
+        Some(b) => b,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Modification Failed",
+                "A blacklist with the given id was not found",
+            )
+            .await
+        }
+    };
+
+    let message = args.message.trim();
+    if message.chars().count() > MAX_MESSAGE_LENGTH {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .color(Color::Red as u32)
+            .title("Blacklist Modification Failed")
+            .description(format!(
+                "The message may be at most {} characters long",
+                MAX_MESSAGE_LENGTH
+            ))
+            .build()
+            .unwrap();
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+    let message = if message.eq_ignore_ascii_case("disable") || message.is_empty() {
+        None
+    } else {
+        Some(message.to_string())
+    };
+
+    let mut new_blacklist = blacklist.clone();
+    new_blacklist.message = message.clone();
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET blacklists = array_append(array_remove(blacklists, $1), $2) WHERE guild_id = $3",
+            &[blacklist, &new_blacklist, &guild_id],
+        )
+        .await?;
+
+    let desc = match &message {
+        Some(m) => format!(
+            "Members matching blacklist `{}` will now be sent `{}` before being kicked",
+            blacklist.blacklist_id, m
+        ),
+        None => format!(
+            "Members matching blacklist `{}` will now be sent the default message before being kicked",
+            blacklist.blacklist_id
+        ),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Blacklist Modification: Id {} - Message - {} -> {}",
+            blacklist.blacklist_id,
+            blacklist.message.as_deref().unwrap_or("Default"),
+            message.as_deref().unwrap_or("Default")
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+fn display_action(action: Option<BlacklistActionType>) -> String {
+    action.map_or_else(|| "Server Default".into(), |a| a.to_string())
+}
+
+impl FromArg for BlacklistActionChoice {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        if arg.eq_ignore_ascii_case("default") {
+            return Ok(BlacklistActionChoice(None));
+        }
+        match BlacklistActionType::from_arg(arg) {
+            Ok(action) => Ok(BlacklistActionChoice(Some(action))),
+            Err(_) => Err(ParseError(
+                "one of `Default` `None` `Kick` `Ban` `Quarantine` `Timeout` `DM-Kick`",
+            )),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.clone(),
+            CommandOptionValue::Integer(value) => value.to_string(),
+            _ => unreachable!("BlacklistActionChoice unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::Utc;
 use rowifi_framework::prelude::*;
 use rowifi_models::{
     blacklist::{Blacklist, BlacklistData},
@@ -7,8 +8,12 @@ use rowifi_models::{
 };
 use std::collections::HashMap;
 
+use super::{expiry_info, BlacklistDuration};
+
 #[derive(FromArgs)]
 pub struct BlacklistCustomArguments {
+    #[arg(help = "How long the blacklist lasts, such as `7d`. Permanent if not given")]
+    pub duration: Option<BlacklistDuration>,
     #[arg(help = "Code to use in the blacklist", rest)]
     pub code: String,
 }
@@ -93,6 +98,10 @@ pub async fn blacklist_custom(
         blacklist_id,
         reason,
         data: BlacklistData::Custom(command),
+        expires_at: args.duration.map(|d| Utc::now() + d.0),
+        created_by: Some(UserId(ctx.author.id)),
+        action: None,
+        message: None,
     };
     ctx.bot
         .database
@@ -103,7 +112,12 @@ pub async fn blacklist_custom(
         .await?;
 
     let name = format!("Type: {:?}", blacklist.kind());
-    let desc = format!("Code: {}\nReason: {}", code, blacklist.reason);
+    let desc = format!(
+        "Code: {}\nReason: {}{}",
+        code,
+        blacklist.reason,
+        expiry_info(&blacklist)
+    );
 
     let embed = EmbedBuilder::new()
         .default_data()
@@ -36,6 +36,13 @@ pub async fn blacklist_delete(
             &[&blacklist, &(guild_id)],
         )
         .await?;
+    ctx.bot
+        .database
+        .execute(
+            "DELETE FROM blacklist_bans WHERE list_guild_id = $1 AND blacklist_id = $2",
+            &[&guild_id, &blacklist.blacklist_id],
+        )
+        .await?;
 
     let embed = EmbedBuilder::new()
         .default_data()
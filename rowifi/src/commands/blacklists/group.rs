@@ -1,10 +1,18 @@
+use chrono::Utc;
 use rowifi_framework::prelude::*;
-use rowifi_models::blacklist::{Blacklist, BlacklistData};
+use rowifi_models::{
+    blacklist::{Blacklist, BlacklistData},
+    id::UserId,
+};
+
+use super::{expiry_info, BlacklistDuration};
 
 #[derive(FromArgs)]
 pub struct BlacklistGroupArguments {
     #[arg(help = "The Group ID to blacklist")]
     pub group_id: i64,
+    #[arg(help = "How long the blacklist lasts, such as `7d`. Permanent if not given")]
+    pub duration: Option<BlacklistDuration>,
     #[arg(help = "The reason of the blacklist", rest)]
     pub reason: String,
 }
@@ -30,6 +38,10 @@ pub async fn blacklist_group(ctx: CommandContext, args: BlacklistGroupArguments)
         blacklist_id,
         reason,
         data: BlacklistData::Group(group_id),
+        expires_at: args.duration.map(|d| Utc::now() + d.0),
+        created_by: Some(UserId(ctx.author.id)),
+        action: None,
+        message: None,
     };
     ctx.bot
         .database
@@ -40,7 +52,12 @@ pub async fn blacklist_group(ctx: CommandContext, args: BlacklistGroupArguments)
         .await?;
 
     let name = format!("Type: {:?}", blacklist.kind());
-    let desc = format!("Group Id: {}\nReason: {}", group_id, blacklist.reason);
+    let desc = format!(
+        "Group Id: {}\nReason: {}{}",
+        group_id,
+        blacklist.reason,
+        expiry_info(&blacklist)
+    );
 
     let embed = EmbedBuilder::new()
         .default_data()
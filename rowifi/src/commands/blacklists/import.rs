@@ -0,0 +1,261 @@
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    blacklist::{Blacklist, BlacklistData},
+    id::UserId,
+    roblox::id::UserId as RobloxUserId,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The most entries a single import may contain
+const MAX_IMPORT_ENTRIES: usize = 1000;
+
+/// The longest reason an entry may have, so it still fits in the `blacklist view` field
+const MAX_REASON_LENGTH: usize = 512;
+
+/// A line of the imported file before the user is resolved
+struct ImportEntry {
+    target: String,
+    reason: String,
+}
+
+pub async fn blacklist_import(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let file = await_file_reply(
+        "Upload a text or CSV file with one Roblox username or id per line, optionally followed by a comma and the reason. Prefix an id with `id:` to skip the username lookup",
+        &ctx,
+    )
+    .await?;
+    let file = String::from_utf8_lossy(&file);
+    let entries = match parse_entries(&file) {
+        Ok(entries) => entries,
+        Err(line) => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Import Failed",
+                &format!(
+                    "The reason on line {} is longer than {} characters",
+                    line, MAX_REASON_LENGTH
+                ),
+            )
+            .await;
+        }
+    };
+
+    if entries.is_empty() {
+        return respond_failure(
+            &ctx,
+            "Blacklist Import Failed",
+            "No usernames or ids were found in the file",
+        )
+        .await;
+    }
+    if entries.len() > MAX_IMPORT_ENTRIES {
+        return respond_failure(
+            &ctx,
+            "Blacklist Import Failed",
+            &format!(
+                "A single import may contain at most {} entries",
+                MAX_IMPORT_ENTRIES
+            ),
+        )
+        .await;
+    }
+
+    let ids = entries
+        .iter()
+        .filter_map(|e| e.id())
+        .unique()
+        .collect::<Vec<_>>();
+    let mut resolved_ids = HashMap::new();
+    for chunk in ids.chunks(100) {
+        let user_ids = chunk.iter().map(|i| RobloxUserId(*i)).collect::<Vec<_>>();
+        for user in ctx.bot.roblox.get_users(&user_ids).await? {
+            resolved_ids.insert(user.id.0, user.id.0 as i64);
+        }
+    }
+
+    // All-digit entries that are not ids may still be usernames
+    let usernames = entries
+        .iter()
+        .filter(|e| match e.id() {
+            Some(id) => !e.explicit_id() && !resolved_ids.contains_key(&id),
+            None => !e.explicit_id(),
+        })
+        .map(|e| e.target.as_str())
+        .unique_by(|t| t.to_lowercase())
+        .collect::<Vec<_>>();
+    let mut resolved_names = HashMap::new();
+    for chunk in usernames.chunks(100) {
+        for user in ctx.bot.roblox.get_users_from_usernames(chunk).await? {
+            let requested = user.requested_username.as_ref().unwrap_or(&user.name);
+            resolved_names.insert(requested.to_lowercase(), user.id.0 as i64);
+        }
+    }
+
+    let mut unresolved = Vec::new();
+    let mut to_add = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in entries {
+        let roblox_id = entry.id().and_then(|id| resolved_ids.get(&id)).or_else(|| {
+            if entry.explicit_id() {
+                None
+            } else {
+                resolved_names.get(&entry.target.to_lowercase())
+            }
+        });
+        match roblox_id {
+            Some(roblox_id) => {
+                if seen.insert(*roblox_id) {
+                    to_add.push((*roblox_id, entry.reason));
+                }
+            }
+            None => unresolved.push(entry.target),
+        }
+    }
+
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    let get = transaction
+        .prepare_cached("SELECT blacklists FROM guilds WHERE guild_id = $1 FOR UPDATE")
+        .await?;
+    let blacklists: Vec<Blacklist> = match transaction.query_opt(&get, &[&guild_id]).await? {
+        Some(row) => row.get("blacklists"),
+        None => Vec::new(),
+    };
+    let existing = blacklists
+        .iter()
+        .filter_map(|b| match b.data {
+            BlacklistData::User(u) => Some(u),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let mut blacklist_id = blacklists
+        .iter()
+        .map(|b| b.blacklist_id)
+        .max()
+        .unwrap_or_default();
+    let mut duplicates = 0;
+    let mut new_blacklists = Vec::new();
+    for (roblox_id, reason) in to_add {
+        if existing.contains(&roblox_id) {
+            duplicates += 1;
+            continue;
+        }
+        blacklist_id += 1;
+        new_blacklists.push(Blacklist {
+            blacklist_id,
+            reason,
+            data: BlacklistData::User(roblox_id),
+            expires_at: None,
+            created_by: Some(UserId(ctx.author.id)),
+            action: None,
+            message: None,
+        });
+    }
+    if !new_blacklists.is_empty() {
+        let insert = transaction
+            .prepare_cached(
+                "UPDATE guilds SET blacklists = array_cat(blacklists, $1) WHERE guild_id = $2",
+            )
+            .await?;
+        transaction
+            .execute(&insert, &[&new_blacklists, &guild_id])
+            .await?;
+    }
+    transaction.commit().await?;
+
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Import Successful")
+        .field(EmbedFieldBuilder::new("Added", new_blacklists.len().to_string()).inline())
+        .field(EmbedFieldBuilder::new("Already Blacklisted", duplicates.to_string()).inline())
+        .field(EmbedFieldBuilder::new("Unresolved", unresolved.len().to_string()).inline());
+    if !unresolved.is_empty() {
+        let mut names = String::new();
+        for name in &unresolved {
+            if names.len() + name.len() > 1000 {
+                names.push_str("...");
+                break;
+            }
+            names.push_str(&format!("`{}` ", name));
+        }
+        embed = embed.field(EmbedFieldBuilder::new("Unresolved Users", names));
+    }
+    ctx.respond()
+        .embeds(&[embed.build().unwrap()])?
+        .exec()
+        .await?;
+
+    if !new_blacklists.is_empty() {
+        let log_embed = EmbedBuilder::new()
+            .default_data()
+            .title(format!("Action by {}", ctx.author.name))
+            .description(format!(
+                "Blacklist Import: {} user blacklists added",
+                new_blacklists.len()
+            ))
+            .build()
+            .unwrap();
+        ctx.log_guild(guild_id, log_embed).await;
+    }
+    Ok(())
+}
+
+impl ImportEntry {
+    /// Whether the entry was written as `id:<id>` and should never be looked up as a username
+    fn explicit_id(&self) -> bool {
+        matches!(self.target.get(..3), Some(prefix) if prefix.eq_ignore_ascii_case("id:"))
+    }
+
+    /// The Roblox id the entry may refer to
+    fn id(&self) -> Option<u64> {
+        if self.explicit_id() {
+            self.target[3..].trim().parse().ok()
+        } else {
+            self.target.parse().ok()
+        }
+    }
+}
+
+/// Read the entries of the file. Each line holds a username or id, optionally followed by a comma
+/// or whitespace and the reason. Blank lines, comments and a CSV header are skipped. Returns the
+/// line number of the first entry whose reason is too long
+fn parse_entries(file: &str) -> Result<Vec<ImportEntry>, usize> {
+    let mut entries = Vec::new();
+    for (index, line) in file.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (target, reason) = match line.split_once(',') {
+            Some((target, reason)) => (target, reason),
+            None => line.split_once(char::is_whitespace).unwrap_or((line, "")),
+        };
+        let target = target.trim().trim_matches('"');
+        let reason = reason.trim().trim_matches('"');
+        if index == 0
+            && ["username", "user", "id", "roblox_id", "name"]
+                .contains(&target.to_lowercase().as_str())
+        {
+            continue;
+        }
+        if target.is_empty() {
+            continue;
+        }
+        if reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(index + 1);
+        }
+        entries.push(ImportEntry {
+            target: target.to_string(),
+            reason: if reason.is_empty() {
+                "N/A".into()
+            } else {
+                reason.to_string()
+            },
+        });
+    }
+    Ok(entries)
+}
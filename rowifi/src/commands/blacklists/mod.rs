@@ -1,16 +1,30 @@
+mod action;
 mod custom;
 mod delete;
+mod discord;
 mod group;
+mod import;
 mod name;
+mod shared;
 
+use chrono::Duration;
 use itertools::Itertools;
 use rowifi_framework::prelude::*;
-use rowifi_models::blacklist::BlacklistData;
+use rowifi_models::blacklist::{Blacklist, BlacklistData};
 
+use crate::utils::parse_duration;
+
+pub use action::{blacklist_action, blacklist_message};
 pub use custom::blacklist_custom;
 pub use delete::blacklist_delete;
+pub use discord::blacklist_discord;
 pub use group::blacklist_group;
+pub use import::blacklist_import;
 pub use name::blacklist_name;
+pub use shared::{
+    blacklist_publish, blacklist_subscribe, blacklist_subscriptions, blacklist_unpublish,
+    blacklist_unsubscribe,
+};
 
 pub fn blacklists_config(cmds: &mut Vec<Command>) {
     let blacklist_view_cmd = Command::builder()
@@ -37,12 +51,66 @@ pub fn blacklists_config(cmds: &mut Vec<Command>) {
         .description("Command to add a user blacklist")
         .handler(blacklist_name);
 
+    let blacklist_discord_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["discord", "user"])
+        .description("Command to add a discord account blacklist")
+        .handler(blacklist_discord);
+
+    let blacklist_import_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["import"])
+        .description("Command to add user blacklists in bulk from a file of usernames or ids")
+        .handler(blacklist_import);
+
     let blacklist_delete_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["delete", "d", "remove"])
         .description("Command to delete a blacklist")
         .handler(blacklist_delete);
 
+    let blacklist_action_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["action"])
+        .description("Command to set the action taken when a blacklist is matched")
+        .handler(blacklist_action);
+
+    let blacklist_message_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["message", "dm"])
+        .description("Command to set the message sent to members before a blacklist kicks them")
+        .handler(blacklist_message);
+
+    let blacklist_publish_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["publish"])
+        .description("Command to publish the server's blacklists for other servers to subscribe to")
+        .handler(blacklist_publish);
+
+    let blacklist_unpublish_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["unpublish"])
+        .description("Command to stop sharing the server's blacklists")
+        .handler(blacklist_unpublish);
+
+    let blacklist_subscribe_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["subscribe"])
+        .description("Command to subscribe to another server's shared blacklists")
+        .handler(blacklist_subscribe);
+
+    let blacklist_unsubscribe_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["unsubscribe"])
+        .description("Command to unsubscribe from a shared blacklist")
+        .handler(blacklist_unsubscribe);
+
+    let blacklist_subscriptions_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["subscriptions", "shared"])
+        .description("Command to view the shared blacklists of the server")
+        .handler(blacklist_subscriptions);
+
     let blacklist_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["blacklist", "bl", "blacklists"])
@@ -52,7 +120,16 @@ pub fn blacklists_config(cmds: &mut Vec<Command>) {
         .sub_command(blacklist_custom_cmd)
         .sub_command(blacklist_group_cmd)
         .sub_command(blacklist_name_cmd)
+        .sub_command(blacklist_discord_cmd)
+        .sub_command(blacklist_import_cmd)
         .sub_command(blacklist_delete_cmd)
+        .sub_command(blacklist_action_cmd)
+        .sub_command(blacklist_message_cmd)
+        .sub_command(blacklist_publish_cmd)
+        .sub_command(blacklist_unpublish_cmd)
+        .sub_command(blacklist_subscribe_cmd)
+        .sub_command(blacklist_unsubscribe_cmd)
+        .sub_command(blacklist_subscriptions_cmd)
         .handler(blacklist);
     cmds.push(blacklist_cmd);
 }
@@ -60,8 +137,9 @@ pub fn blacklists_config(cmds: &mut Vec<Command>) {
 pub async fn blacklist(ctx: CommandContext) -> CommandResult {
     let guild_id = ctx.guild_id.unwrap();
     let guild = ctx.bot.database.get_guild(guild_id).await?;
+    let subscribed = ctx.bot.database.get_subscribed_blacklists(guild_id).await?;
 
-    if guild.blacklists.is_empty() {
+    if guild.blacklists.is_empty() && subscribed.is_empty() {
         let e = EmbedBuilder::new()
             .default_data()
             .title("Bind Viewing Failed")
@@ -82,24 +160,27 @@ pub async fn blacklist(ctx: CommandContext) -> CommandResult {
             .description(format!("Page {}", page_count + 1));
         for bl in bls {
             let name = format!("Id: {}", bl.blacklist_id);
-            let desc = match &bl.data {
-                BlacklistData::User(user) => format!(
-                    "Type: {}\nUser Id: {}\nReason: {}",
-                    bl.kind(),
-                    user,
-                    bl.reason
-                ),
-                BlacklistData::Group(group) => format!(
-                    "Type: {}\nGroup Id: {}\nReason: {}",
-                    bl.kind(),
-                    group,
-                    bl.reason
-                ),
-                BlacklistData::Custom(code) => {
-                    format!("Type: {}\nCode: {}\nReason: {}", bl.kind(), code, bl.reason)
-                }
-            };
-            embed = embed.field(EmbedFieldBuilder::new(name, desc).inline().build());
+            embed = embed.field(
+                EmbedFieldBuilder::new(name, blacklist_info(bl))
+                    .inline()
+                    .build(),
+            );
+        }
+        pages.push(embed.build()?);
+        page_count += 1;
+    }
+    for bls in &subscribed.iter().chunks(12) {
+        let mut embed = EmbedBuilder::new()
+            .default_data()
+            .title("Subscribed Blacklists")
+            .description(format!("Page {}", page_count + 1));
+        for bl in bls {
+            let name = format!("{} - Id: {}", bl.list_name, bl.blacklist.blacklist_id);
+            embed = embed.field(
+                EmbedFieldBuilder::new(name, blacklist_info(&bl.blacklist))
+                    .inline()
+                    .build(),
+            );
         }
         pages.push(embed.build()?);
         page_count += 1;
@@ -107,3 +188,75 @@ pub async fn blacklist(ctx: CommandContext) -> CommandResult {
     paginate_embed(&ctx, pages, page_count).await?;
     Ok(())
 }
+
+/// The type, target, reason, expiry and action of a blacklist as shown by `blacklist view`
+fn blacklist_info(bl: &Blacklist) -> String {
+    let desc = match &bl.data {
+        BlacklistData::User(user) => format!(
+            "Type: {}\nUser Id: {}\nReason: {}",
+            bl.kind(),
+            user,
+            bl.reason
+        ),
+        BlacklistData::Group(group) => format!(
+            "Type: {}\nGroup Id: {}\nReason: {}",
+            bl.kind(),
+            group,
+            bl.reason
+        ),
+        BlacklistData::Custom(code) => {
+            format!("Type: {}\nCode: {}\nReason: {}", bl.kind(), code, bl.reason)
+        }
+        BlacklistData::Discord(user) => format!(
+            "Type: {}\nDiscord User: <@{}>\nReason: {}",
+            bl.kind(),
+            user,
+            bl.reason
+        ),
+    };
+    let mut desc = format!("{}{}", desc, expiry_info(bl));
+    if let Some(action) = bl.action {
+        desc.push_str(&format!("\nAction: {}", action));
+    }
+    desc
+}
+
+/// The duration of a temporary blacklist
+pub struct BlacklistDuration(pub Duration);
+
+/// The longest a temporary blacklist may last, in days
+const MAX_DURATION_DAYS: i64 = 3650;
+
+impl FromArg for BlacklistDuration {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        let units = [
+            ('h', Duration::hours(1)),
+            ('d', Duration::days(1)),
+            ('w', Duration::weeks(1)),
+            ('m', Duration::days(30)),
+        ];
+        parse_duration(arg, &units, Duration::days(MAX_DURATION_DAYS))
+            .map(BlacklistDuration)
+            .ok_or(ParseError(
+                "a time duration of at most 10 years such as `12h` `7d` `2w` `1m`",
+            ))
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::Integer(value) => value.to_string(),
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("BlacklistDuration unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
+
+/// The expiry line shown under temporary blacklists
+pub(crate) fn expiry_info(blacklist: &Blacklist) -> String {
+    blacklist.expires_at.map_or_else(String::new, |e| {
+        format!("\nExpires: <t:{}:f>", e.timestamp())
+    })
+}
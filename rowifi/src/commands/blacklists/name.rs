@@ -1,10 +1,18 @@
+use chrono::Utc;
 use rowifi_framework::prelude::*;
-use rowifi_models::blacklist::{Blacklist, BlacklistData};
+use rowifi_models::{
+    blacklist::{Blacklist, BlacklistData},
+    id::UserId,
+};
+
+use super::{expiry_info, BlacklistDuration};
 
 #[derive(FromArgs)]
 pub struct BlacklistNameArguments {
     #[arg(help = "The username to blacklist. This will get converted into the id in the database")]
     pub username: String,
+    #[arg(help = "How long the blacklist lasts, such as `7d`. Permanent if not given")]
+    pub duration: Option<BlacklistDuration>,
     #[arg(help = "The reason of the blacklist", rest)]
     pub reason: String,
 }
@@ -48,6 +56,10 @@ pub async fn blacklist_name(ctx: CommandContext, args: BlacklistNameArguments) -
         blacklist_id,
         reason,
         data: BlacklistData::User(user.id.0 as i64),
+        expires_at: args.duration.map(|d| Utc::now() + d.0),
+        created_by: Some(UserId(ctx.author.id)),
+        action: None,
+        message: None,
     };
 
     ctx.bot
@@ -59,7 +71,12 @@ pub async fn blacklist_name(ctx: CommandContext, args: BlacklistNameArguments) -
         .await?;
 
     let name = format!("Type: {:?}", blacklist.kind());
-    let desc = format!("User Id: {}\nReason: {}", user.id.0, blacklist.reason);
+    let desc = format!(
+        "User Id: {}\nReason: {}{}",
+        user.id.0,
+        blacklist.reason,
+        expiry_info(&blacklist)
+    );
 
     let embed = EmbedBuilder::new()
         .default_data()
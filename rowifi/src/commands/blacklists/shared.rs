@@ -0,0 +1,355 @@
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::blacklist::SharedBlacklist;
+
+#[derive(FromArgs)]
+pub struct BlacklistPublishArguments {
+    #[arg(
+        help = "The name other servers use to subscribe to this server's blacklists",
+        rest
+    )]
+    pub name: String,
+}
+
+pub async fn blacklist_publish(
+    ctx: CommandContext,
+    args: BlacklistPublishArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let name = args.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 32 {
+        return respond_failure(
+            &ctx,
+            "Blacklist Publish Failed",
+            "The name of the list must be between 1 and 32 characters",
+        )
+        .await;
+    }
+
+    if let Some(list) = ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT * FROM shared_blacklists WHERE guild_id = $1",
+            &[&guild_id],
+        )
+        .await?
+    {
+        return respond_failure(
+            &ctx,
+            "Blacklist Publish Failed",
+            &format!(
+                "This server's blacklists are already published as `{}`",
+                list.name
+            ),
+        )
+        .await;
+    }
+
+    if ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT * FROM shared_blacklists WHERE LOWER(name) = LOWER($1)",
+            &[&name],
+        )
+        .await?
+        .is_some()
+    {
+        return respond_failure(
+            &ctx,
+            "Blacklist Publish Failed",
+            "A list with this name already exists",
+        )
+        .await;
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "INSERT INTO shared_blacklists(guild_id, name) VALUES($1, $2)",
+            &[&guild_id, &name],
+        )
+        .await?;
+
+    let mut description = format!(
+        "This server's blacklists have been published as `{}`. Other servers can now subscribe to them",
+        name
+    );
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+    let unshared = guild
+        .blacklists
+        .iter()
+        .filter(|b| !b.is_shareable())
+        .map(|b| format!("`{}`", b.blacklist_id))
+        .collect::<Vec<_>>();
+    if !unshared.is_empty() {
+        description.push_str(&format!(
+            "\n\nCustom blacklists that check for discord roles are not shared: {}",
+            unshared.join(", ")
+        ));
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Publish Successful")
+        .description(description)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Blacklists published as `{}`", name))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+pub async fn blacklist_unpublish(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let list = match ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT * FROM shared_blacklists WHERE guild_id = $1",
+            &[&guild_id],
+        )
+        .await?
+    {
+        Some(l) => l,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Unpublish Failed",
+                "This server's blacklists are not published",
+            )
+            .await
+        }
+    };
+
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    let delete_subscriptions = transaction
+        .prepare_cached("DELETE FROM blacklist_subscriptions WHERE list_id = $1")
+        .await?;
+    transaction
+        .execute(&delete_subscriptions, &[&list.list_id])
+        .await?;
+    let delete_list = transaction
+        .prepare_cached("DELETE FROM shared_blacklists WHERE list_id = $1")
+        .await?;
+    transaction.execute(&delete_list, &[&list.list_id]).await?;
+    transaction.commit().await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Unpublish Successful")
+        .description(format!(
+            "`{}` has been unpublished and all subscriptions to it were removed",
+            list.name
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Blacklists unpublished from `{}`", list.name))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct BlacklistSubscribeArguments {
+    #[arg(help = "The name of the shared list", rest)]
+    pub name: String,
+}
+
+pub async fn blacklist_subscribe(
+    ctx: CommandContext,
+    args: BlacklistSubscribeArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let list = match find_list(&ctx, &args.name).await? {
+        Some(l) => l,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Subscription Failed",
+                "There is no shared list with the given name",
+            )
+            .await
+        }
+    };
+    if list.guild_id == guild_id {
+        return respond_failure(
+            &ctx,
+            "Blacklist Subscription Failed",
+            "A server cannot subscribe to its own list",
+        )
+        .await;
+    }
+
+    let subscribed = ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT s.* FROM blacklist_subscriptions bs INNER JOIN shared_blacklists s ON s.list_id = bs.list_id WHERE bs.guild_id = $1 AND bs.list_id = $2",
+            &[&guild_id, &list.list_id],
+        )
+        .await?;
+    if subscribed.is_some() {
+        return respond_failure(
+            &ctx,
+            "Blacklist Subscription Failed",
+            "This server is already subscribed to this list",
+        )
+        .await;
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "INSERT INTO blacklist_subscriptions(guild_id, list_id) VALUES($1, $2)",
+            &[&guild_id, &list.list_id],
+        )
+        .await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Subscription Successful")
+        .description(format!(
+            "This server is now subscribed to `{}`. Its blacklists will be checked alongside this server's own",
+            list.name
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Subscribed to the shared blacklist `{}`",
+            list.name
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+pub async fn blacklist_unsubscribe(
+    ctx: CommandContext,
+    args: BlacklistSubscribeArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let list = match find_list(&ctx, &args.name).await? {
+        Some(l) => l,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Blacklist Unsubscription Failed",
+                "There is no shared list with the given name",
+            )
+            .await
+        }
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "DELETE FROM blacklist_subscriptions WHERE guild_id = $1 AND list_id = $2",
+            &[&guild_id, &list.list_id],
+        )
+        .await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Blacklist Unsubscription Successful")
+        .description(format!(
+            "This server is no longer subscribed to `{}`",
+            list.name
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Unsubscribed from the shared blacklist `{}`",
+            list.name
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+pub async fn blacklist_subscriptions(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let published = ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT * FROM shared_blacklists WHERE guild_id = $1",
+            &[&guild_id],
+        )
+        .await?;
+    let subscriptions = ctx
+        .bot
+        .database
+        .query::<SharedBlacklist>(
+            "SELECT s.* FROM blacklist_subscriptions bs INNER JOIN shared_blacklists s ON s.list_id = bs.list_id WHERE bs.guild_id = $1 ORDER BY s.name",
+            &[&guild_id],
+        )
+        .await?;
+
+    let published = published.map_or_else(|| "Not Published".into(), |l| format!("`{}`", l.name));
+    let mut subscribed = subscriptions
+        .iter()
+        .map(|s| format!("- `{}`", s.name))
+        .join("\n");
+    if subscribed.is_empty() {
+        subscribed = "None".into();
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title("Shared Blacklists")
+        .field(EmbedFieldBuilder::new("Published As", published))
+        .field(EmbedFieldBuilder::new("Subscriptions", subscribed))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
+
+async fn find_list(ctx: &CommandContext, name: &str) -> Result<Option<SharedBlacklist>, RoError> {
+    let list = ctx
+        .bot
+        .database
+        .query_opt::<SharedBlacklist>(
+            "SELECT * FROM shared_blacklists WHERE LOWER(name) = LOWER($1)",
+            &[&name.trim()],
+        )
+        .await?;
+    Ok(list)
+}
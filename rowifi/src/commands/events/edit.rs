@@ -0,0 +1,530 @@
+use chrono::Utc;
+use itertools::Itertools;
+use rowifi_database::{blind_index, encrypt_bytes};
+use rowifi_framework::{get_perm_level, prelude::*};
+use rowifi_models::{
+    events::{EventEdit, EventLog, EventType},
+    guild::GuildType,
+    id::UserId,
+};
+
+use super::can_host_event;
+
+#[derive(FromArgs)]
+pub struct EventEditArguments {
+    #[arg(help = "The id of the event to edit")]
+    pub event_id: i64,
+    #[arg(help = "What to edit. One of `add` `remove` `type` `notes`")]
+    pub field: EventEditField,
+    #[arg(
+        help = "The roblox usernames to add or remove, the new event type id or the new notes",
+        rest
+    )]
+    pub value: String,
+}
+
+pub enum EventEditField {
+    AddAttendees,
+    RemoveAttendees,
+    EventType,
+    Notes,
+}
+
+pub async fn event_edit(ctx: CommandContext, args: EventEditArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let event = match editable_event(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let value = args.value.trim();
+    let mut updated = event.clone();
+    // Only replaced when the notes are edited
+    let mut notes_index = None;
+    let change = match args.field {
+        EventEditField::AddAttendees | EventEditField::RemoveAttendees => {
+            let usernames = value.split_whitespace().collect::<Vec<_>>();
+            if usernames.is_empty() {
+                return respond_failure(
+                    &ctx,
+                    "Event Edit Failed",
+                    "You must give at least one roblox username",
+                )
+                .await;
+            }
+            let mut users = Vec::new();
+            for chunk in usernames.chunks(100) {
+                users.extend(ctx.bot.roblox.get_users_from_usernames(chunk).await?);
+            }
+            let unresolved = usernames
+                .iter()
+                .filter(|u| {
+                    !users.iter().any(|r| {
+                        r.requested_username
+                            .as_ref()
+                            .unwrap_or(&r.name)
+                            .eq_ignore_ascii_case(u)
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !unresolved.is_empty() {
+                return respond_failure(
+                    &ctx,
+                    "Event Edit Failed",
+                    &format!(
+                        "The following usernames do not have an associated id: {}",
+                        unresolved.iter().join(", ")
+                    ),
+                )
+                .await;
+            }
+
+            let is_add = matches!(args.field, EventEditField::AddAttendees);
+            let mut names = Vec::new();
+            for user in users {
+                let roblox_id = user.id.0 as i64;
+                if is_add && !updated.attendees.contains(&roblox_id) {
+                    updated.attendees.push(roblox_id);
+                    names.push(user.name);
+                } else if !is_add && updated.attendees.contains(&roblox_id) {
+                    updated.attendees.retain(|a| *a != roblox_id);
+                    names.push(user.name);
+                }
+            }
+            if names.is_empty() {
+                return respond_failure(
+                    &ctx,
+                    "Event Edit Failed",
+                    "None of the given members needed to be changed",
+                )
+                .await;
+            }
+            if updated.attendees.is_empty() {
+                return respond_failure(
+                    &ctx,
+                    "Event Edit Failed",
+                    "An event must have at least one attendee. Void the event instead",
+                )
+                .await;
+            }
+            if is_add {
+                format!("Added Attendees: {}", names.join(", "))
+            } else {
+                format!("Removed Attendees: {}", names.join(", "))
+            }
+        }
+        EventEditField::EventType => {
+            let event_type = match value.parse::<i32>() {
+                Ok(e) => e,
+                Err(_) => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Edit Failed",
+                        "The event type id must be a number",
+                    )
+                    .await
+                }
+            };
+            let event_types = ctx
+                .bot
+                .database
+                .query::<EventType>(
+                    "SELECT * FROM event_types WHERE guild_id = $1",
+                    &[&(guild_id)],
+                )
+                .await?;
+            match event_types
+                .iter()
+                .find(|e| e.event_type_guild_id == event_type)
+            {
+                Some(e) if e.disabled => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Edit Failed",
+                        "This event type is disabled",
+                    )
+                    .await
+                }
+                Some(e) if !can_host_event(&ctx, e) => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Edit Failed",
+                        "You are not allowed to host this event type",
+                    )
+                    .await
+                }
+                Some(_) => {}
+                None => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Edit Failed",
+                        &format!("An event type with id {} does not exist", event_type),
+                    )
+                    .await
+                }
+            }
+            if event_type == event.event_type {
+                return respond_failure(
+                    &ctx,
+                    "Event Edit Failed",
+                    "The event is already of this type",
+                )
+                .await;
+            }
+            updated.event_type = event_type;
+            let name = |id: i32| {
+                event_types
+                    .iter()
+                    .find(|e| e.event_type_guild_id == id)
+                    .map_or_else(|| id.to_string(), |e| e.name.clone())
+            };
+            format!(
+                "Event Type: {} -> {}",
+                name(event.event_type),
+                name(event_type)
+            )
+        }
+        EventEditField::Notes => {
+            let now = Utc::now();
+            if value.eq_ignore_ascii_case("N/A") || value.is_empty() {
+                updated.notes = None;
+                updated.notes_updated_at = None;
+                notes_index = Some(Vec::new());
+                "Notes Removed".into()
+            } else {
+                updated.notes = Some(encrypt_bytes(
+                    value.as_bytes(),
+                    &ctx.bot.database.keyring,
+                    guild_id.get(),
+                )?);
+                updated.notes_updated_at = Some(now);
+                notes_index = Some(blind_index(
+                    value,
+                    ctx.bot.database.keyring.index_key(),
+                    guild_id.get(),
+                ));
+                "Notes Edited".into()
+            }
+        }
+    };
+
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    // The row is locked and compared with the event the edit was made against, so that an edit
+    // made in the meantime is not overwritten
+    let lock = transaction
+        .prepare_cached("SELECT event_type, attendees, notes FROM events WHERE guild_id = $1 AND guild_event_id = $2 AND voided_at IS NULL FOR UPDATE")
+        .await?;
+    let current = match transaction
+        .query_opt(&lock, &[&guild_id, &event.guild_event_id])
+        .await?
+    {
+        Some(row) => row,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Edit Failed",
+                "This event has been voided and can no longer be edited",
+            )
+            .await
+        }
+    };
+    if current.get::<_, i32>("event_type") != event.event_type
+        || current.get::<_, Vec<i64>>("attendees") != event.attendees
+        || current.get::<_, Option<Vec<u8>>>("notes") != event.notes
+    {
+        return respond_failure(
+            &ctx,
+            "Event Edit Failed",
+            "This event was edited by someone else in the meantime. Please try again",
+        )
+        .await;
+    }
+    let update = transaction
+        .prepare_cached("UPDATE events SET event_type = $1, attendees = $2, notes = $3, notes_updated_at = $4, notes_index = COALESCE($5, notes_index) WHERE guild_id = $6 AND guild_event_id = $7")
+        .await?;
+    transaction
+        .execute(
+            &update,
+            &[
+                &updated.event_type,
+                &updated.attendees,
+                &updated.notes,
+                &updated.notes_updated_at,
+                &notes_index,
+                &guild_id,
+                &event.guild_event_id,
+            ],
+        )
+        .await?;
+    let history = transaction
+        .prepare_cached("INSERT INTO event_edits(guild_id, guild_event_id, editor_id, timestamp, change) VALUES($1, $2, $3, $4, $5)")
+        .await?;
+    transaction
+        .execute(
+            &history,
+            &[
+                &guild_id,
+                &event.guild_event_id,
+                &UserId(ctx.author.id),
+                &Utc::now(),
+                &change,
+            ],
+        )
+        .await?;
+    transaction.commit().await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Edit Successful")
+        .description(format!("Event `{}` - {}", event.guild_event_id, change))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Event Edited: Id {} - {}",
+            event.guild_event_id, change
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+/// The longest reason an event may be voided with
+const MAX_VOID_REASON_LENGTH: usize = 256;
+
+#[derive(FromArgs)]
+pub struct EventVoidArguments {
+    #[arg(help = "The id of the event to void")]
+    pub event_id: i64,
+    #[arg(help = "The reason for voiding the event", rest)]
+    pub reason: Option<String>,
+}
+
+pub async fn event_void(ctx: CommandContext, args: EventVoidArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let event = match editable_event(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    let reason = args.reason.unwrap_or_else(|| "N/A".into());
+    if reason.chars().count() > MAX_VOID_REASON_LENGTH {
+        return respond_failure(
+            &ctx,
+            "Event Edit Failed",
+            &format!(
+                "The reason may be at most {} characters long",
+                MAX_VOID_REASON_LENGTH
+            ),
+        )
+        .await;
+    }
+    let now = Utc::now();
+
+    let mut db = ctx.bot.database.get().await?;
+    let transaction = db.transaction().await?;
+    let void = transaction
+        .prepare_cached("UPDATE events SET voided_at = $1, void_reason = $2 WHERE guild_id = $3 AND guild_event_id = $4 AND voided_at IS NULL")
+        .await?;
+    let voided = transaction
+        .execute(&void, &[&now, &reason, &guild_id, &event.guild_event_id])
+        .await?;
+    if voided == 0 {
+        return respond_failure(
+            &ctx,
+            "Event Edit Failed",
+            "This event has already been voided",
+        )
+        .await;
+    }
+    let change = format!("Voided: {}", reason);
+    let history = transaction
+        .prepare_cached("INSERT INTO event_edits(guild_id, guild_event_id, editor_id, timestamp, change) VALUES($1, $2, $3, $4, $5)")
+        .await?;
+    transaction
+        .execute(
+            &history,
+            &[
+                &guild_id,
+                &event.guild_event_id,
+                &UserId(ctx.author.id),
+                &now,
+                &change,
+            ],
+        )
+        .await?;
+    transaction.commit().await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Void Successful")
+        .description(format!(
+            "Event `{}` has been voided and will no longer count towards summaries, statistics and quotas",
+            event.guild_event_id
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Event Voided: Id {} - {}",
+            event.guild_event_id, reason
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventHistoryArguments {
+    #[arg(help = "The id of the event")]
+    pub event_id: i64,
+}
+
+pub async fn event_history(ctx: CommandContext, args: EventHistoryArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let edits = ctx
+        .bot
+        .database
+        .query::<EventEdit>(
+            "SELECT * FROM event_edits WHERE guild_id = $1 AND guild_event_id = $2 ORDER BY timestamp DESC",
+            &[&guild_id, &args.event_id],
+        )
+        .await?;
+
+    let mut pages = Vec::new();
+    let mut page_count = 0;
+    for edits in &edits.iter().chunks(12) {
+        let mut embed = EmbedBuilder::new()
+            .default_data()
+            .title(format!("Edit History: Event {}", args.event_id))
+            .description(format!("Page {}", page_count + 1));
+        for edit in edits {
+            embed = embed.field(EmbedFieldBuilder::new(
+                format!("<t:{}:f>", edit.timestamp.timestamp()),
+                format!("By <@{}>\n{}", edit.editor_id, edit.change),
+            ));
+        }
+        pages.push(embed.build()?);
+        page_count += 1;
+    }
+
+    if pages.is_empty() {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .title(format!("Edit History: Event {}", args.event_id))
+            .description("This event has not been edited")
+            .build()?;
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+    paginate_embed(&ctx, pages, page_count).await?;
+    Ok(())
+}
+
+/// Fetch an event that the author is allowed to change. Admins may change any event while
+/// trainers may only change the events they hosted. Failures are responded to here
+async fn editable_event(ctx: &CommandContext, event_id: i64) -> Result<Option<EventLog>, RoError> {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        beta_only(ctx).await?;
+        return Ok(None);
+    }
+
+    let event = match ctx
+        .bot
+        .database
+        .query_opt::<EventLog>(
+            "SELECT * FROM events WHERE guild_id = $1 AND guild_event_id = $2",
+            &[&guild_id, &event_id],
+        )
+        .await?
+    {
+        Some(e) => e,
+        None => {
+            respond_failure(
+                ctx,
+                "Event Edit Failed",
+                &format!("An event with id {} does not exist", event_id),
+            )
+            .await?;
+            return Ok(None);
+        }
+    };
+    if event.voided_at.is_some() {
+        respond_failure(
+            ctx,
+            "Event Edit Failed",
+            "This event has been voided and can no longer be changed",
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    let is_admin = match (
+        ctx.bot.cache.guild(guild_id),
+        ctx.bot.cache.member(guild_id, UserId(ctx.author.id)),
+    ) {
+        (Some(guild), Some(member)) => get_perm_level(&ctx.bot, &guild, &member) >= RoLevel::Admin,
+        _ => false,
+    };
+    if !is_admin {
+        let is_host = ctx
+            .bot
+            .database
+            .get_linked_user(UserId(ctx.author.id), guild_id)
+            .await?
+            .map_or(false, |u| u.roblox_id == event.host_id);
+        if !is_host {
+            respond_failure(
+                ctx,
+                "Event Edit Failed",
+                "Only the host or an admin may change this event",
+            )
+            .await?;
+            return Ok(None);
+        }
+    }
+    Ok(Some(event))
+}
+
+impl FromArg for EventEditField {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match arg.to_lowercase().as_str() {
+            "add" => Ok(EventEditField::AddAttendees),
+            "remove" => Ok(EventEditField::RemoveAttendees),
+            "type" => Ok(EventEditField::EventType),
+            "notes" => Ok(EventEditField::Notes),
+            _ => Err(ParseError("one of `add` `remove` `type` `notes`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("EventEditField unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
@@ -0,0 +1,375 @@
+use chrono::{DateTime, Duration as CDuration, NaiveDate, Utc};
+use itertools::Itertools;
+use rowifi_database::{blind_index, decrypt_bytes};
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{EventLog, EventType},
+    guild::GuildType,
+    id::GuildId,
+    roblox::id::UserId as RobloxUserId,
+};
+use std::collections::HashMap;
+use twilight_http::request::AttachmentFile;
+
+use crate::utils::{csv_field, ExportFormat};
+
+/// The most events shown by a single search
+const MAX_SEARCH_RESULTS: i64 = 60;
+
+/// The most events a single export may hold
+const MAX_EXPORT_EVENTS: i64 = 10_000;
+
+/// The largest file an export may produce, keeping under Discord's attachment limit
+const MAX_EXPORT_BYTES: usize = 7 * 1024 * 1024;
+
+#[derive(FromArgs)]
+pub struct EventExportArguments {
+    #[arg(help = "The format of the export. One of `CSV` `JSON`")]
+    pub format: ExportFormat,
+    #[arg(
+        help = "Filters such as `from:2022-01-01` `to:2022-01-31` `type:1` `host:RobloxName`",
+        rest
+    )]
+    pub filters: Option<String>,
+}
+
+pub async fn event_export(ctx: CommandContext, args: EventExportArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let mut from: Option<DateTime<Utc>> = None;
+    let mut to: Option<DateTime<Utc>> = None;
+    let mut event_type: Option<i32> = None;
+    let mut host: Option<i64> = None;
+    for filter in args.filters.unwrap_or_default().split_whitespace() {
+        let (key, value) = match filter.split_once(':') {
+            Some(f) => f,
+            None => {
+                return respond_failure(
+                    &ctx,
+                    "Event Export Failed",
+                    &format!("`{}` is not a valid filter", filter),
+                )
+                .await
+            }
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "from" | "to" => {
+                let date = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    Ok(d) => DateTime::<Utc>::from_utc(d.and_hms(0, 0, 0), Utc),
+                    Err(_) => {
+                        return respond_failure(
+                            &ctx,
+                            "Event Export Failed",
+                            &format!("`{}` is not a date of the form YYYY-MM-DD", value),
+                        )
+                        .await
+                    }
+                };
+                if key.eq_ignore_ascii_case("from") {
+                    from = Some(date);
+                } else {
+                    // The end date is inclusive
+                    to = Some(date + CDuration::days(1));
+                }
+            }
+            "type" => match value.parse::<i32>() {
+                Ok(t) => event_type = Some(t),
+                Err(_) => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Export Failed",
+                        "The event type id must be a number",
+                    )
+                    .await
+                }
+            },
+            "host" => match ctx.bot.roblox.get_user_from_username(value).await? {
+                Some(u) => host = Some(u.id.0 as i64),
+                None => {
+                    return respond_failure(
+                        &ctx,
+                        "Event Export Failed",
+                        &format!("`{}` does not have an associated roblox id", value),
+                    )
+                    .await
+                }
+            },
+            _ => {
+                return respond_failure(
+                    &ctx,
+                    "Event Export Failed",
+                    &format!("`{}` is not a valid filter", filter),
+                )
+                .await
+            }
+        }
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let events = ctx
+        .bot
+        .database
+        .query::<EventLog>(
+            "SELECT * FROM events WHERE guild_id = $1 AND ($2::TIMESTAMPTZ IS NULL OR timestamp >= $2) AND ($3::TIMESTAMPTZ IS NULL OR timestamp < $3) AND ($4::INTEGER IS NULL OR event_type = $4) AND ($5::BIGINT IS NULL OR host_id = $5) ORDER BY guild_event_id LIMIT $6",
+            &[&guild_id, &from, &to, &event_type, &host, &(MAX_EXPORT_EVENTS + 1)],
+        )
+        .await?;
+    if events.is_empty() {
+        return respond_failure(
+            &ctx,
+            "Event Export Failed",
+            "No events matched the given filters",
+        )
+        .await;
+    }
+    if events.len() as i64 > MAX_EXPORT_EVENTS {
+        return respond_failure(
+            &ctx, "Event Export Failed",
+            &format!(
+                "More than {} events matched. Use the `from` and `to` filters to export them in parts",
+                MAX_EXPORT_EVENTS
+            ),
+        )
+        .await;
+    }
+
+    let type_name = |id: i32| {
+        event_types
+            .iter()
+            .find(|e| e.event_type_guild_id == id)
+            .map_or_else(String::new, |e| e.name.clone())
+    };
+    let notes = events
+        .iter()
+        .map(|event| event_notes(&ctx, guild_id, event))
+        .collect::<Vec<_>>();
+    let (file_name, contents) = match args.format {
+        ExportFormat::Csv => {
+            let mut csv = String::from("event_id,event_type,event_type_name,host_id,timestamp,attendees,notes,fields,voided_at,void_reason\n");
+            for (event, notes) in events.iter().zip(&notes) {
+                let fields = event
+                    .fields
+                    .iter()
+                    .map(|f| format!("{}={}", f.name, f.value))
+                    .join("; ");
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{},{}\n",
+                    event.guild_event_id,
+                    event.event_type,
+                    csv_field(&type_name(event.event_type)),
+                    event.host_id,
+                    event.timestamp.to_rfc3339(),
+                    event.attendees.iter().join(" "),
+                    csv_field(notes.as_deref().unwrap_or_default()),
+                    csv_field(&fields),
+                    event.voided_at.map_or_else(String::new, |v| v.to_rfc3339()),
+                    csv_field(event.void_reason.as_deref().unwrap_or_default())
+                ));
+            }
+            ("events.csv", csv)
+        }
+        ExportFormat::Json => {
+            let rows = events
+                .iter()
+                .zip(&notes)
+                .map(|(event, notes)| {
+                    serde_json::json!({
+                        "event_id": event.guild_event_id,
+                        "event_type": event.event_type,
+                        "event_type_name": type_name(event.event_type),
+                        "host_id": event.host_id,
+                        "timestamp": event.timestamp.to_rfc3339(),
+                        "attendees": event.attendees,
+                        "notes": notes,
+                        "fields": event
+                            .fields
+                            .iter()
+                            .map(|f| (f.name.clone(), f.value.clone()))
+                            .collect::<HashMap<_, _>>(),
+                        "voided_at": event.voided_at.map(|v| v.to_rfc3339()),
+                        "void_reason": event.void_reason,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (
+                "events.json",
+                serde_json::to_string_pretty(&rows).unwrap_or_default(),
+            )
+        }
+    };
+
+    if contents.len() > MAX_EXPORT_BYTES {
+        return respond_failure(
+            &ctx, "Event Export Failed",
+            "The export is too large to upload. Use the `from` and `to` filters to export the events in parts",
+        )
+        .await;
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Export Successful")
+        .description(format!("Exported {} events", events.len()))
+        .build()
+        .unwrap();
+    ctx.respond()
+        .embeds(&[embed])?
+        .files(&[AttachmentFile::from_bytes(file_name, contents.as_bytes())])
+        .exec()
+        .await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Events Exported: {} events", events.len()))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventSearchArguments {
+    #[arg(
+        help = "The words to search for in the notes and type names of events",
+        rest
+    )]
+    pub query: String,
+}
+
+pub async fn event_search(ctx: CommandContext, args: EventSearchArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let query = args.query.trim();
+    if query.is_empty() {
+        return respond_failure(
+            &ctx,
+            "Event Search Failed",
+            "You must give something to search for",
+        )
+        .await;
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let matching_types = event_types
+        .iter()
+        .filter(|e| e.name.to_lowercase().contains(&query.to_lowercase()))
+        .map(|e| e.event_type_guild_id)
+        .collect::<Vec<_>>();
+    let terms = blind_index(query, ctx.bot.database.keyring.index_key(), guild_id.get());
+    if matching_types.is_empty() && terms.is_empty() {
+        return respond_failure(
+            &ctx,
+            "Event Search Failed",
+            "Searches must contain words of at least 3 characters",
+        )
+        .await;
+    }
+
+    // Every word of the query must appear in the notes for them to match
+    let events = ctx
+        .bot
+        .database
+        .query::<EventLog>(
+            "SELECT * FROM events WHERE guild_id = $1 AND voided_at IS NULL AND (event_type = ANY($2) OR (cardinality($3::BYTEA[]) > 0 AND notes_index @> $3)) ORDER BY timestamp DESC LIMIT $4",
+            &[&guild_id, &matching_types, &terms, &MAX_SEARCH_RESULTS],
+        )
+        .await?;
+    if events.is_empty() {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .title("Event Search")
+            .description("No events matched the search")
+            .build()?;
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+
+    let host_ids = events
+        .iter()
+        .map(|e| RobloxUserId(e.host_id as u64))
+        .unique()
+        .collect::<Vec<_>>();
+    let mut hosts = HashMap::new();
+    for chunk in host_ids.chunks(100) {
+        for user in ctx.bot.roblox.get_users(chunk).await? {
+            hosts.insert(user.id.0 as i64, user.name);
+        }
+    }
+
+    let mut pages = Vec::new();
+    let mut page_count = 0;
+    for events in events.chunks(12) {
+        let mut embed = EmbedBuilder::new()
+            .default_data()
+            .title("Event Search")
+            .description(format!("Page {}", page_count + 1));
+        for event in events {
+            let event_type = event_types
+                .iter()
+                .find(|e| e.event_type_guild_id == event.event_type)
+                .map_or_else(|| event.event_type.to_string(), |e| e.name.clone());
+            let host = hosts
+                .get(&event.host_id)
+                .cloned()
+                .unwrap_or_else(|| event.host_id.to_string());
+            let desc = format!(
+                "Event Type: {}\nHost: {}\nTimestamp: <t:{}:f>",
+                event_type,
+                host,
+                event.timestamp.timestamp()
+            );
+            embed = embed.field(
+                EmbedFieldBuilder::new(format!("Id: {}", event.guild_event_id), desc).inline(),
+            );
+        }
+        pages.push(embed.build()?);
+        page_count += 1;
+    }
+    paginate_embed(&ctx, pages, page_count).await?;
+    Ok(())
+}
+
+/// The decrypted notes of an event. Notes that fail to decrypt are logged and treated as empty so
+/// that one bad row does not fail the whole export
+fn event_notes(ctx: &CommandContext, guild_id: GuildId, event: &EventLog) -> Option<String> {
+    let notes = event.notes.as_ref()?;
+    match decrypt_bytes(
+        notes,
+        &ctx.bot.database.keyring,
+        guild_id.get(),
+        event.host_id as u64,
+        event.notes_timestamp().timestamp() as u64,
+    ) {
+        Ok(notes) => Some(String::from_utf8_lossy(&notes).into_owned()),
+        Err(err) => {
+            tracing::error!(err = ?err, guild_id = ?guild_id, event_id = event.guild_event_id, "Error in decrypting event notes");
+            None
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use itertools::Itertools;
+use rowifi_database::postgres::types::Json;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{EventFieldKind, EventFieldSchema, EventType},
+    guild::GuildType,
+    id::RoleId,
+};
+
+/// The most custom fields an event type may have
+const MAX_FIELDS: usize = 10;
+
+#[derive(FromArgs)]
+pub struct EventTypeHostsArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_id: i32,
+    #[arg(
+        help = "The roles allowed to log this event type. Leave empty to allow any trainer",
+        rest
+    )]
+    pub roles: Option<String>,
+}
+
+pub async fn event_type_hosts(ctx: CommandContext, args: EventTypeHostsArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let event_type = match fetch_event_type(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let server_roles = ctx.bot.cache.roles(guild_id);
+    let mut host_roles = Vec::new();
+    for role in args.roles.unwrap_or_default().split_ascii_whitespace() {
+        match parse_role(role) {
+            Some(role_id) if server_roles.contains(&role_id) => {
+                if !host_roles.contains(&role_id) {
+                    host_roles.push(role_id);
+                }
+            }
+            _ => {
+                return respond_failure(
+                    &ctx,
+                    "Event Type Modification Failed",
+                    &format!("`{}` is not a role in this server", role),
+                )
+                .await
+            }
+        }
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE event_types SET host_roles = $1 WHERE event_type_id = $2",
+            &[&host_roles, &event_type.event_type_id],
+        )
+        .await?;
+
+    let name = format!("Event Type Id: {}", event_type.event_type_guild_id);
+    let desc = format!(
+        "Host Roles: {} -> {}",
+        host_roles_list(&event_type.host_roles),
+        host_roles_list(&host_roles)
+    );
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Type Modification Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Type Host Roles Modified")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventTypeFieldsArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_id: i32,
+}
+
+pub async fn event_type_fields(
+    ctx: CommandContext,
+    args: EventTypeFieldsArguments,
+) -> CommandResult {
+    let event_type = match fetch_event_type(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Event Type Fields: {}", event_type.name))
+        .field(EmbedFieldBuilder::new(
+            "Host Roles",
+            host_roles_list(&event_type.host_roles),
+        ));
+    if event_type.fields.is_empty() {
+        embed = embed.description("This event type has no custom fields");
+    }
+    for field in &event_type.fields {
+        let mut desc = format!("Kind: {}", field.kind);
+        if field.kind == EventFieldKind::Choice {
+            desc.push_str(&format!("\nOptions: {}", field.options.join(", ")));
+        }
+        embed = embed.field(EmbedFieldBuilder::new(field.name.clone(), desc).inline());
+    }
+    ctx.respond().embeds(&[embed.build()?])?.exec().await?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventTypeFieldAddArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_id: i32,
+    #[arg(help = "The kind of the field. One of `Text` `Duration` `Choice`")]
+    pub kind: EventFieldKind,
+    #[arg(help = "The name of the field, such as `Location` or `Outcome`")]
+    pub name: String,
+    #[arg(help = "The comma separated answers of a `Choice` field", rest)]
+    pub options: Option<String>,
+}
+
+pub async fn event_type_field_add(
+    ctx: CommandContext,
+    args: EventTypeFieldAddArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let event_type = match fetch_event_type(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    if event_type.fields.len() >= MAX_FIELDS {
+        return respond_failure(
+            &ctx,
+            "Event Type Modification Failed",
+            &format!("An event type may have at most {} fields", MAX_FIELDS),
+        )
+        .await;
+    }
+    if args.name.len() > 32 {
+        return respond_failure(
+            &ctx,
+            "Event Type Modification Failed",
+            "Field names may be at most 32 characters long",
+        )
+        .await;
+    }
+    if event_type
+        .fields
+        .iter()
+        .any(|f| f.name.eq_ignore_ascii_case(&args.name))
+    {
+        return respond_failure(
+            &ctx,
+            "Event Type Modification Failed",
+            &format!("A field named `{}` already exists", args.name),
+        )
+        .await;
+    }
+
+    let options = if args.kind == EventFieldKind::Choice {
+        let options = args
+            .options
+            .unwrap_or_default()
+            .split(',')
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .unique()
+            .collect::<Vec<_>>();
+        if !(2..=25).contains(&options.len()) {
+            return respond_failure(
+                &ctx,
+                "Event Type Modification Failed",
+                "A choice field must have between 2 and 25 comma separated options",
+            )
+            .await;
+        }
+        options
+    } else {
+        Vec::new()
+    };
+
+    let field = EventFieldSchema {
+        name: args.name,
+        kind: args.kind,
+        options,
+    };
+    let mut fields = event_type.fields.clone();
+    fields.push(field.clone());
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE event_types SET fields = $1 WHERE event_type_id = $2",
+            &[&Json(&fields), &event_type.event_type_id],
+        )
+        .await?;
+
+    let name = format!("Event Type Id: {}", event_type.event_type_guild_id);
+    let desc = format!("Added Field: {} ({})", field.name, field.kind);
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Type Modification Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Type Field Added")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventTypeFieldRemoveArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_id: i32,
+    #[arg(help = "The name of the field to remove")]
+    pub name: String,
+}
+
+pub async fn event_type_field_remove(
+    ctx: CommandContext,
+    args: EventTypeFieldRemoveArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let event_type = match fetch_event_type(&ctx, args.event_id).await? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let mut fields = event_type.fields.clone();
+    let before = fields.len();
+    fields.retain(|f| !f.name.eq_ignore_ascii_case(&args.name));
+    if fields.len() == before {
+        return respond_failure(
+            &ctx,
+            "Event Type Modification Failed",
+            &format!("A field named `{}` does not exist", args.name),
+        )
+        .await;
+    }
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE event_types SET fields = $1 WHERE event_type_id = $2",
+            &[&Json(&fields), &event_type.event_type_id],
+        )
+        .await?;
+
+    // Answers already logged are kept on the events so that past logs stay intact
+    let name = format!("Event Type Id: {}", event_type.event_type_guild_id);
+    let desc = format!("Removed Field: {}", args.name);
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Type Modification Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Type Field Removed")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+async fn fetch_event_type(
+    ctx: &CommandContext,
+    event_id: i32,
+) -> Result<Option<EventType>, RoError> {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        beta_only(ctx).await?;
+        return Ok(None);
+    }
+
+    let event_type = ctx
+        .bot
+        .database
+        .query_opt::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+            &[&guild_id, &event_id],
+        )
+        .await?;
+    if event_type.is_none() {
+        respond_failure(
+            ctx,
+            "Event Type Modification Failed",
+            &format!("An event type with id {} does not exist", event_id),
+        )
+        .await?;
+    }
+    Ok(event_type)
+}
+
+fn host_roles_list(roles: &[RoleId]) -> String {
+    if roles.is_empty() {
+        "Any Trainer".into()
+    } else {
+        roles.iter().map(|r| format!("<@&{}>", r)).join(" ")
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{Duration as CDuration, Utc};
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{EventLeaderboardEntry, EventType},
+    guild::GuildType,
+    roblox::id::UserId as RobloxUserId,
+};
+use std::collections::HashMap;
+
+use crate::utils::parse_duration;
+
+#[derive(FromArgs)]
+pub struct EventLeaderboardArguments {
+    #[arg(help = "The window to rank over such as `7d`, `2w` or `3m`. Leave empty for all time")]
+    pub window: Option<EventWindow>,
+    #[arg(help = "The id of the event type to rank. Leave empty for all event types")]
+    pub event_type: Option<i32>,
+}
+
+pub struct EventWindow(pub CDuration);
+
+/// The longest window a leaderboard may be ranked over, in days
+const MAX_WINDOW_DAYS: i64 = 365;
+
+#[derive(Clone, Copy)]
+enum LeaderboardKind {
+    Hosts,
+    Attendees,
+}
+
+pub async fn event_leaderboard_hosts(
+    ctx: CommandContext,
+    args: EventLeaderboardArguments,
+) -> CommandResult {
+    event_leaderboard(ctx, args, LeaderboardKind::Hosts).await
+}
+
+pub async fn event_leaderboard_attendees(
+    ctx: CommandContext,
+    args: EventLeaderboardArguments,
+) -> CommandResult {
+    event_leaderboard(ctx, args, LeaderboardKind::Attendees).await
+}
+
+async fn event_leaderboard(
+    ctx: CommandContext,
+    args: EventLeaderboardArguments,
+    kind: LeaderboardKind,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let event_type = match args.event_type {
+        Some(event_type_id) => {
+            match event_types
+                .iter()
+                .find(|e| e.event_type_guild_id == event_type_id)
+            {
+                Some(e) => Some(e),
+                None => {
+                    let embed = EmbedBuilder::new()
+                        .default_data()
+                        .color(Color::Red as u32)
+                        .title("Event Leaderboard Failed")
+                        .description(format!(
+                            "An event type with id {} does not exist",
+                            event_type_id
+                        ))
+                        .build()
+                        .unwrap();
+                    ctx.respond().embeds(&[embed])?.exec().await?;
+                    return Ok(());
+                }
+            }
+        }
+        None => None,
+    };
+
+    let event_type_id = event_type.map(|e| e.event_type_guild_id);
+    let since = args.window.as_ref().map(|w| Utc::now() - w.0);
+    let statement = match kind {
+        LeaderboardKind::Hosts => {
+            "SELECT host_id AS roblox_id, COUNT(*) AS count FROM events WHERE guild_id = $1 AND voided_at IS NULL AND ($2::INTEGER IS NULL OR event_type = $2) AND ($3::TIMESTAMPTZ IS NULL OR timestamp >= $3) GROUP BY host_id ORDER BY count DESC, host_id LIMIT 10"
+        }
+        LeaderboardKind::Attendees => {
+            "SELECT attendee AS roblox_id, COUNT(*) AS count FROM events, unnest(attendees) AS attendee WHERE guild_id = $1 AND voided_at IS NULL AND ($2::INTEGER IS NULL OR event_type = $2) AND ($3::TIMESTAMPTZ IS NULL OR timestamp >= $3) GROUP BY attendee ORDER BY count DESC, attendee LIMIT 10"
+        }
+    };
+    let entries = ctx
+        .bot
+        .database
+        .query::<EventLeaderboardEntry>(statement, &[&(guild_id), &event_type_id, &since])
+        .await?;
+
+    let user_ids = entries
+        .iter()
+        .map(|e| RobloxUserId(e.roblox_id as u64))
+        .collect::<Vec<_>>();
+    let names = if user_ids.is_empty() {
+        HashMap::new()
+    } else {
+        ctx.bot
+            .roblox
+            .get_users(&user_ids)
+            .await?
+            .into_iter()
+            .map(|u| (u.id.0 as i64, u.name))
+            .collect::<HashMap<_, _>>()
+    };
+
+    let (title, unit) = match kind {
+        LeaderboardKind::Hosts => ("Top Hosts", "hosted"),
+        LeaderboardKind::Attendees => ("Top Attendees", "attended"),
+    };
+    let window = args.window.as_ref().map_or_else(
+        || "All Time".into(),
+        |w| format!("Last {} days", w.0.num_days()),
+    );
+    let event_type_name = event_type.map_or("All Event Types", |e| e.name.as_str());
+
+    let mut description = String::new();
+    for (rank, entry) in entries.iter().enumerate() {
+        let name = names
+            .get(&entry.roblox_id)
+            .cloned()
+            .unwrap_or_else(|| entry.roblox_id.to_string());
+        description.push_str(&format!(
+            "**{}.** {} - {} {}\n",
+            rank + 1,
+            name,
+            entry.count,
+            unit
+        ));
+    }
+    if description.is_empty() {
+        description = "No events have been logged in this window".into();
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("{}: {}", title, event_type_name))
+        .description(description)
+        .footer(EmbedFooterBuilder::new(window))
+        .build()?;
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
+
+impl FromArg for EventWindow {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        let units = [
+            ('d', CDuration::days(1)),
+            ('w', CDuration::weeks(1)),
+            ('m', CDuration::days(30)),
+        ];
+        parse_duration(arg, &units, CDuration::days(MAX_WINDOW_DAYS))
+            .map(EventWindow)
+            .ok_or(ParseError(
+                "a window of at most a year such as `7d` `2w` `3m`",
+            ))
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::Integer(value) => value.to_string(),
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("EventWindow unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
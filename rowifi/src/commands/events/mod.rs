@@ -1,14 +1,35 @@
+mod edit;
+mod export;
+mod fields;
+mod leaderboard;
 mod new;
+mod points;
+mod quota;
 mod reset;
+mod schedule;
+mod stats;
 mod summary;
 mod types;
 mod view;
 
-use rowifi_framework::prelude::*;
-use rowifi_models::guild::GuildType;
+use rowifi_framework::{get_perm_level, prelude::*};
+use rowifi_models::{events::EventType, guild::GuildType, id::UserId};
 
+use edit::{event_edit, event_history, event_void};
+use export::{event_export, event_search};
+use fields::{event_type_field_add, event_type_field_remove, event_type_fields, event_type_hosts};
+use leaderboard::{event_leaderboard_attendees, event_leaderboard_hosts};
 use new::events_new;
+use points::{
+    event_promotions, event_ranks, event_ranks_delete, event_ranks_set, event_type_points,
+};
+use quota::{
+    event_quota, event_quota_channel, event_quota_delete, event_quota_exempt, event_quota_new,
+    event_quota_report,
+};
 use reset::event_reset;
+use schedule::{event_cancel, event_schedule, event_upcoming};
+use stats::event_stats;
 use summary::event_summary;
 use types::{event_type, event_type_disable, event_type_enable, event_type_modify, event_type_new};
 use view::{event_attendee, event_host, event_view};
@@ -38,6 +59,38 @@ pub fn events_config(cmds: &mut Vec<Command>) {
         .description("Command to enable an event type for logging")
         .handler(event_type_enable);
 
+    let event_types_points_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["points"])
+        .description("Command to set the points awarded for hosting and attending an event type")
+        .handler(event_type_points);
+
+    let event_types_hosts_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["hosts"])
+        .description("Command to set the roles allowed to log an event type")
+        .handler(event_type_hosts);
+
+    let event_types_fields_add_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["add", "new"])
+        .description("Command to add a custom field to an event type")
+        .handler(event_type_field_add);
+
+    let event_types_fields_remove_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["remove", "delete", "d"])
+        .description("Command to remove a custom field from an event type")
+        .handler(event_type_field_remove);
+
+    let event_types_fields_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["fields", "field"])
+        .description("Command to view the custom fields and host roles of an event type")
+        .sub_command(event_types_fields_add_cmd)
+        .sub_command(event_types_fields_remove_cmd)
+        .handler(event_type_fields);
+
     let event_types_view_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["view"])
@@ -52,6 +105,9 @@ pub fn events_config(cmds: &mut Vec<Command>) {
         .sub_command(event_types_modify_cmd)
         .sub_command(event_types_disable_cmd)
         .sub_command(event_types_enable_cmd)
+        .sub_command(event_types_points_cmd)
+        .sub_command(event_types_hosts_cmd)
+        .sub_command(event_types_fields_cmd)
         .sub_command(event_types_view_cmd)
         .handler(event_type);
 
@@ -61,6 +117,42 @@ pub fn events_config(cmds: &mut Vec<Command>) {
         .description("Command for users with `RoWifi Trainer` to log an event")
         .handler(events_new);
 
+    let events_schedule_cmd = Command::builder()
+        .level(RoLevel::Trainer)
+        .names(&["schedule"])
+        .description("Command for users with `RoWifi Trainer` to schedule an event that members can RSVP and check in to")
+        .handler(event_schedule);
+
+    let events_upcoming_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["upcoming", "sessions"])
+        .description("Command to view the upcoming scheduled events")
+        .handler(event_upcoming);
+
+    let events_cancel_cmd = Command::builder()
+        .level(RoLevel::Trainer)
+        .names(&["cancel"])
+        .description("Command to cancel a scheduled event")
+        .handler(event_cancel);
+
+    let events_edit_cmd = Command::builder()
+        .level(RoLevel::Trainer)
+        .names(&["edit"])
+        .description("Command for the host or an admin to edit the attendees, type or notes of a logged event")
+        .handler(event_edit);
+
+    let events_void_cmd = Command::builder()
+        .level(RoLevel::Trainer)
+        .names(&["void"])
+        .description("Command for the host or an admin to void a logged event")
+        .handler(event_void);
+
+    let events_history_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["history"])
+        .description("Command to view the edit history of a logged event")
+        .handler(event_history);
+
     let events_attendee_cmd = Command::builder()
         .level(RoLevel::Normal)
         .names(&["attendee"])
@@ -79,6 +171,18 @@ pub fn events_config(cmds: &mut Vec<Command>) {
         .description("Command to view information about a specific event")
         .handler(event_view);
 
+    let events_search_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["search"])
+        .description("Command to search the notes and types of logged events")
+        .handler(event_search);
+
+    let events_export_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["export"])
+        .description("Command to export the event logs as a CSV or JSON file")
+        .handler(event_export);
+
     let events_reset_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["reset"])
@@ -88,21 +192,127 @@ pub fn events_config(cmds: &mut Vec<Command>) {
     let events_summary_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["summary"])
-        .description("Command to view the summary of all logged events")
+        .description("Command to view the summary of all logged events, optionally as charts")
         .handler(event_summary);
 
+    let events_leaderboard_hosts_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["hosts", "host"])
+        .description("Command to view the members who hosted the most events")
+        .handler(event_leaderboard_hosts);
+
+    let events_leaderboard_attendees_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["attendees", "attendee"])
+        .description("Command to view the members who attended the most events")
+        .handler(event_leaderboard_attendees);
+
+    let events_leaderboard_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["leaderboard", "lb", "top"])
+        .description("Command to view the top attendees and hosts of the server")
+        .sub_command(events_leaderboard_hosts_cmd)
+        .sub_command(events_leaderboard_attendees_cmd)
+        .handler(event_leaderboard_attendees);
+
+    let events_quota_new_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["new", "add"])
+        .description("Command to add an event quota for a role")
+        .handler(event_quota_new);
+
+    let events_quota_delete_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["delete", "remove", "d"])
+        .description("Command to delete an event quota")
+        .handler(event_quota_delete);
+
+    let events_quota_channel_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["channel"])
+        .description("Command to set the channel weekly quota reports are posted to")
+        .handler(event_quota_channel);
+
+    let events_quota_exempt_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["exempt"])
+        .description("Command to toggle a role's exemption from event quotas")
+        .handler(event_quota_exempt);
+
+    let events_quota_report_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["report"])
+        .description("Command to generate a quota compliance report")
+        .handler(event_quota_report);
+
+    let events_quota_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["quota", "quotas"])
+        .description("Command to view the event quotas of the server")
+        .sub_command(events_quota_new_cmd)
+        .sub_command(events_quota_delete_cmd)
+        .sub_command(events_quota_channel_cmd)
+        .sub_command(events_quota_exempt_cmd)
+        .sub_command(events_quota_report_cmd)
+        .handler(event_quota);
+
+    let events_ranks_set_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["set", "new", "add"])
+        .description("Command to set the points needed to be recommended for a group rank")
+        .handler(event_ranks_set);
+
+    let events_ranks_delete_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["delete", "remove", "d"])
+        .description("Command to delete a rank threshold")
+        .handler(event_ranks_delete);
+
+    let events_ranks_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["ranks", "thresholds"])
+        .description("Command to view the points needed for each group rank")
+        .sub_command(events_ranks_set_cmd)
+        .sub_command(events_ranks_delete_cmd)
+        .handler(event_ranks);
+
+    let events_promotions_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["promotions"])
+        .description("Command to list the members whose points qualify them for their next rank")
+        .handler(event_promotions);
+
+    let events_stats_cmd = Command::builder()
+        .level(RoLevel::Normal)
+        .names(&["stats"])
+        .description("Command to view the attendance and hosting statistics of a member")
+        .handler(event_stats);
+
     let events_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["event", "events"])
         .description("Module to interact with the events subsystem")
         .group("Premium")
         .sub_command(events_new_cmd)
+        .sub_command(events_schedule_cmd)
+        .sub_command(events_upcoming_cmd)
+        .sub_command(events_cancel_cmd)
+        .sub_command(events_edit_cmd)
+        .sub_command(events_void_cmd)
+        .sub_command(events_history_cmd)
         .sub_command(event_types_cmd)
         .sub_command(events_attendee_cmd)
         .sub_command(events_host_cmd)
         .sub_command(events_view_cmd)
+        .sub_command(events_search_cmd)
+        .sub_command(events_export_cmd)
         .sub_command(events_reset_cmd)
         .sub_command(events_summary_cmd)
+        .sub_command(events_leaderboard_cmd)
+        .sub_command(events_stats_cmd)
+        .sub_command(events_quota_cmd)
+        .sub_command(events_ranks_cmd)
+        .sub_command(events_promotions_cmd)
         .handler(events);
     cmds.push(events_cmd);
 }
@@ -112,25 +322,41 @@ pub async fn events(ctx: CommandContext) -> CommandResult {
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let embed = EmbedBuilder::new().default_data()
         .title("Events Module")
         .description("An amazing module of RoWifi to allow your members to log events they host and for you to track them")
-        .field(EmbedFieldBuilder::new("Event Types", "To register a new event type: `!event type new <Event Id> <Event Name>`\nTo modify an existing event type: `!event type modify <Event Id> <Event Name>`"))
-        .field(EmbedFieldBuilder::new("For Trainers", "To add a new event: `!event new`"))
-        .field(EmbedFieldBuilder::new("Viewing Events", "To see the last 12 events attended by the member: `!event attendee [RobloxName]`\nTo see the last 12 events hosted by the member: `!event host [RobloxName]`\nTo view specific information about an event: `!event view <Event Id>`"))
+        .field(EmbedFieldBuilder::new("Event Types", "To register a new event type: `!event type new <Event Id> <Event Name>`\nTo modify an existing event type: `!event type modify <Event Id> <Event Name>`\nTo restrict who may log an event type: `!event type hosts <Event Id> [Roles]`\nTo add a custom field: `!event type fields add <Event Id> <Text|Duration|Choice> <Name> [Options]`"))
+        .field(EmbedFieldBuilder::new("For Trainers", "To add a new event: `!event new`\nTo schedule an event with RSVPs and check-in: `!event schedule <Event Type Id> <Start> [Check-In Minutes]`\nTo edit an event you hosted: `!event edit <Event Id> <add|remove|type|notes> <Value>`\nTo void an event you hosted: `!event void <Event Id> [Reason]`"))
+        .field(EmbedFieldBuilder::new("Viewing Events", "To see the last 12 events attended by the member: `!event attendee [RobloxName]`\nTo see the last 12 events hosted by the member: `!event host [RobloxName]`\nTo view specific information about an event: `!event view <Event Id>`\nTo view the edit history of an event: `!event history <Event Id>`\nTo search the notes and types of events: `!event search <Text>`\nTo export the event logs: `!event export <CSV|JSON> [from:Date] [to:Date] [type:Id] [host:RobloxName]`"))
+        .field(EmbedFieldBuilder::new("Statistics", "To see the top attendees or hosts: `!event leaderboard <attendees|hosts> [Window] [Event Type Id]`\nTo see the statistics of a member: `!event stats [RobloxName]`\nTo chart the events of the server: `!event summary --chart [Window]`"))
+        .field(EmbedFieldBuilder::new("Quotas", "To add a quota: `!event quota new <Role> <Host|Attend> <Required> <Days> [Event Type Id]`\nTo set the weekly report channel: `!event quota channel <Channel>`\nTo generate a report now: `!event quota report`"))
+        .field(EmbedFieldBuilder::new("Points & Promotions", "To set the points of an event type: `!event type points <Event Type Id> <Host Points> <Attend Points>`\nTo set the points needed for a rank: `!event ranks set <Group Id> <Rank> <Points>`\nTo see who is due for a promotion: `!event promotions [Group Id]`"))
         .build()?;
     ctx.respond().embeds(&[embed])?.exec().await?;
 
     Ok(())
 }
+
+/// Whether the author may log events of the given type. Admins may log any event type
+pub fn can_host_event(ctx: &CommandContext, event_type: &EventType) -> bool {
+    if event_type.host_roles.is_empty() {
+        return true;
+    }
+    let guild_id = ctx.guild_id.unwrap();
+    match (
+        ctx.bot.cache.guild(guild_id),
+        ctx.bot.cache.member(guild_id, UserId(ctx.author.id)),
+    ) {
+        (Some(guild), Some(member)) => {
+            get_perm_level(&ctx.bot, &guild, &member) >= RoLevel::Admin
+                || member
+                    .roles
+                    .iter()
+                    .any(|r| event_type.host_roles.contains(r))
+        }
+        _ => false,
+    }
+}
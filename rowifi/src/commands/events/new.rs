@@ -1,26 +1,27 @@
-use rowifi_database::{encrypt_bytes, postgres::Row};
+use rowifi_database::{
+    blind_index, encrypt_bytes,
+    postgres::{types::Json, Row},
+};
 use rowifi_framework::prelude::*;
 use rowifi_models::{
-    events::{EventLog, EventType},
+    events::{EventFieldKind, EventFieldValue, EventLog, EventType},
     guild::GuildType,
     id::{EventId, UserId},
 };
 use std::time::Duration;
 
+use super::can_host_event;
+use crate::utils::{resolve_attendees, truncated_list};
+
+/// The longest answer a text field of an event may hold
+const MAX_TEXT_ANSWER_LENGTH: usize = 256;
+
 pub async fn events_new(ctx: CommandContext) -> CommandResult {
     let guild_id = ctx.guild_id.unwrap();
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let roblox_id = match ctx
@@ -54,7 +55,7 @@ pub async fn events_new(ctx: CommandContext) -> CommandResult {
 
     let mut options = Vec::new();
     for event_type in &event_types {
-        if !event_type.disabled {
+        if !event_type.disabled && can_host_event(&ctx, event_type) {
             options.push(SelectMenuOption {
                 default: false,
                 description: None,
@@ -70,7 +71,7 @@ pub async fn events_new(ctx: CommandContext) -> CommandResult {
             .default_data()
             .color(Color::Red as u32)
             .title("Event Addition Failed")
-            .description("There are no event types or all event types are disabled")
+            .description("There are no enabled event types that you are allowed to log")
             .build()
             .unwrap();
         ctx.respond().embeds(&[embed])?.exec().await?;
@@ -194,41 +195,126 @@ pub async fn events_new(ctx: CommandContext) -> CommandResult {
         .unwrap();
 
     let attendees_str = await_reply(
-        "Enter the usernames of Roblox Users who attended this event",
+        "Enter the attendees of this event. You may give Roblox usernames, mention members or roles, mention a voice channel or say `vc` to add everyone in your voice channel",
         &ctx,
     )
     .await?;
-    let mut attendees = Vec::new();
-    for attendee in attendees_str.split(|c| c == ' ' || c == ',') {
-        if let Ok(Some(roblox_id)) = ctx.bot.roblox.get_user_from_username(attendee).await {
-            attendees.push(roblox_id);
-        }
-    }
+    let resolved =
+        resolve_attendees(&ctx.bot, guild_id, UserId(ctx.author.id), &attendees_str).await?;
+    let unverified = resolved
+        .unverified
+        .iter()
+        .map(|u| format!("<@{}>", u))
+        .collect::<Vec<_>>();
 
-    if attendees.is_empty() {
-        let embed = EmbedBuilder::new()
+    if resolved.roblox_ids.is_empty() {
+        let mut embed = EmbedBuilder::new()
             .default_data()
             .color(Color::Red as u32)
             .title("Event Addition Failed")
-            .description("The number of valid attendees was found to be zero")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
+            .description("The number of valid attendees was found to be zero");
+        if !unverified.is_empty() {
+            embed = embed.field(EmbedFieldBuilder::new(
+                "Not Verified",
+                truncated_list(&unverified),
+            ));
+        }
+        if !resolved.invalid.is_empty() {
+            embed = embed.field(EmbedFieldBuilder::new(
+                "Not Found",
+                truncated_list(&resolved.invalid),
+            ));
+        }
+        ctx.respond().embeds(&[embed.build()?])?.exec().await?;
         return Ok(());
     }
+
+    let mut fields = Vec::new();
+    for field in &event_type.fields {
+        let question = match field.kind {
+            EventFieldKind::Text => format!(
+                "Enter the {} of this event. Say N/A to leave it empty",
+                field.name
+            ),
+            EventFieldKind::Duration => format!(
+                "Enter the {} of this event in minutes. Say N/A to leave it empty",
+                field.name
+            ),
+            EventFieldKind::Choice => format!(
+                "Enter the {} of this event. One of: {}. Say N/A to leave it empty",
+                field.name,
+                field.options.join(", ")
+            ),
+        };
+        let answer = await_reply(&question, &ctx).await?;
+        let answer = answer.trim();
+        if answer.eq_ignore_ascii_case("N/A") {
+            continue;
+        }
+        if field.kind == EventFieldKind::Text && answer.chars().count() > MAX_TEXT_ANSWER_LENGTH {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Event Addition Failed")
+                .description(format!(
+                    "The {} may be at most {} characters long",
+                    field.name, MAX_TEXT_ANSWER_LENGTH
+                ))
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+        let value = match field.kind {
+            EventFieldKind::Text => Some(answer.to_string()),
+            EventFieldKind::Duration => {
+                answer.parse::<u32>().ok().map(|m| format!("{} minutes", m))
+            }
+            EventFieldKind::Choice => field
+                .options
+                .iter()
+                .find(|o| o.eq_ignore_ascii_case(answer))
+                .cloned(),
+        };
+        match value {
+            Some(value) => fields.push(EventFieldValue {
+                name: field.name.clone(),
+                value,
+            }),
+            None => {
+                let embed = EmbedBuilder::new()
+                    .default_data()
+                    .color(Color::Red as u32)
+                    .title("Event Addition Failed")
+                    .description(format!(
+                        "`{}` is not a valid answer for {}",
+                        answer, field.name
+                    ))
+                    .build()
+                    .unwrap();
+                ctx.respond().embeds(&[embed])?.exec().await?;
+                return Ok(());
+            }
+        }
+    }
+
     let timestamp = chrono::Utc::now();
 
     let notes_raw = await_reply("Would you like to add any notes to this event log? Say N/A if you would like to not add any notes", &ctx).await?;
-    let notes = if notes_raw.eq_ignore_ascii_case("N/A") {
-        None
+    let (notes, notes_index) = if notes_raw.eq_ignore_ascii_case("N/A") {
+        (None, Vec::new())
     } else {
-        Some(encrypt_bytes(
+        let notes = encrypt_bytes(
             notes_raw.as_bytes(),
-            &ctx.bot.database.cipher,
+            &ctx.bot.database.keyring,
+            guild_id.get(),
+        )?;
+        let notes_index = blind_index(
+            &notes_raw,
+            ctx.bot.database.keyring.index_key(),
             guild_id.get(),
-            roblox_id as u64,
-            timestamp.timestamp() as u64,
-        ))
+        );
+        (Some(notes), notes_index)
     };
 
     let new_event = EventLog {
@@ -237,16 +323,20 @@ pub async fn events_new(ctx: CommandContext) -> CommandResult {
         event_type: event_type.event_type_guild_id,
         guild_event_id: 0,
         host_id: roblox_id,
-        attendees: attendees.iter().map(|a| a.id.0 as i64).collect(),
+        attendees: resolved.roblox_ids,
         timestamp,
         notes,
+        notes_updated_at: None,
+        voided_at: None,
+        void_reason: None,
+        fields,
     };
 
     let row = ctx.bot.database.query_one::<Row>(
-        r#"INSERT INTO events(guild_id, event_type, guild_event_id, host_id, timestamp, attendees, notes)
-        VALUES($1, $2, (SELECT COALESCE(max(guild_event_id) + 1, 1) FROM events WHERE guild_id = $1), $3, $4, $5, $6)
+        r#"INSERT INTO events(guild_id, event_type, guild_event_id, host_id, timestamp, attendees, notes, fields, notes_index)
+        VALUES($1, $2, (SELECT COALESCE(max(guild_event_id) + 1, 1) FROM events WHERE guild_id = $1), $3, $4, $5, $6, $7, $8)
         RETURNING guild_event_id"#,
-        &[&new_event.guild_id, &new_event.event_type, &new_event.host_id, &new_event.timestamp, &new_event.attendees, &new_event.notes]
+        &[&new_event.guild_id, &new_event.event_type, &new_event.host_id, &new_event.timestamp, &new_event.attendees, &new_event.notes, &Json(&new_event.fields), &notes_index]
     ).await?;
 
     let value = format!(
@@ -255,16 +345,27 @@ pub async fn events_new(ctx: CommandContext) -> CommandResult {
         event_type.name,
         new_event.attendees.len()
     );
-    let embed = EmbedBuilder::new()
+    let mut embed = EmbedBuilder::new()
         .default_data()
         .color(Color::DarkGreen as u32)
         .title("Event Addition Successful")
         .field(EmbedFieldBuilder::new(
             format!("Event Id: {}", row.get::<'_, _, i64>("guild_event_id")),
             value,
-        ))
-        .build()
-        .unwrap();
-    ctx.respond().embeds(&[embed])?.exec().await?;
+        ));
+    // Members who could not be mapped to a roblox account are left out of the log
+    if !unverified.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Not Verified",
+            truncated_list(&unverified),
+        ));
+    }
+    if !resolved.invalid.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Not Found",
+            truncated_list(&resolved.invalid),
+        ));
+    }
+    ctx.respond().embeds(&[embed.build()?])?.exec().await?;
     Ok(())
 }
@@ -0,0 +1,434 @@
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{EventRankThreshold, EventType},
+    guild::GuildType,
+    roblox::id::{GroupId, UserId as RobloxUserId},
+};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::utils::event_points;
+
+/// The most members checked for a promotion in a single report. Each one costs a roblox request
+const MAX_CANDIDATES: usize = 100;
+
+#[derive(FromArgs)]
+pub struct EventTypePointsArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_id: i32,
+    #[arg(help = "The points awarded to the host")]
+    pub host_points: i32,
+    #[arg(help = "The points awarded to each attendee")]
+    pub attend_points: i32,
+}
+
+pub async fn event_type_points(
+    ctx: CommandContext,
+    args: EventTypePointsArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    if !(0..=1000).contains(&args.host_points) || !(0..=1000).contains(&args.attend_points) {
+        return respond_failure(
+            &ctx,
+            "Event Points Failed",
+            "Points must be between 0 and 1000",
+        )
+        .await;
+    }
+
+    let event_type = match ctx
+        .bot
+        .database
+        .query_opt::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+            &[&guild_id, &args.event_id],
+        )
+        .await?
+    {
+        Some(e) => e,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Points Failed",
+                &format!("An event type with id {} does not exist", args.event_id),
+            )
+            .await
+        }
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE event_types SET host_points = $1, attend_points = $2 WHERE event_type_id = $3",
+            &[
+                &args.host_points,
+                &args.attend_points,
+                &event_type.event_type_id,
+            ],
+        )
+        .await?;
+
+    let name = format!("Event Type Id: {}", event_type.event_type_guild_id);
+    let desc = format!(
+        "Host Points: {} -> {}\nAttend Points: {} -> {}",
+        event_type.host_points, args.host_points, event_type.attend_points, args.attend_points
+    );
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Type Modification Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Type Points Modified")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+pub async fn event_ranks(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let thresholds = ctx
+        .bot
+        .database
+        .query::<EventRankThreshold>(
+            "SELECT * FROM event_rank_thresholds WHERE guild_id = $1 ORDER BY group_id, rank",
+            &[&guild_id],
+        )
+        .await?;
+
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title("Event Rank Thresholds");
+    if thresholds.is_empty() {
+        embed = embed.description("This server has no rank thresholds");
+    }
+    for (group_id, thresholds) in &thresholds.iter().group_by(|t| t.group_id) {
+        let desc = thresholds
+            .map(|t| format!("`{}` Rank {}: {} points", t.threshold_id, t.rank, t.points))
+            .join("\n");
+        embed = embed.field(EmbedFieldBuilder::new(format!("Group {}", group_id), desc));
+    }
+    ctx.respond().embeds(&[embed.build()?])?.exec().await?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventRankSetArguments {
+    #[arg(help = "The id of the roblox group")]
+    pub group_id: i64,
+    #[arg(help = "The rank number (1-255) in the group")]
+    pub rank: i32,
+    #[arg(help = "The points a member needs to be recommended for the rank")]
+    pub points: i64,
+}
+
+pub async fn event_ranks_set(ctx: CommandContext, args: EventRankSetArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    if args.points < 1 {
+        return respond_failure(
+            &ctx,
+            "Event Points Failed",
+            "A rank must require at least one point",
+        )
+        .await;
+    }
+    let group = match ctx
+        .bot
+        .roblox
+        .get_group_ranks(GroupId(args.group_id as u64))
+        .await?
+    {
+        Some(g) => g,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Points Failed",
+                "A group with the given id does not exist",
+            )
+            .await
+        }
+    };
+    let rank = match group.roles.iter().find(|r| i32::from(r.rank) == args.rank) {
+        Some(r) => r,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Points Failed",
+                &format!("Rank {} does not exist in the group", args.rank),
+            )
+            .await
+        }
+    };
+
+    let threshold = ctx
+        .bot
+        .database
+        .query_opt::<EventRankThreshold>(
+            "INSERT INTO event_rank_thresholds(guild_id, group_id, rank, points) VALUES($1, $2, $3, $4) ON CONFLICT(guild_id, group_id, rank) DO UPDATE SET points = $4 RETURNING *",
+            &[&guild_id, &args.group_id, &args.rank, &args.points],
+        )
+        .await?
+        .unwrap();
+
+    let name = format!("Threshold Id: {}", threshold.threshold_id);
+    let desc = format!(
+        "Group: {}\nRank: {} ({})\nPoints: {}",
+        args.group_id, rank.name, args.rank, args.points
+    );
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Rank Threshold Addition Successful")
+        .field(EmbedFieldBuilder::new(name.clone(), desc.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Rank Threshold Set")
+        .field(EmbedFieldBuilder::new(name, desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventRankDeleteArguments {
+    #[arg(help = "The id of the threshold to delete")]
+    pub id: i64,
+}
+
+pub async fn event_ranks_delete(
+    ctx: CommandContext,
+    args: EventRankDeleteArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let threshold = ctx
+        .bot
+        .database
+        .query_opt::<EventRankThreshold>(
+            "DELETE FROM event_rank_thresholds WHERE guild_id = $1 AND threshold_id = $2 RETURNING *",
+            &[&guild_id, &args.id],
+        )
+        .await?;
+    if threshold.is_none() {
+        return respond_failure(
+            &ctx,
+            "Event Points Failed",
+            "A threshold with the given id was not found",
+        )
+        .await;
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Rank Threshold Deletion Successful")
+        .description(format!("Threshold `{}` has been deleted", args.id))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Event Rank Threshold Deleted: Id {}", args.id))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventPromotionsArguments {
+    #[arg(help = "The id of the roblox group. Leave empty to check every group with thresholds")]
+    pub group_id: Option<i64>,
+}
+
+struct Promotion {
+    roblox_id: i64,
+    group_id: i64,
+    current_rank: i32,
+    next_rank: i32,
+    points: i64,
+    required: i64,
+}
+
+pub async fn event_promotions(
+    ctx: CommandContext,
+    args: EventPromotionsArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let thresholds = ctx
+        .bot
+        .database
+        .query::<EventRankThreshold>(
+            "SELECT * FROM event_rank_thresholds WHERE guild_id = $1 AND ($2::BIGINT IS NULL OR group_id = $2) ORDER BY group_id, rank",
+            &[&guild_id, &args.group_id],
+        )
+        .await?;
+    let min_points = match thresholds.iter().map(|t| t.points).min() {
+        Some(m) => m,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Points Failed",
+                "There are no rank thresholds to check. Add one with `event ranks set`",
+            )
+            .await
+        }
+    };
+    let mut groups = BTreeMap::new();
+    for threshold in &thresholds {
+        groups
+            .entry(threshold.group_id)
+            .or_insert_with(Vec::new)
+            .push(threshold);
+    }
+
+    let candidates = event_points(&ctx.bot, guild_id, None)
+        .await?
+        .into_iter()
+        .filter(|p| p.points >= min_points)
+        .take(MAX_CANDIDATES)
+        .collect::<Vec<_>>();
+
+    let mut promotions = Vec::new();
+    for candidate in &candidates {
+        let roles = ctx
+            .bot
+            .roblox
+            .get_user_roles(RobloxUserId(candidate.roblox_id as u64))
+            .await?;
+        for (group_id, thresholds) in &groups {
+            let current_rank = match roles.iter().find(|r| r.group.id.0 == *group_id as u64) {
+                Some(r) => i32::from(r.role.rank),
+                None => continue,
+            };
+            // Only the rank directly above the current one is recommended
+            if let Some(next) = thresholds.iter().find(|t| t.rank > current_rank) {
+                if candidate.points >= next.points {
+                    promotions.push(Promotion {
+                        roblox_id: candidate.roblox_id,
+                        group_id: *group_id,
+                        current_rank,
+                        next_rank: next.rank,
+                        points: candidate.points,
+                        required: next.points,
+                    });
+                }
+            }
+        }
+    }
+
+    if promotions.is_empty() {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .title("Promotion Recommendations")
+            .description("No members are due for a promotion")
+            .build()?;
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+
+    let mut rank_names = HashMap::new();
+    for group_id in groups.keys() {
+        if let Some(group) = ctx
+            .bot
+            .roblox
+            .get_group_ranks(GroupId(*group_id as u64))
+            .await?
+        {
+            for rank in group.roles {
+                rank_names.insert((*group_id, i32::from(rank.rank)), rank.name);
+            }
+        }
+    }
+    let rank_name = |group_id: i64, rank: i32| {
+        rank_names
+            .get(&(group_id, rank))
+            .cloned()
+            .unwrap_or_else(|| rank.to_string())
+    };
+
+    let mut usernames = HashMap::new();
+    let ids = promotions
+        .iter()
+        .map(|p| RobloxUserId(p.roblox_id as u64))
+        .unique()
+        .collect::<Vec<_>>();
+    for chunk in ids.chunks(100) {
+        for user in ctx.bot.roblox.get_users(chunk).await? {
+            usernames.insert(user.id.0 as i64, user.name);
+        }
+    }
+
+    let mut pages = Vec::new();
+    let mut page_count = 0;
+    for promotions in &promotions.iter().chunks(12) {
+        let mut embed = EmbedBuilder::new()
+            .default_data()
+            .title("Promotion Recommendations")
+            .description(format!("Page {}", page_count + 1));
+        for promotion in promotions {
+            let name = usernames
+                .get(&promotion.roblox_id)
+                .cloned()
+                .unwrap_or_else(|| promotion.roblox_id.to_string());
+            let desc = format!(
+                "Group: {}\nRank: {} -> {}\nPoints: {}/{}",
+                promotion.group_id,
+                rank_name(promotion.group_id, promotion.current_rank),
+                rank_name(promotion.group_id, promotion.next_rank),
+                promotion.points,
+                promotion.required
+            );
+            embed = embed.field(EmbedFieldBuilder::new(name, desc).inline());
+        }
+        pages.push(embed.build()?);
+        page_count += 1;
+    }
+    paginate_embed(&ctx, pages, page_count).await?;
+    Ok(())
+}
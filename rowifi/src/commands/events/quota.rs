@@ -0,0 +1,402 @@
+use itertools::Itertools;
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{EventQuota, EventType, QuotaKind},
+    guild::GuildType,
+    id::{ChannelId, RoleId},
+};
+use twilight_http::request::AttachmentFile;
+
+use crate::utils::{build_quota_report, quota_report_csv, quota_report_embed, quota_requirement};
+
+pub async fn event_quota(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let quotas = ctx
+        .bot
+        .database
+        .query::<EventQuota>(
+            "SELECT * FROM event_quotas WHERE guild_id = $1 ORDER BY quota_id",
+            &[&(guild_id)],
+        )
+        .await?;
+
+    let mut desc = quotas
+        .iter()
+        .map(|q| format!("`{}` {}", q.quota_id, quota_requirement(q, &event_types)))
+        .join("\n");
+    if desc.is_empty() {
+        desc = "This server has no event quotas".into();
+    }
+    let channel = guild
+        .quota_channel
+        .map_or_else(|| "None".into(), |c| format!("<#{}>", c));
+    let mut exempt = guild
+        .quota_exempt_roles
+        .iter()
+        .map(|r| format!("<@&{}>", r))
+        .join(" ");
+    if exempt.is_empty() {
+        exempt = "None".into();
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title("Event Quotas")
+        .description(desc)
+        .field(EmbedFieldBuilder::new("Report Channel", channel).inline())
+        .field(EmbedFieldBuilder::new("Exempt Roles", exempt).inline())
+        .build()?;
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct QuotaNewArguments {
+    #[arg(help = "The role whose members the quota applies to")]
+    pub role: RoleId,
+    #[arg(help = "Whether members must `Host` or `Attend` the events")]
+    pub kind: QuotaKind,
+    #[arg(help = "The number of events required in the window")]
+    pub required: i32,
+    #[arg(help = "The number of days in the rolling window")]
+    pub days: i32,
+    #[arg(help = "The id of the event type counted. Leave empty to count all event types")]
+    pub event_type: Option<i32>,
+}
+
+pub async fn event_quota_new(ctx: CommandContext, args: QuotaNewArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    if !ctx.bot.cache.roles(guild_id).contains(&args.role) {
+        return respond_failure(
+            &ctx,
+            "Event Quota Failed",
+            "The given role does not exist in this server",
+        )
+        .await;
+    }
+    if args.required < 1 {
+        return respond_failure(
+            &ctx,
+            "Event Quota Failed",
+            "A quota must require at least one event",
+        )
+        .await;
+    }
+    if !(1..=90).contains(&args.days) {
+        return respond_failure(
+            &ctx,
+            "Event Quota Failed",
+            "The window must be between 1 and 90 days",
+        )
+        .await;
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    if let Some(event_type) = args.event_type {
+        if !event_types
+            .iter()
+            .any(|e| e.event_type_guild_id == event_type)
+        {
+            return respond_failure(
+                &ctx,
+                "Event Quota Failed",
+                &format!("An event type with id {} does not exist", event_type),
+            )
+            .await;
+        }
+    }
+
+    let quota = ctx
+        .bot
+        .database
+        .query_opt::<EventQuota>(
+            "INSERT INTO event_quotas(guild_id, role_id, event_type, kind, required, window_days) VALUES($1, $2, $3, $4, $5, $6) RETURNING *",
+            &[&guild_id, &args.role, &args.event_type, &args.kind, &args.required, &args.days],
+        )
+        .await?
+        .unwrap();
+
+    let requirement = quota_requirement(&quota, &event_types);
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Quota Addition Successful")
+        .field(EmbedFieldBuilder::new(
+            format!("Quota Id: {}", quota.quota_id),
+            requirement.clone(),
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Event Quota Added")
+        .field(EmbedFieldBuilder::new(
+            format!("Quota Id: {}", quota.quota_id),
+            requirement,
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct QuotaDeleteArguments {
+    #[arg(help = "The id of the quota to delete")]
+    pub id: i64,
+}
+
+pub async fn event_quota_delete(ctx: CommandContext, args: QuotaDeleteArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let quota = ctx
+        .bot
+        .database
+        .query_opt::<EventQuota>(
+            "DELETE FROM event_quotas WHERE guild_id = $1 AND quota_id = $2 RETURNING *",
+            &[&guild_id, &args.id],
+        )
+        .await?;
+    if quota.is_none() {
+        return respond_failure(
+            &ctx,
+            "Event Quota Failed",
+            "A quota with the given id was not found",
+        )
+        .await;
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Quota Deletion Successful")
+        .description(format!("Quota `{}` has been deleted", args.id))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Event Quota Deleted: Id {}", args.id))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct QuotaChannelArguments {
+    #[arg(
+        help = "The channel weekly quota reports are posted to. Leave empty to stop posting reports"
+    )]
+    pub channel: Option<ChannelId>,
+}
+
+pub async fn event_quota_channel(
+    ctx: CommandContext,
+    args: QuotaChannelArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    if let Some(channel_id) = args.channel {
+        if ctx.bot.cache.channel(channel_id).is_none() {
+            return respond_failure(
+                &ctx,
+                "Event Quota Failed",
+                "This channel cannot be set as the report channel or does not exist",
+            )
+            .await;
+        }
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET quota_channel = $1 WHERE guild_id = $2",
+            &[&args.channel, &guild_id],
+        )
+        .await?;
+
+    let desc = match args.channel {
+        Some(channel_id) => format!(
+            "Quota reports will now be posted weekly to <#{}>",
+            channel_id
+        ),
+        None => "Quota reports will no longer be posted".into(),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Quota Channel - {} -> {}",
+            guild
+                .quota_channel
+                .map_or_else(|| "None".into(), |c| format!("<#{}>", c)),
+            args.channel
+                .map_or_else(|| "None".into(), |c| format!("<#{}>", c))
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct QuotaExemptArguments {
+    #[arg(
+        help = "The role to exempt from quotas. Running this again on the role removes the exemption"
+    )]
+    pub role: RoleId,
+}
+
+pub async fn event_quota_exempt(ctx: CommandContext, args: QuotaExemptArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let desc = if guild.quota_exempt_roles.contains(&args.role) {
+        ctx.bot
+            .database
+            .execute(
+                "UPDATE guilds SET quota_exempt_roles = array_remove(quota_exempt_roles, $1) WHERE guild_id = $2",
+                &[&args.role, &guild_id],
+            )
+            .await?;
+        format!("<@&{}> is no longer exempt from quotas", args.role)
+    } else {
+        if !ctx.bot.cache.roles(guild_id).contains(&args.role) {
+            return respond_failure(
+                &ctx,
+                "Event Quota Failed",
+                "The given role does not exist in this server",
+            )
+            .await;
+        }
+        ctx.bot
+            .database
+            .execute(
+                "UPDATE guilds SET quota_exempt_roles = array_append(COALESCE(quota_exempt_roles, '{}'), $1) WHERE guild_id = $2",
+                &[&args.role, &guild_id],
+            )
+            .await?;
+        format!("<@&{}> is now exempt from quotas", args.role)
+    };
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc.clone())
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Settings Modification: {}", desc))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+pub async fn event_quota_report(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let quotas = ctx
+        .bot
+        .database
+        .query::<EventQuota>(
+            "SELECT * FROM event_quotas WHERE guild_id = $1 ORDER BY quota_id",
+            &[&(guild_id)],
+        )
+        .await?;
+    if quotas.is_empty() {
+        return respond_failure(
+            &ctx,
+            "Event Quota Failed",
+            "This server has no event quotas",
+        )
+        .await;
+    }
+
+    let entries = build_quota_report(&ctx.bot, &guild, &quotas).await?;
+    let embed = quota_report_embed(&quotas, &event_types, &entries).build()?;
+    let csv = quota_report_csv(&quotas, &entries);
+    ctx.respond()
+        .embeds(&[embed])?
+        .files(&[AttachmentFile::from_bytes(
+            "quota_report.csv",
+            csv.as_bytes(),
+        )])
+        .exec()
+        .await?;
+    Ok(())
+}
@@ -6,19 +6,11 @@ pub async fn event_reset(ctx: CommandContext) -> CommandResult {
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let confirmation = await_confirmation(
-        "Are you sure you would like to delete all logged events & reset all event types?",
+        "Are you sure you would like to delete all logged events & reset all event types and quotas?",
         &ctx,
     )
     .await?;
@@ -48,6 +40,30 @@ pub async fn event_reset(ctx: CommandContext) -> CommandResult {
         .await?;
     transaction.execute(&events_change, &[&(guild_id)]).await?;
 
+    let quotas_change = transaction
+        .prepare_cached("DELETE FROM event_quotas WHERE guild_id = $1")
+        .await?;
+    transaction.execute(&quotas_change, &[&(guild_id)]).await?;
+
+    let sessions_change = transaction
+        .prepare_cached("DELETE FROM event_sessions WHERE guild_id = $1")
+        .await?;
+    transaction
+        .execute(&sessions_change, &[&(guild_id)])
+        .await?;
+
+    let edits_change = transaction
+        .prepare_cached("DELETE FROM event_edits WHERE guild_id = $1")
+        .await?;
+    transaction.execute(&edits_change, &[&(guild_id)]).await?;
+
+    let thresholds_change = transaction
+        .prepare_cached("DELETE FROM event_rank_thresholds WHERE guild_id = $1")
+        .await?;
+    transaction
+        .execute(&thresholds_change, &[&(guild_id)])
+        .await?;
+
     transaction.commit().await?;
 
     ctx.respond()
@@ -0,0 +1,431 @@
+use chrono::{DateTime, Duration as CDuration, TimeZone, Utc};
+use itertools::Itertools;
+use rowifi_framework::{get_perm_level, prelude::*};
+use rowifi_models::{
+    discord::id::Id,
+    events::{EventSession, EventType, SessionStatus},
+    guild::GuildType,
+    id::UserId,
+};
+
+use super::can_host_event;
+use crate::utils::{parse_duration, session_components, session_embed};
+
+/// The check-in window used when none is given
+const DEFAULT_CHECKIN_MINUTES: i32 = 30;
+
+#[derive(FromArgs)]
+pub struct EventScheduleArguments {
+    #[arg(help = "The id of the event type")]
+    pub event_type: i32,
+    #[arg(
+        help = "When the event starts. Either a delay such as `30m` `2h` `1d` or a discord timestamp"
+    )]
+    pub start: SessionStart,
+    #[arg(help = "The number of minutes check-in stays open after the start. Defaults to 30")]
+    pub checkin: Option<i32>,
+}
+
+pub struct SessionStart(pub DateTime<Utc>);
+
+pub async fn event_schedule(ctx: CommandContext, args: EventScheduleArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let host_roblox_id = match ctx
+        .bot
+        .database
+        .get_linked_user(UserId(ctx.author.id), guild_id)
+        .await?
+    {
+        Some(r) => r.roblox_id,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                "You need to be verified to use this command",
+            )
+            .await
+        }
+    };
+
+    let event_type = match ctx
+        .bot
+        .database
+        .query_opt::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+            &[&guild_id, &args.event_type],
+        )
+        .await?
+    {
+        Some(e) if e.disabled => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                "This event type is disabled",
+            )
+            .await
+        }
+        Some(e) if !can_host_event(&ctx, &e) => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                "You are not allowed to host this event type",
+            )
+            .await
+        }
+        Some(e) => e,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                &format!("An event type with id {} does not exist", args.event_type),
+            )
+            .await
+        }
+    };
+
+    let start_time = args.start.0;
+    if start_time <= Utc::now() {
+        return respond_failure(
+            &ctx,
+            "Event Scheduling Failed",
+            "The event must start in the future",
+        )
+        .await;
+    }
+    if start_time - Utc::now() > CDuration::days(30) {
+        return respond_failure(
+            &ctx,
+            "Event Scheduling Failed",
+            "Events may be scheduled at most 30 days ahead",
+        )
+        .await;
+    }
+    let checkin_minutes = args.checkin.unwrap_or(DEFAULT_CHECKIN_MINUTES);
+    if !(5..=240).contains(&checkin_minutes) {
+        return respond_failure(
+            &ctx,
+            "Event Scheduling Failed",
+            "Check-in must stay open between 5 and 240 minutes",
+        )
+        .await;
+    }
+
+    let session = EventSession {
+        session_id: 0,
+        guild_id,
+        channel_id: ctx.channel_id,
+        message_id: 0,
+        event_type: event_type.event_type_guild_id,
+        host_id: UserId(ctx.author.id),
+        host_roblox_id,
+        start_time,
+        checkin_minutes,
+        status: SessionStatus::Scheduled,
+        rsvps: Vec::new(),
+        checked_in: Vec::new(),
+    };
+    // The session is stored before its message is posted so that a failed insert leaves no orphaned
+    // message behind. Until the message exists the session holds a placeholder message id of 0, which
+    // readers skip, and the session is removed again if the message cannot be posted
+    let mut session = ctx
+        .bot
+        .database
+        .query_one::<EventSession>(
+            "INSERT INTO event_sessions(guild_id, channel_id, message_id, event_type, host_id, host_roblox_id, start_time, checkin_minutes, status, rsvps, checked_in) VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING *",
+            &[
+                &session.guild_id,
+                &session.channel_id,
+                &session.message_id,
+                &session.event_type,
+                &session.host_id,
+                &session.host_roblox_id,
+                &session.start_time,
+                &session.checkin_minutes,
+                &session.status,
+                &session.rsvps,
+                &session.checked_in,
+            ],
+        )
+        .await?;
+
+    session.message_id = match post_session(&ctx, &session, &event_type.name).await {
+        Ok(message_id) => message_id,
+        Err(err) => {
+            ctx.bot
+                .database
+                .execute(
+                    "DELETE FROM event_sessions WHERE session_id = $1",
+                    &[&session.session_id],
+                )
+                .await?;
+            return Err(err);
+        }
+    };
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE event_sessions SET message_id = $1 WHERE session_id = $2",
+            &[&session.message_id, &session.session_id],
+        )
+        .await?;
+    ctx.bot
+        .ignore_message_components
+        .insert(Id::new(session.message_id as u64));
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Event Scheduled: Session {} - {} at <t:{}:f>",
+            session.session_id,
+            event_type.name,
+            start_time.timestamp()
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+/// Post the message of a newly scheduled session and return its id
+async fn post_session(
+    ctx: &CommandContext,
+    session: &EventSession,
+    event_type: &str,
+) -> Result<i64, RoError> {
+    let embed = session_embed(session, event_type)?;
+    let message = ctx
+        .respond()
+        .embeds(&[embed])?
+        .components(&session_components(session.status))?
+        .exec()
+        .await?
+        .model()
+        .await?;
+    Ok(message.id.get() as i64)
+}
+
+pub async fn event_upcoming(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let sessions = ctx
+        .bot
+        .database
+        .query::<EventSession>(
+            "SELECT * FROM event_sessions WHERE guild_id = $1 AND (status = $2 OR status = $3) ORDER BY start_time",
+            &[&guild_id, &SessionStatus::Scheduled, &SessionStatus::CheckIn],
+        )
+        .await?;
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+
+    let mut pages = Vec::new();
+    let mut page_count = 0;
+    for sessions in &sessions.iter().chunks(12) {
+        let mut embed = EmbedBuilder::new()
+            .default_data()
+            .title("Scheduled Events")
+            .description(format!("Page {}", page_count + 1));
+        for session in sessions {
+            let event_type = event_types
+                .iter()
+                .find(|e| e.event_type_guild_id == session.event_type)
+                .map_or_else(|| session.event_type.to_string(), |e| e.name.clone());
+            let desc = format!(
+                "Event Type: {}\nHost: <@{}>\nStarts: <t:{}:f>\nStatus: {}\nRSVPs: {}",
+                event_type,
+                session.host_id,
+                session.start_time.timestamp(),
+                session.status,
+                session.rsvps.len()
+            );
+            embed = embed.field(
+                EmbedFieldBuilder::new(format!("Session Id: {}", session.session_id), desc)
+                    .inline(),
+            );
+        }
+        pages.push(embed.build()?);
+        page_count += 1;
+    }
+
+    if pages.is_empty() {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .title("Scheduled Events")
+            .description("There are no upcoming events")
+            .build()?;
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+    paginate_embed(&ctx, pages, page_count).await?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct EventCancelArguments {
+    #[arg(help = "The id of the scheduled event to cancel")]
+    pub session_id: i64,
+}
+
+pub async fn event_cancel(ctx: CommandContext, args: EventCancelArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+
+    let session = match ctx
+        .bot
+        .database
+        .query_opt::<EventSession>(
+            "SELECT * FROM event_sessions WHERE guild_id = $1 AND session_id = $2",
+            &[&guild_id, &args.session_id],
+        )
+        .await?
+    {
+        Some(s) => s,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                "A scheduled event with the given id was not found",
+            )
+            .await
+        }
+    };
+
+    // Trainers may only cancel their own events while admins may cancel any
+    let is_admin = match (
+        ctx.bot.cache.guild(guild_id),
+        ctx.bot.cache.member(guild_id, UserId(ctx.author.id)),
+    ) {
+        (Some(guild), Some(member)) => get_perm_level(&ctx.bot, &guild, &member) >= RoLevel::Admin,
+        _ => false,
+    };
+    if session.host_id != UserId(ctx.author.id) && !is_admin {
+        return respond_failure(
+            &ctx,
+            "Event Scheduling Failed",
+            "Only the host or an admin may cancel this event",
+        )
+        .await;
+    }
+
+    let cancelled = ctx
+        .bot
+        .database
+        .query_opt::<EventSession>(
+            "UPDATE event_sessions SET status = $1 WHERE session_id = $2 AND (status = $3 OR status = $4) RETURNING *",
+            &[
+                &SessionStatus::Cancelled,
+                &session.session_id,
+                &SessionStatus::Scheduled,
+                &SessionStatus::CheckIn,
+            ],
+        )
+        .await?;
+    let cancelled = match cancelled {
+        Some(c) => c,
+        None => {
+            return respond_failure(
+                &ctx,
+                "Event Scheduling Failed",
+                "This event has already been closed or cancelled",
+            )
+            .await
+        }
+    };
+
+    let event_type = ctx
+        .bot
+        .database
+        .query_opt::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+            &[&guild_id, &cancelled.event_type],
+        )
+        .await?
+        .map_or_else(|| cancelled.event_type.to_string(), |e| e.name);
+    // A message id of 0 means the session's message is still being posted
+    if cancelled.message_id != 0 {
+        let message_id = Id::new(cancelled.message_id as u64);
+        let _ = ctx
+            .bot
+            .http
+            .update_message(cancelled.channel_id.0, message_id)
+            .embeds(Some(&[session_embed(&cancelled, &event_type)?]))?
+            .components(Some(&session_components(cancelled.status)))?
+            .exec()
+            .await;
+        ctx.bot.ignore_message_components.remove(&message_id);
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Event Cancellation Successful")
+        .description(format!(
+            "Scheduled event `{}` has been cancelled",
+            cancelled.session_id
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!("Event Cancelled: Session {}", cancelled.session_id))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+impl FromArg for SessionStart {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        // Discord timestamps look like <t:1640995200:f>
+        let timestamp = arg
+            .trim_start_matches("<t:")
+            .trim_end_matches('>')
+            .split(':')
+            .next()
+            .unwrap_or_default();
+        if let Ok(secs) = timestamp.parse::<i64>() {
+            if let Some(start) = Utc.timestamp_opt(secs, 0).single() {
+                return Ok(SessionStart(start));
+            }
+        }
+
+        // Delays past the scheduling limit are turned down by the command with a clearer message
+        let units = [
+            ('m', CDuration::minutes(1)),
+            ('h', CDuration::hours(1)),
+            ('d', CDuration::days(1)),
+        ];
+        if let Some(delay) = parse_duration(arg, &units, CDuration::days(365)) {
+            return Ok(SessionStart(Utc::now() + delay));
+        }
+        Err(ParseError(
+            "a delay such as `30m` `2h` `1d` or a discord timestamp",
+        ))
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::Integer(value) => value.to_string(),
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("SessionStart unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{Duration as CDuration, Utc};
+use rowifi_framework::prelude::*;
+use rowifi_models::{
+    events::{AttendanceStreak, EventMemberSummary, EventType},
+    guild::GuildType,
+    id::UserId,
+    roblox::id::UserId as RobloxUserId,
+};
+
+use crate::utils::event_points;
+
+/// The most event types shown, leaving room for the totals and streak fields under the 25 field
+/// limit of an embed
+const MAX_EVENT_TYPE_FIELDS: usize = 23;
+
+#[derive(FromArgs)]
+pub struct EventStatsArguments {
+    #[arg(help = "The roblox username of the member")]
+    pub username: Option<String>,
+}
+
+pub async fn event_stats(ctx: CommandContext, args: EventStatsArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if guild.kind != GuildType::Beta {
+        return beta_only(&ctx).await;
+    }
+
+    let (roblox_id, roblox_name) = match args.username {
+        Some(s) => match ctx.bot.roblox.get_user_from_username(&s).await? {
+            Some(i) => (i.id.0 as i64, i.name),
+            None => {
+                let embed = EmbedBuilder::new()
+                    .default_data()
+                    .title("Event Stats Failed")
+                    .description("Given roblox username does not have an associated id")
+                    .color(Color::Red as u32)
+                    .build()
+                    .unwrap();
+                ctx.respond().embeds(&[embed])?.exec().await?;
+                return Ok(());
+            }
+        },
+        None => {
+            let user = ctx
+                .bot
+                .database
+                .get_linked_user(UserId(ctx.author.id), guild_id)
+                .await?;
+            match user {
+                Some(u) => {
+                    let roblox_user = ctx
+                        .bot
+                        .roblox
+                        .get_user(RobloxUserId(u.roblox_id as u64), false)
+                        .await?;
+                    (u.roblox_id, roblox_user.name)
+                }
+                None => {
+                    let embed = EmbedBuilder::new()
+                        .default_data()
+                        .title("Event Stats Failed")
+                        .description("You must be verified to use this command on yourself")
+                        .color(Color::Red as u32)
+                        .build()
+                        .unwrap();
+                    ctx.respond().embeds(&[embed])?.exec().await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let event_types = ctx
+        .bot
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&(guild_id)],
+        )
+        .await?;
+    let summaries = ctx
+        .bot
+        .database
+        .query::<EventMemberSummary>(
+            "SELECT event_type, COUNT(*) FILTER (WHERE $2 = ANY(attendees)) AS attended, COUNT(*) FILTER (WHERE host_id = $2) AS hosted, MAX(timestamp) FILTER (WHERE $2 = ANY(attendees)) AS last_attended FROM events WHERE guild_id = $1 AND voided_at IS NULL AND (host_id = $2 OR $2 = ANY(attendees)) GROUP BY event_type ORDER BY event_type",
+            &[&(guild_id), &roblox_id],
+        )
+        .await?;
+    // Consecutive weeks collapse into the same island since the week and its row number grow together
+    let streaks = ctx
+        .bot
+        .database
+        .query::<AttendanceStreak>(
+            "WITH weeks AS (SELECT DISTINCT date_trunc('week', timestamp) AS week FROM events WHERE guild_id = $1 AND voided_at IS NULL AND $2 = ANY(attendees)), islands AS (SELECT week, week - ROW_NUMBER() OVER (ORDER BY week) * INTERVAL '1 week' AS island FROM weeks) SELECT COUNT(*) AS length, MAX(week) AS last_week FROM islands GROUP BY island",
+            &[&(guild_id), &roblox_id],
+        )
+        .await?;
+
+    let points = event_points(&ctx.bot, guild_id, Some(roblox_id))
+        .await?
+        .first()
+        .map(|p| p.points)
+        .unwrap_or_default();
+
+    let total_attended = summaries.iter().map(|s| s.attended).sum::<i64>();
+    let total_hosted = summaries.iter().map(|s| s.hosted).sum::<i64>();
+    let last_attended = summaries
+        .iter()
+        .filter_map(|s| s.last_attended)
+        .max()
+        .map_or_else(|| "Never".into(), |t| format!("<t:{}:R>", t.timestamp()));
+    let longest_streak = streaks.iter().map(|s| s.length).max().unwrap_or_default();
+    // A streak is still running if the member attended an event this week or the last
+    let current_streak = streaks
+        .iter()
+        .find(|s| Utc::now() - s.last_week < CDuration::weeks(2))
+        .map(|s| s.length)
+        .unwrap_or_default();
+
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Event Stats: {}", roblox_name))
+        .field(EmbedFieldBuilder::new(
+            "Totals",
+            format!(
+                "Attended: {}\nHosted: {}\nPoints: {}\nLast Attended: {}",
+                total_attended, total_hosted, points, last_attended
+            ),
+        ))
+        .field(EmbedFieldBuilder::new(
+            "Attendance Streak",
+            format!(
+                "Current: {} weeks\nLongest: {} weeks",
+                current_streak, longest_streak
+            ),
+        ));
+
+    if summaries.len() > MAX_EVENT_TYPE_FIELDS {
+        embed = embed.footer(EmbedFooterBuilder::new(format!(
+            "Showing {} of {} event types",
+            MAX_EVENT_TYPE_FIELDS,
+            summaries.len()
+        )));
+    }
+    for summary in summaries.iter().take(MAX_EVENT_TYPE_FIELDS) {
+        let name = event_types
+            .iter()
+            .find(|e| e.event_type_guild_id == summary.event_type)
+            .map_or_else(|| summary.event_type.to_string(), |e| e.name.clone());
+        let last_attended = summary
+            .last_attended
+            .map_or_else(|| "Never".into(), |t| format!("<t:{}:R>", t.timestamp()));
+        embed = embed.field(
+            EmbedFieldBuilder::new(
+                name,
+                format!(
+                    "Attended: {}\nHosted: {}\nLast Attended: {}",
+                    summary.attended, summary.hosted, last_attended
+                ),
+            )
+            .inline(),
+        );
+    }
+
+    ctx.respond().embeds(&[embed.build()?])?.exec().await?;
+    Ok(())
+}
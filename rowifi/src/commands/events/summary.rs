@@ -1,25 +1,43 @@
 use chrono::{Duration as CDuration, Utc};
+use image::{png::PngEncoder, ColorType};
 use itertools::Itertools;
-use rowifi_framework::prelude::*;
+use plotters::prelude::*;
+use rowifi_framework::prelude::{Color as DiscordColor, *};
 use rowifi_models::{
     events::{EventLog, EventType},
     guild::GuildType,
+    roblox::id::UserId as RobloxUserId,
 };
+use std::{collections::HashMap, io::Cursor};
+use twilight_http::request::AttachmentFile;
 
-pub async fn event_summary(ctx: CommandContext) -> CommandResult {
+use super::leaderboard::EventWindow;
+
+const CHART_WIDTH: u32 = 1024;
+const CHART_HEIGHT: u32 = 768;
+/// The most hosts shown in the host distribution chart
+const MAX_CHART_HOSTS: usize = 15;
+
+#[derive(FromArgs)]
+pub struct EventSummaryArguments {
+    #[arg(help = "Use `--chart` to render the summary as charts")]
+    pub mode: Option<SummaryMode>,
+    #[arg(help = "The window to chart such as `7d`, `2w` or `3m`. Defaults to 30 days")]
+    pub window: Option<EventWindow>,
+}
+
+#[derive(PartialEq, Eq)]
+pub enum SummaryMode {
+    Text,
+    Chart,
+}
+
+pub async fn event_summary(ctx: CommandContext, args: EventSummaryArguments) -> CommandResult {
     let guild_id = ctx.guild_id.unwrap();
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_types = ctx
@@ -30,10 +48,21 @@ pub async fn event_summary(ctx: CommandContext) -> CommandResult {
             &[&(guild_id)],
         )
         .await?;
+
+    if args.mode == Some(SummaryMode::Chart) {
+        let window = args
+            .window
+            .unwrap_or_else(|| EventWindow(CDuration::days(30)));
+        return event_summary_charts(ctx, event_types, window).await;
+    }
+
     let events = ctx
         .bot
         .database
-        .query::<EventLog>("SELECT * FROM events WHERE guild_id = $1", &[&(guild_id)])
+        .query::<EventLog>(
+            "SELECT * FROM events WHERE guild_id = $1 AND voided_at IS NULL",
+            &[&(guild_id)],
+        )
         .await?;
 
     let mut embed = EmbedBuilder::new().default_data().title("Events Summary");
@@ -78,3 +107,347 @@ pub async fn event_summary(ctx: CommandContext) -> CommandResult {
 
     Ok(())
 }
+
+#[allow(clippy::cast_possible_truncation)]
+async fn event_summary_charts(
+    ctx: CommandContext,
+    event_types: Vec<EventType>,
+    window: EventWindow,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let start = (Utc::now() - window.0).date().and_hms(0, 0, 0);
+    let events = ctx
+        .bot
+        .database
+        .query::<EventLog>(
+            "SELECT * FROM events WHERE guild_id = $1 AND voided_at IS NULL AND timestamp >= $2",
+            &[&(guild_id), &start],
+        )
+        .await?;
+
+    if events.is_empty() {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .color(DiscordColor::Red as u32)
+            .title("Events Summary Failed")
+            .description("There are no events logged in this window to chart")
+            .build()
+            .unwrap();
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+
+    let days = (Utc::now() - start).num_days() as i32 + 1;
+    let type_ids = events
+        .iter()
+        .map(|e| e.event_type)
+        .unique()
+        .sorted()
+        .collect::<Vec<_>>();
+    let type_names = type_ids
+        .iter()
+        .map(|id| {
+            event_types
+                .iter()
+                .find(|e| e.event_type_guild_id == *id)
+                .map_or_else(|| id.to_string(), |e| e.name.clone())
+        })
+        .collect::<Vec<_>>();
+
+    // The number of events and attendees of every type on every day of the window
+    let mut daily_events = vec![vec![0_i32; days as usize]; type_ids.len()];
+    let mut daily_attendance = vec![vec![0_i32; days as usize]; type_ids.len()];
+    let mut host_counts = HashMap::<i64, i32>::new();
+    for event in &events {
+        let type_index = type_ids
+            .iter()
+            .position(|t| *t == event.event_type)
+            .unwrap();
+        let day = ((event.timestamp - start).num_days() as usize).min(days as usize - 1);
+        daily_events[type_index][day] += 1;
+        daily_attendance[type_index][day] += event.attendees.len() as i32;
+        *host_counts.entry(event.host_id).or_default() += 1;
+    }
+
+    let top_hosts = host_counts
+        .into_iter()
+        .sorted_unstable_by_key(|(host, count)| (-count, *host))
+        .take(MAX_CHART_HOSTS)
+        .collect::<Vec<_>>();
+    let host_ids = top_hosts
+        .iter()
+        .map(|(h, _)| RobloxUserId(*h as u64))
+        .collect::<Vec<_>>();
+    let host_names = ctx
+        .bot
+        .roblox
+        .get_users(&host_ids)
+        .await?
+        .into_iter()
+        .map(|u| (u.id.0 as i64, u.name))
+        .collect::<HashMap<_, _>>();
+    let hosts = top_hosts
+        .iter()
+        .map(|(host, count)| {
+            (
+                host_names
+                    .get(host)
+                    .cloned()
+                    .unwrap_or_else(|| host.to_string()),
+                *count,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let day_label = |day: i32| {
+        (start + CDuration::days(i64::from(day)))
+            .format("%m-%d")
+            .to_string()
+    };
+    let events_png = events_per_day_chart(&type_names, &daily_events, &day_label);
+    let attendance_png = attendance_chart(&type_names, &daily_attendance, &day_label);
+    let hosts_png = host_distribution_chart(&hosts);
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title("Events Summary")
+        .description(format!(
+            "Events Logged: {}\nWindow: {} to {}",
+            events.len(),
+            start.date().naive_utc(),
+            Utc::now().date().naive_utc()
+        ))
+        .build()?;
+    ctx.respond()
+        .embeds(&[embed])?
+        .files(&[
+            AttachmentFile::from_bytes("events_per_day.png", &events_png),
+            AttachmentFile::from_bytes("attendance.png", &attendance_png),
+            AttachmentFile::from_bytes("hosts.png", &hosts_png),
+        ])
+        .exec()
+        .await?;
+    Ok(())
+}
+
+/// A stacked bar chart of the events logged on each day, split by event type
+#[allow(clippy::cast_possible_truncation)]
+fn events_per_day_chart(
+    type_names: &[String],
+    daily_events: &[Vec<i32>],
+    day_label: &dyn Fn(i32) -> String,
+) -> Vec<u8> {
+    let days = daily_events[0].len() as i32;
+    let max_events = (0..days as usize)
+        .map(|d| daily_events.iter().map(|t| t[d]).sum::<i32>())
+        .max()
+        .unwrap_or_default();
+
+    let mut buffer = vec![0_u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+    {
+        let root_drawing_area =
+            BitMapBackend::with_buffer(&mut buffer, (CHART_WIDTH, CHART_HEIGHT))
+                .into_drawing_area();
+        root_drawing_area.fill(&WHITE).unwrap();
+        let mut chart = ChartBuilder::on(&root_drawing_area)
+            .caption("Events per Day", ("Arial", 30))
+            .margin(10)
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .build_cartesian_2d((0..days).into_segmented(), 0..max_events + 1)
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_label_formatter(&|x| segment_label(x, day_label))
+            .draw()
+            .unwrap();
+
+        let mut bottoms = vec![0_i32; days as usize];
+        for (index, (name, counts)) in type_names.iter().zip(daily_events).enumerate() {
+            let color = Palette99::pick(index).to_rgba();
+            let bars = counts
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(day, count)| {
+                    let bottom = bottoms[day];
+                    bottoms[day] += count;
+                    let mut bar = Rectangle::new(
+                        [
+                            (SegmentValue::Exact(day as i32), bottom),
+                            (SegmentValue::Exact(day as i32 + 1), bottom + count),
+                        ],
+                        color.filled(),
+                    );
+                    bar.set_margin(0, 0, 2, 2);
+                    bar
+                })
+                .collect::<Vec<_>>();
+            chart
+                .draw_series(bars)
+                .unwrap()
+                .label(name)
+                .legend(move |(x, y)| {
+                    Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled())
+                });
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(&WHITE.mix(0.8))
+            .border_style(&BLACK)
+            .draw()
+            .unwrap();
+    }
+    encode_png(&buffer)
+}
+
+/// A line for each event type of the attendees of its events on each day
+#[allow(clippy::cast_possible_truncation)]
+fn attendance_chart(
+    type_names: &[String],
+    daily_attendance: &[Vec<i32>],
+    day_label: &dyn Fn(i32) -> String,
+) -> Vec<u8> {
+    let days = daily_attendance[0].len() as i32;
+    let max_attendance = daily_attendance
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or_default();
+
+    let mut buffer = vec![0_u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+    {
+        let root_drawing_area =
+            BitMapBackend::with_buffer(&mut buffer, (CHART_WIDTH, CHART_HEIGHT))
+                .into_drawing_area();
+        root_drawing_area.fill(&WHITE).unwrap();
+        let mut chart = ChartBuilder::on(&root_drawing_area)
+            .caption("Attendance per Day", ("Arial", 30))
+            .margin(10)
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .build_cartesian_2d(0..days.max(2) - 1, 0..max_attendance + 1)
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .x_label_formatter(&|x| day_label(*x))
+            .draw()
+            .unwrap();
+
+        for (index, (name, attendance)) in type_names.iter().zip(daily_attendance).enumerate() {
+            let color = Palette99::pick(index).to_rgba();
+            chart
+                .draw_series(LineSeries::new(
+                    attendance
+                        .iter()
+                        .enumerate()
+                        .map(|(day, count)| (day as i32, *count)),
+                    color.stroke_width(2),
+                ))
+                .unwrap()
+                .label(name)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(&WHITE.mix(0.8))
+            .border_style(&BLACK)
+            .draw()
+            .unwrap();
+    }
+    encode_png(&buffer)
+}
+
+/// A bar chart of the events logged by the most active hosts
+#[allow(clippy::cast_possible_truncation)]
+fn host_distribution_chart(hosts: &[(String, i32)]) -> Vec<u8> {
+    let max_events = hosts.iter().map(|(_, c)| *c).max().unwrap_or_default();
+
+    let mut buffer = vec![0_u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+    {
+        let root_drawing_area =
+            BitMapBackend::with_buffer(&mut buffer, (CHART_WIDTH, CHART_HEIGHT))
+                .into_drawing_area();
+        root_drawing_area.fill(&WHITE).unwrap();
+        let mut chart = ChartBuilder::on(&root_drawing_area)
+            .caption("Events by Host", ("Arial", 30))
+            .margin(10)
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .build_cartesian_2d((0..hosts.len() as i32).into_segmented(), 0..max_events + 1)
+            .unwrap();
+
+        let host_label = |index: i32| {
+            hosts
+                .get(index as usize)
+                .map_or_else(String::new, |(name, _)| name.clone())
+        };
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(hosts.len())
+            .x_label_formatter(&|x| segment_label(x, &host_label))
+            .draw()
+            .unwrap();
+
+        chart
+            .draw_series(hosts.iter().enumerate().map(|(index, (_, count))| {
+                let mut bar = Rectangle::new(
+                    [
+                        (SegmentValue::Exact(index as i32), 0),
+                        (SegmentValue::Exact(index as i32 + 1), *count),
+                    ],
+                    BLUE.filled(),
+                );
+                bar.set_margin(0, 0, 5, 5);
+                bar
+            }))
+            .unwrap();
+    }
+    encode_png(&buffer)
+}
+
+fn segment_label(value: &SegmentValue<i32>, label: &dyn Fn(i32) -> String) -> String {
+    match value {
+        SegmentValue::CenterOf(x) | SegmentValue::Exact(x) => label(*x),
+        SegmentValue::Last => String::new(),
+    }
+}
+
+fn encode_png(buffer: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let img = PngEncoder::new(Cursor::new(&mut bytes));
+    img.encode(buffer, CHART_WIDTH, CHART_HEIGHT, ColorType::Rgb8)
+        .unwrap();
+    bytes
+}
+
+impl FromArg for SummaryMode {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match arg.trim_start_matches('-').to_ascii_lowercase().as_str() {
+            "chart" | "charts" => Ok(SummaryMode::Chart),
+            "text" => Ok(SummaryMode::Text),
+            _ => Err(ParseError("one of `--chart` `--text`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::Boolean(true) => "chart".to_string(),
+            CommandOptionValue::Boolean(false) => "text".to_string(),
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("SummaryMode unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
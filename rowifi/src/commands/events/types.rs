@@ -6,15 +6,7 @@ pub async fn event_type(ctx: CommandContext) -> CommandResult {
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_types = ctx
@@ -29,7 +21,10 @@ pub async fn event_type(ctx: CommandContext) -> CommandResult {
     let mut embed = EmbedBuilder::new().default_data().title("Event Types");
     for event_type in &event_types {
         let name = format!("Id: {}", event_type.event_type_guild_id);
-        let value = format!("Name: {}", event_type.name);
+        let value = format!(
+            "Name: {}\nPoints: {} Host / {} Attend",
+            event_type.name, event_type.host_points, event_type.attend_points
+        );
         embed = embed.field(EmbedFieldBuilder::new(name, value).inline());
     }
     ctx.respond().embeds(&[embed.build()?])?.exec().await?;
@@ -49,15 +44,7 @@ pub async fn event_type_new(ctx: CommandContext, args: EventTypeArguments) -> Co
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_id = args.event_id;
@@ -90,9 +77,13 @@ pub async fn event_type_new(ctx: CommandContext, args: EventTypeArguments) -> Co
         guild_id,
         name: event_name.to_string(),
         disabled: false,
+        host_points: 1,
+        attend_points: 1,
+        host_roles: Vec::new(),
+        fields: Vec::new(),
     };
 
-    ctx.bot.database.execute("INSERT INTO event_types(event_type_guild_id, guild_id, name, disabled) VALUES($1, $2, $3, $4)", &[&event_type.event_type_guild_id, &event_type.guild_id, &event_type.name, &event_type.disabled]).await?;
+    ctx.bot.database.execute("INSERT INTO event_types(event_type_guild_id, guild_id, name, disabled, host_points, attend_points) VALUES($1, $2, $3, $4, $5, $6)", &[&event_type.event_type_guild_id, &event_type.guild_id, &event_type.name, &event_type.disabled, &event_type.host_points, &event_type.attend_points]).await?;
 
     let embed = EmbedBuilder::new()
         .default_data()
@@ -113,15 +104,7 @@ pub async fn event_type_modify(ctx: CommandContext, args: EventTypeArguments) ->
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_type_guild_id = args.event_id;
@@ -187,15 +170,7 @@ pub async fn event_type_disable(ctx: CommandContext, args: DisableArguments) ->
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_types = ctx
@@ -261,15 +236,7 @@ pub async fn event_type_enable(ctx: CommandContext, args: EnableArguments) -> Co
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_types = ctx
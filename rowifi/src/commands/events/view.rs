@@ -1,4 +1,3 @@
-use itertools::Itertools;
 use rowifi_database::decrypt_bytes;
 use rowifi_framework::prelude::*;
 use rowifi_models::{
@@ -9,6 +8,8 @@ use rowifi_models::{
     roblox::id::UserId as RobloxUserId,
 };
 
+use crate::utils::truncated_list;
+
 #[derive(FromArgs)]
 pub struct EventAttendeeArguments {
     #[arg(help = "The roblox username of the attendee")]
@@ -20,15 +21,7 @@ pub async fn event_attendee(ctx: CommandContext, args: EventAttendeeArguments) -
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let roblox_id = match args.username {
@@ -80,7 +73,7 @@ pub async fn event_attendee(ctx: CommandContext, args: EventAttendeeArguments) -
         .bot
         .database
         .query::<EventLog>(
-            "SELECT * FROM events WHERE guild_id = $1 AND $2 = ANY(attendees)",
+            "SELECT * FROM events WHERE guild_id = $1 AND $2 = ANY(attendees) AND voided_at IS NULL",
             &[&(guild_id), &roblox_id],
         )
         .await?;
@@ -134,15 +127,7 @@ pub async fn event_host(ctx: CommandContext, args: EventHostArguments) -> Comman
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let roblox_id = match args.username {
@@ -195,7 +180,7 @@ pub async fn event_host(ctx: CommandContext, args: EventHostArguments) -> Comman
         .bot
         .database
         .query::<EventLog>(
-            "SELECT * FROM events WHERE guild_id = $1 AND host_id = $2",
+            "SELECT * FROM events WHERE guild_id = $1 AND host_id = $2 AND voided_at IS NULL",
             &[&(guild_id), &roblox_id],
         )
         .await?;
@@ -249,15 +234,7 @@ pub async fn event_view(ctx: CommandContext, args: EventViewArguments) -> Comman
     let guild = ctx.bot.database.get_guild(guild_id).await?;
 
     if guild.kind != GuildType::Beta {
-        let embed = EmbedBuilder::new()
-            .default_data()
-            .color(Color::Red as u32)
-            .title("Command Failed")
-            .description("This module may only be used in Beta Tier Servers")
-            .build()
-            .unwrap();
-        ctx.respond().embeds(&[embed])?.exec().await?;
-        return Ok(());
+        return beta_only(&ctx).await;
     }
 
     let event_id = args.event_id;
@@ -302,15 +279,26 @@ pub async fn event_view(ctx: CommandContext, args: EventViewArguments) -> Comman
         .roblox
         .get_user(RobloxUserId(event.host_id as u64), false)
         .await?;
-    let mut attendees = Vec::new();
-    for a in &event.attendees {
-        let roblox_name = ctx
-            .bot
-            .roblox
-            .get_user(RobloxUserId(*a as u64), false)
-            .await?;
-        attendees.push(roblox_name);
+    let mut users = Vec::new();
+    for chunk in event.attendees.chunks(100) {
+        let user_ids = chunk
+            .iter()
+            .map(|a| RobloxUserId(*a as u64))
+            .collect::<Vec<_>>();
+        users.extend(ctx.bot.roblox.get_users(&user_ids).await?);
     }
+    // Attendees whose account no longer exists are shown by their id
+    let attendees = event
+        .attendees
+        .iter()
+        .map(|a| {
+            let name = users
+                .iter()
+                .find(|u| u.id.0 as i64 == *a)
+                .map_or_else(|| a.to_string(), |u| u.name.clone());
+            format!("- {}", name)
+        })
+        .collect::<Vec<_>>();
 
     let mut embed = EmbedBuilder::new()
         .default_data()
@@ -325,21 +313,37 @@ pub async fn event_view(ctx: CommandContext, args: EventViewArguments) -> Comman
     if !event.attendees.is_empty() {
         embed = embed.field(EmbedFieldBuilder::new(
             "Attendees",
-            attendees.iter().map(|a| format!("- {}", a.name)).join("\n"),
+            truncated_list(&attendees),
+        ));
+    }
+
+    for field in &event.fields {
+        embed =
+            embed.field(EmbedFieldBuilder::new(field.name.clone(), field.value.clone()).inline());
+    }
+
+    if let Some(voided_at) = event.voided_at {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Voided",
+            format!(
+                "<t:{}:f>\nReason: {}",
+                voided_at.timestamp(),
+                event.void_reason.as_deref().unwrap_or("N/A")
+            ),
         ));
     }
 
     if let Some(notes_bytes) = &event.notes {
         let notes = decrypt_bytes(
             notes_bytes,
-            &ctx.bot.database.cipher,
+            &ctx.bot.database.keyring,
             guild_id.get(),
             event.host_id as u64,
-            event.timestamp.timestamp() as u64,
-        );
+            event.notes_timestamp().timestamp() as u64,
+        )?;
         embed = embed.field(EmbedFieldBuilder::new(
             "Notes",
-            String::from_utf8(notes).unwrap(),
+            String::from_utf8_lossy(&notes),
         ));
     }
 
@@ -3,7 +3,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use rowifi_framework::prelude::*;
 use rowifi_models::{
-    bind::{AssetType, Assetbind, BindType, Groupbind, Rankbind, Template},
+    bind::{AssetType, Assetbind, BindType, Groupbind, Premiumbind, Rankbind, Template},
     id::{BindId, GuildId, RoleId},
     roblox::{group::PartialRank, id::GroupId},
 };
@@ -47,6 +47,13 @@ pub async fn bind(ctx: CommandContext) -> CommandResult {
                 label: "Group".into(),
                 value: "group".into(),
             },
+            SelectMenuOption {
+                default: false,
+                description: Some("Bind the members with a Roblox Premium membership".into()),
+                emoji: None,
+                label: "Premium".into(),
+                value: "premium".into(),
+            },
         ],
         placeholder: None,
     };
@@ -136,6 +143,7 @@ pub async fn bind(ctx: CommandContext) -> CommandResult {
         "custom" => bind_custom(ctx, guild_id).await?,
         "asset" => bind_asset(ctx, guild_id).await?,
         "group" => bind_group(ctx, guild_id).await?,
+        "premium" => bind_premium(ctx, guild_id).await?,
         _ => {}
     }
 
@@ -652,6 +660,159 @@ async fn bind_group(ctx: CommandContext, guild_id: GuildId) -> CommandResult {
     Ok(())
 }
 
+async fn bind_premium(ctx: CommandContext, guild_id: GuildId) -> CommandResult {
+    let select_menu = SelectMenu {
+        custom_id: "template-reply".into(),
+        disabled: false,
+        max_values: Some(1),
+        min_values: Some(1),
+        options: vec![
+            SelectMenuOption {
+                default: true,
+                description: Some("Sets the nickname as just the roblox username".into()),
+                emoji: None,
+                label: "{roblox-username}".into(),
+                value: "{roblox-username}".into(),
+            },
+            SelectMenuOption {
+                default: false,
+                description: Some("Sets the nickname as the roblox id of the user".into()),
+                emoji: None,
+                label: "{roblox-id}".into(),
+                value: "{roblox-id}".into(),
+            },
+            SelectMenuOption {
+                default: false,
+                description: Some("Sets the nickname as the discord id of the user".into()),
+                emoji: None,
+                label: "{discord-id}".into(),
+                value: "{discord-id}".into(),
+            },
+            SelectMenuOption {
+                default: false,
+                description: Some("Sets the nickname as the discord username".into()),
+                emoji: None,
+                label: "{discord-name}".into(),
+                value: "{discord-name}".into(),
+            },
+            SelectMenuOption {
+                default: false,
+                description: Some("Sets the nickname as the display name on Roblox".into()),
+                emoji: None,
+                label: "{display-name}".into(),
+                value: "{display-name}".into(),
+            },
+        ],
+        placeholder: None,
+    };
+    let template = await_template_reply(
+        "Enter the template you wish to set for the bind.\nSelect one of the below or enter your own.",
+        &ctx,
+        select_menu
+    )
+    .await?;
+
+    let priority = match await_reply("Enter the priority you wish to set for the bind.", &ctx)
+        .await?
+        .parse::<i32>()
+    {
+        Ok(p) => p,
+        Err(_) => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Bind Addition Failed")
+                .description("Expected priority to be a number")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    };
+
+    let server_roles = ctx.bot.cache.roles(guild_id);
+    let discord_roles_str = await_reply("Enter the roles you wish to set for the bind.\nEnter `N/A` if you would not like to set roles. Please tag the roles to ensure the bot can recognize them.", &ctx).await?;
+    let mut discord_roles = Vec::new();
+    for role_str in discord_roles_str.split_ascii_whitespace() {
+        if let Some(role_id) = parse_role(role_str) {
+            if server_roles.contains(&role_id) {
+                discord_roles.push(role_id);
+            }
+        }
+    }
+
+    let bind = Premiumbind {
+        // default is entered here since this field is not used in the insertion. The struct is only constructed to ensure we have
+        // collected all fields.
+        bind_id: BindId::default(),
+        discord_roles: discord_roles.into_iter().unique().collect::<Vec<_>>(),
+        priority,
+        template,
+    };
+
+    // A server only needs a single premium bind, so creating another one replaces the existing bind
+    let existing = ctx
+        .bot
+        .database
+        .query_opt::<Premiumbind>(
+            "SELECT * FROM binds WHERE guild_id = $1 AND bind_type = $2",
+            &[&(guild_id), &BindType::Premium],
+        )
+        .await?;
+    match &existing {
+        Some(existing) => {
+            ctx.bot
+                .database
+                .execute(
+                    "UPDATE binds SET priority = $1, template = $2, discord_roles = $3 WHERE bind_id = $4",
+                    &[&bind.priority, &bind.template, &bind.discord_roles, &existing.bind_id],
+                )
+                .await?;
+        }
+        None => {
+            ctx.bot.database.execute(
+                "INSERT INTO binds(bind_type, guild_id, discord_roles, priority, template) VALUES($1, $2, $3, $4, $5)",
+                &[&BindType::Premium, &(guild_id), &bind.discord_roles, &bind.priority, &bind.template]
+            ).await?;
+        }
+    }
+
+    let name = "Premium".to_string();
+    let value = format!(
+        "Template: `{}`\nPriority: {}\nRoles: {}",
+        bind.template,
+        bind.priority,
+        bind.discord_roles
+            .iter()
+            .map(|r| format!("<@&{}> ", r))
+            .collect::<String>()
+    );
+    let (title, description) = if existing.is_some() {
+        ("Bind Modification Successful", "Premium Bind Modification")
+    } else {
+        ("Bind Addition Successful", "Premium Bind Addition")
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title(title)
+        .color(Color::DarkGreen as u32)
+        .field(EmbedFieldBuilder::new(name.clone(), value.clone()))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(description)
+        .field(EmbedFieldBuilder::new(name, value))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+
+    Ok(())
+}
+
 async fn bind_rank(
     ctx: CommandContext,
     guild_id: GuildId,
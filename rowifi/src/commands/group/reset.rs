@@ -1,7 +1,8 @@
 use rowifi_framework::prelude::*;
 use rowifi_models::{
+    bind::Template,
     blacklist::Blacklist,
-    guild::{BlacklistActionType, GuildType},
+    guild::{BlacklistActionType, GuildType, InvalidAccountAction},
     id::{ChannelId, RoleId, UserId},
 };
 
@@ -42,31 +43,40 @@ pub async fn reset(ctx: CommandContext) -> CommandResult {
     }
 
     let upsert = transaction.prepare_cached(
-        r#"INSERT INTO guilds(guild_id, kind, premium_owner, command_prefix, verification_roles, verified_roles, blacklists, disabled_channels, registered_groups, auto_detection, blacklist_action, update_on_join, admin_roles, trainer_roles, bypass_roles, nickname_bypass_roles, log_channel)
-        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17) ON CONFLICT (guild_id) DO UPDATE SET
-        kind = $2, premium_owner = $3, command_prefix = $4, verification_roles = $5, verified_roles = $6, blacklists = $7, disabled_channels = $8, registered_groups = $9, auto_detection = $10, blacklist_action = $11, update_on_join = $12, admin_roles = $13, trainer_roles = $14, bypass_roles = $15, nickname_bypass_roles = $16, log_channel = $17"#
+        r#"INSERT INTO guilds(guild_id, kind, premium_owner, command_prefix, verification_roles, verified_roles, blacklists, disabled_channels, registered_groups, auto_detection, blacklist_action, update_on_join, admin_roles, trainer_roles, bypass_roles, nickname_bypass_roles, log_channel, update_on_role_change, unverified_template, verification_reminder, unverified_kick, invalid_account_action, quarantine_role, blacklist_timeout, quota_channel, quota_exempt_roles)
+        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26) ON CONFLICT (guild_id) DO UPDATE SET
+        kind = $2, premium_owner = $3, command_prefix = $4, verification_roles = $5, verified_roles = $6, blacklists = $7, disabled_channels = $8, registered_groups = $9, auto_detection = $10, blacklist_action = $11, update_on_join = $12, admin_roles = $13, trainer_roles = $14, bypass_roles = $15, nickname_bypass_roles = $16, log_channel = $17, update_on_role_change = $18, unverified_template = $19, verification_reminder = $20, unverified_kick = $21, invalid_account_action = $22, quarantine_role = $23, blacklist_timeout = $24, quota_channel = $25, quota_exempt_roles = $26, quota_reported_at = NULL"#
     ).await?;
     transaction
         .execute(
             &upsert,
             &[
-                &guild_id,                  // guild_id
-                &GuildType::Free,           // kind
-                &None::<UserId>,            // premium_owner
-                &"!",                       // command_prefix
-                &Vec::<RoleId>::new(),      // verification_roles
-                &Vec::<RoleId>::new(),      // verified_roles
-                &Vec::<Blacklist>::new(),   // blacklists
-                &Vec::<ChannelId>::new(),   // disabled_channels
-                &Vec::<i64>::new(),         // registered_groups
-                &false,                     // auto_detection
-                &BlacklistActionType::None, // blacklist_action
-                &false,                     // update_on_join
-                &Vec::<RoleId>::new(),      // admin_roles
-                &Vec::<RoleId>::new(),      // trainer_roles
-                &Vec::<RoleId>::new(),      // bypass_roles
-                &Vec::<RoleId>::new(),      // nickname_bypass_roles
-                &None::<ChannelId>,         // log_channel
+                &guild_id,                   // guild_id
+                &GuildType::Free,            // kind
+                &None::<UserId>,             // premium_owner
+                &"!",                        // command_prefix
+                &Vec::<RoleId>::new(),       // verification_roles
+                &Vec::<RoleId>::new(),       // verified_roles
+                &Vec::<Blacklist>::new(),    // blacklists
+                &Vec::<ChannelId>::new(),    // disabled_channels
+                &Vec::<i64>::new(),          // registered_groups
+                &false,                      // auto_detection
+                &BlacklistActionType::None,  // blacklist_action
+                &false,                      // update_on_join
+                &Vec::<RoleId>::new(),       // admin_roles
+                &Vec::<RoleId>::new(),       // trainer_roles
+                &Vec::<RoleId>::new(),       // bypass_roles
+                &Vec::<RoleId>::new(),       // nickname_bypass_roles
+                &None::<ChannelId>,          // log_channel
+                &false,                      // update_on_role_change
+                &None::<Template>,           // unverified_template
+                &None::<i32>,                // verification_reminder
+                &None::<i32>,                // unverified_kick
+                &InvalidAccountAction::None, // invalid_account_action
+                &None::<RoleId>,             // quarantine_role
+                &None::<i32>,                // blacklist_timeout
+                &None::<ChannelId>,          // quota_channel
+                &Vec::<RoleId>::new(),       // quota_exempt_roles
             ],
         )
         .await?;
@@ -76,6 +86,30 @@ pub async fn reset(ctx: CommandContext) -> CommandResult {
         .await?;
     transaction.execute(&delete_binds, &[&(guild_id)]).await?;
 
+    let delete_registrations = transaction
+        .prepare_cached("DELETE FROM analytics_registrations WHERE guild_id = $1")
+        .await?;
+    transaction
+        .execute(&delete_registrations, &[&(guild_id)])
+        .await?;
+
+    let delete_alerts = transaction
+        .prepare_cached("DELETE FROM analytics_alerts WHERE guild_id = $1")
+        .await?;
+    transaction.execute(&delete_alerts, &[&(guild_id)]).await?;
+
+    let delete_pending = transaction
+        .prepare_cached("DELETE FROM analytics_pending_registrations WHERE guild_id = $1")
+        .await?;
+    transaction.execute(&delete_pending, &[&(guild_id)]).await?;
+
+    let delete_subscriptions = transaction
+        .prepare_cached("DELETE FROM blacklist_subscriptions WHERE guild_id = $1")
+        .await?;
+    transaction
+        .execute(&delete_subscriptions, &[&(guild_id)])
+        .await?;
+
     transaction.commit().await?;
 
     ctx.bot.admin_roles.remove(&guild_id);
@@ -83,6 +117,7 @@ pub async fn reset(ctx: CommandContext) -> CommandResult {
     ctx.bot.bypass_roles.remove(&guild_id);
     ctx.bot.nickname_bypass_roles.remove(&guild_id);
     ctx.bot.log_channels.remove(&guild_id);
+    ctx.bot.role_update_guilds.remove(&guild_id);
 
     let embed = EmbedBuilder::new()
         .default_data()
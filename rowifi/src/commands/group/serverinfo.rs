@@ -105,6 +105,17 @@ pub async fn serverinfo(ctx: CommandContext) -> CommandResult {
             )
             .inline(),
         )
+        .field(
+            EmbedFieldBuilder::new(
+                "Premiumbinds",
+                rows.iter()
+                    .find(|r| r.bind_type == BindType::Premium)
+                    .map(|r| r.count)
+                    .unwrap_or_default()
+                    .to_string(),
+            )
+            .inline(),
+        )
         .build()
         .unwrap();
     ctx.respond().embeds(&[embed])?.exec().await?;
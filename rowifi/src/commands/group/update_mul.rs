@@ -9,7 +9,7 @@ use std::{env, sync::atomic::Ordering};
 use tokio::time::sleep;
 use twilight_gateway::Event;
 
-use crate::services::auto_detection::{execute_chunk, mass_update_user};
+use crate::{services::auto_detection::execute_chunk, utils::mass_update_user};
 
 pub async fn update_all(ctx: CommandContext) -> CommandResult {
     let guild_id = ctx.guild_id.unwrap();
@@ -106,6 +106,7 @@ pub async fn update_all(ctx: CommandContext) -> CommandResult {
         .await?;
 
     let guild_roles = ctx.bot.cache.roles(guild_id);
+    let subscribed_blacklists = ctx.bot.database.get_subscribed_blacklists(guild_id).await?;
     let c = ctx.clone();
     let channel_id = ctx.channel_id;
 
@@ -136,6 +137,7 @@ pub async fn update_all(ctx: CommandContext) -> CommandResult {
                         &ctx.bot,
                         &server,
                         &guild,
+                        &subscribed_blacklists,
                         &guild_roles,
                         false,
                         None,
@@ -269,6 +271,7 @@ pub async fn update_role(ctx: CommandContext, args: UpdateMultipleArguments) ->
         .query::<Bind>("SELECT * FROM binds WHERE guild_id = $1", &[&(guild_id)])
         .await?;
     let guild_roles = ctx.bot.cache.roles(guild_id);
+    let subscribed_blacklists = ctx.bot.database.get_subscribed_blacklists(guild_id).await?;
     let c = ctx.clone();
     let channel_id = ctx.channel_id;
 
@@ -299,6 +302,7 @@ pub async fn update_role(ctx: CommandContext, args: UpdateMultipleArguments) ->
                         &ctx.bot,
                         &server,
                         &guild,
+                        &subscribed_blacklists,
                         &guild_roles,
                         false,
                         Some(role_id),
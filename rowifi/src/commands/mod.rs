@@ -8,6 +8,7 @@ mod events;
 mod group;
 mod groupbinds;
 mod premium;
+mod premiumbinds;
 mod rankbinds;
 mod settings;
 mod user;
@@ -22,6 +23,7 @@ pub use events::*;
 pub use group::*;
 pub use groupbinds::*;
 pub use premium::*;
+pub use premiumbinds::*;
 pub use rankbinds::*;
 pub use settings::*;
 pub use user::*;
@@ -0,0 +1,166 @@
+use rowifi_framework::prelude::*;
+use rowifi_models::bind::{BindType, Premiumbind};
+
+pub async fn premiumbinds_delete(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let premiumbind = ctx
+        .bot
+        .database
+        .query_opt::<Premiumbind>(
+            "DELETE FROM binds WHERE guild_id = $1 AND bind_type = $2 RETURNING *",
+            &[&(guild_id), &BindType::Premium],
+        )
+        .await?;
+
+    let premiumbind = match premiumbind {
+        Some(p) => p,
+        None => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Bind Deletion Failed")
+                .description("There was no premium bind found associated with this server")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    };
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Success!")
+        .description("The premium bind was successfully deleted")
+        .build()
+        .unwrap();
+    let message = ctx
+        .respond()
+        .embeds(&[embed])?
+        .components(&[Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                style: ButtonStyle::Danger,
+                emoji: Some(ReactionType::Unicode {
+                    name: "↩️".into()
+                }),
+                label: Some("Uh oh? Revert".into()),
+                custom_id: Some("pb-delete-revert".into()),
+                url: None,
+                disabled: false,
+            })],
+        })])?
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description("Premium Bind Deletion")
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+
+    let message_id = message.id;
+    let author_id = ctx.author.id;
+
+    let stream = ctx
+        .bot
+        .standby
+        .wait_for_component_interaction(message_id)
+        .timeout(Duration::from_secs(60));
+    tokio::pin!(stream);
+
+    ctx.bot.ignore_message_components.insert(message_id);
+    while let Some(Ok(event)) = stream.next().await {
+        if let Event::InteractionCreate(interaction) = &event {
+            if let Interaction::MessageComponent(message_component) = &interaction.0 {
+                let component_interaction_author = message_component.author_id().unwrap();
+                if component_interaction_author == author_id {
+                    ctx.bot
+                        .http
+                        .interaction(ctx.bot.application_id)
+                        .interaction_callback(
+                            message_component.id,
+                            &message_component.token,
+                            &InteractionResponse::UpdateMessage(CallbackData {
+                                allowed_mentions: None,
+                                content: None,
+                                components: Some(Vec::new()),
+                                embeds: None,
+                                flags: None,
+                                tts: None,
+                            }),
+                        )
+                        .exec()
+                        .await?;
+
+                    // A bind may have been created in the meantime, which the restored bind replaces
+                    let mut db = ctx.bot.database.get().await?;
+                    let transaction = db.transaction().await?;
+                    let delete = transaction
+                        .prepare_cached("DELETE FROM binds WHERE guild_id = $1 AND bind_type = $2")
+                        .await?;
+                    transaction
+                        .execute(&delete, &[&(guild_id), &BindType::Premium])
+                        .await?;
+                    let insert = transaction.prepare_cached("INSERT INTO binds(bind_type, guild_id, discord_roles, priority, template) VALUES($1, $2, $3, $4, $5)").await?;
+                    transaction
+                        .execute(
+                            &insert,
+                            &[
+                                &BindType::Premium,
+                                &(guild_id),
+                                &premiumbind.discord_roles,
+                                &premiumbind.priority,
+                                &premiumbind.template,
+                            ],
+                        )
+                        .await?;
+                    transaction.commit().await?;
+
+                    let embed = EmbedBuilder::new()
+                        .default_data()
+                        .color(Color::DarkGreen as u32)
+                        .title("Restoration Successful!")
+                        .description("The deleted bind was successfully restored")
+                        .build()
+                        .unwrap();
+                    ctx.bot
+                        .http
+                        .interaction(ctx.bot.application_id)
+                        .create_followup_message(&message_component.token)
+                        .embeds(&[embed])?
+                        .exec()
+                        .await?;
+
+                    break;
+                }
+                let _ = ctx
+                    .bot
+                    .http
+                    .interaction(ctx.bot.application_id)
+                    .interaction_callback(
+                        message_component.id,
+                        &message_component.token,
+                        &InteractionResponse::DeferredUpdateMessage,
+                    )
+                    .exec()
+                    .await;
+                let _ = ctx
+                    .bot
+                    .http
+                    .interaction(ctx.bot.application_id)
+                    .create_followup_message(&message_component.token)
+                    .ephemeral(true)
+                    .content("This button is only interactable by the original command invoker")?
+                    .exec()
+                    .await;
+            }
+        }
+    }
+    ctx.bot.ignore_message_components.remove(&message_id);
+
+    Ok(())
+}
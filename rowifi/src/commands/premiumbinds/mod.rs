@@ -0,0 +1,75 @@
+mod delete;
+
+use rowifi_framework::prelude::*;
+use rowifi_models::bind::{BindType, Premiumbind};
+
+pub use delete::premiumbinds_delete;
+
+pub fn premiumbinds_config(cmds: &mut Vec<Command>) {
+    let premiumbinds_view_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["view"])
+        .description("Command to view the premium bind of the server")
+        .handler(premiumbinds_view);
+
+    let premiumbinds_delete_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["delete", "d", "remove"])
+        .description("Command to delete the premium bind")
+        .handler(premiumbinds_delete);
+
+    let premiumbinds_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["premiumbinds", "pb"])
+        .description("Module to view & delete the premium bind of the server")
+        .group("Binds")
+        .sub_command(premiumbinds_view_cmd)
+        .sub_command(premiumbinds_delete_cmd)
+        .handler(premiumbinds_view);
+    cmds.push(premiumbinds_cmd);
+}
+
+pub async fn premiumbinds_view(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let premiumbind = ctx
+        .bot
+        .database
+        .query_opt::<Premiumbind>(
+            "SELECT * FROM binds WHERE guild_id = $1 AND bind_type = $2",
+            &[&(guild_id), &BindType::Premium],
+        )
+        .await?;
+
+    let premiumbind = match premiumbind {
+        Some(p) => p,
+        None => {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .title("Bind Viewing Failed")
+                .color(Color::Red as u32)
+                .description("No premium bind was found associated with this server")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    };
+
+    let desc = format!(
+        "Template: {}\nPriority: {}\nRoles: {}",
+        premiumbind.template,
+        premiumbind.priority,
+        premiumbind
+            .discord_roles
+            .iter()
+            .map(|r| format!("<@&{}> ", r))
+            .collect::<String>()
+    );
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .title("Premium Bind")
+        .field(EmbedFieldBuilder::new("Premium", desc))
+        .build()?;
+    ctx.respond().embeds(&[embed])?.exec().await?;
+    Ok(())
+}
@@ -1,12 +1,16 @@
 use rowifi_framework::prelude::*;
-use rowifi_models::guild::{BlacklistActionType, GuildType};
+use rowifi_models::{
+    guild::{BlacklistActionType, GuildType, InvalidAccountAction},
+    id::RoleId,
+};
 
 use super::ToggleOption;
+use crate::utils::DEFAULT_BLACKLIST_TIMEOUT;
 
 #[derive(FromArgs)]
 pub struct BlacklistActionArguments {
     #[arg(
-        help = "The action to be performed on detecting a blacklist. Must be one of `None` `Kick` `Ban`"
+        help = "The action to be performed on detecting a blacklist. Must be one of `None` `Kick` `Ban` `Quarantine` `Timeout` `DM-Kick`"
     )]
     pub option: BlacklistActionType,
 }
@@ -52,6 +56,182 @@ pub async fn blacklist_action(
     Ok(())
 }
 
+#[derive(FromArgs)]
+pub struct QuarantineRoleArguments {
+    #[arg(
+        help = "The role given to members quarantined by a blacklist. Leave empty to stop giving a role"
+    )]
+    pub role: Option<RoleId>,
+}
+
+pub async fn quarantine_role(ctx: CommandContext, args: QuarantineRoleArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if let Some(role) = args.role {
+        if !ctx.bot.cache.roles(guild_id).contains(&role) {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Settings Modification Failed")
+                .description("The given role does not exist in this server")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET quarantine_role = $1 WHERE guild_id = $2",
+            &[&args.role, &guild.guild_id],
+        )
+        .await?;
+
+    let desc = match args.role {
+        Some(role) => format!(
+            "Members quarantined by a blacklist will now be given <@&{}>",
+            role
+        ),
+        None => "Members quarantined by a blacklist will no longer be given a role".into(),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Quarantine Role - {} -> {}",
+            guild
+                .quarantine_role
+                .map_or_else(|| "None".into(), |r| format!("<@&{}>", r)),
+            args.role
+                .map_or_else(|| "None".into(), |r| format!("<@&{}>", r))
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct BlacklistTimeoutArguments {
+    #[arg(help = "The number of hours members are timed out for by a blacklist. At most 672")]
+    pub hours: i32,
+}
+
+pub async fn blacklist_timeout(
+    ctx: CommandContext,
+    args: BlacklistTimeoutArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    // Discord does not allow timeouts longer than 28 days
+    if args.hours <= 0 || args.hours > 672 {
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .color(Color::Red as u32)
+            .title("Settings Modification Failed")
+            .description("The timeout must be between 1 and 672 hours")
+            .build()
+            .unwrap();
+        ctx.respond().embeds(&[embed])?.exec().await?;
+        return Ok(());
+    }
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET blacklist_timeout = $1 WHERE guild_id = $2",
+            &[&args.hours, &guild.guild_id],
+        )
+        .await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(format!(
+            "Members will now be timed out for {} hours by a blacklist",
+            args.hours
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Blacklist Timeout - {} hours -> {} hours",
+            guild.blacklist_timeout.unwrap_or(DEFAULT_BLACKLIST_TIMEOUT),
+            args.hours
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct InvalidAccountActionArguments {
+    #[arg(
+        help = "The action to be performed on members whose Roblox account is banned or deleted. Must be one of `None` `Notify` `Remove-Roles` `Unlink`"
+    )]
+    pub option: InvalidAccountAction,
+}
+
+pub async fn invalid_account_action(
+    ctx: CommandContext,
+    args: InvalidAccountActionArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let action = args.option;
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET invalid_account_action = $1 WHERE guild_id = $2",
+            &[&action, &guild.guild_id],
+        )
+        .await?;
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(format!(
+            "Invalid account action has successfully been set to {}",
+            action
+        ))
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Invalid Account Action - {} -> {}",
+            guild.invalid_account_action, action
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
 #[derive(FromArgs)]
 pub struct ToggleCommandsArguments {
     #[arg(
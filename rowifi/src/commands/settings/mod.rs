@@ -5,22 +5,29 @@ mod log;
 mod misc;
 mod nickname_bypass;
 mod trainer;
+mod unverified;
 mod update;
 mod verify;
 
 use itertools::Itertools;
 use rowifi_framework::prelude::*;
 
+use crate::utils::DEFAULT_BLACKLIST_TIMEOUT;
+
 use admin::{admin_add, admin_remove, admin_set, admin_view};
 use bypass::{bypass_add, bypass_remove, bypass_set, bypass_view};
 use functional::functional;
 use log::log_channel;
-use misc::{blacklist_action, settings_prefix, toggle_ad, toggle_commands};
+use misc::{
+    blacklist_action, blacklist_timeout, invalid_account_action, quarantine_role, settings_prefix,
+    toggle_ad, toggle_commands,
+};
 use nickname_bypass::{
     nickname_bypass_add, nickname_bypass_remove, nickname_bypass_set, nickname_bypass_view,
 };
 use trainer::{trainer_add, trainer_remove, trainer_set, trainer_view};
-use update::update_on_join;
+use unverified::{unverified_kick, unverified_nickname, verification_reminder};
+use update::{update_on_join, update_on_role_change};
 use verify::{
     settings_verification_add, settings_verification_remove, settings_verified_add,
     settings_verified_remove,
@@ -39,6 +46,26 @@ pub fn settings_config(cmds: &mut Vec<Command>) {
         .description("Command to set the blacklist action setting")
         .handler(blacklist_action);
 
+    let settings_quarantine_role_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["quarantine-role", "quarantine"])
+        .description("Command to set the role given to members quarantined by a blacklist")
+        .handler(quarantine_role);
+
+    let settings_blacklist_timeout_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["blacklist-timeout", "bl-timeout"])
+        .description("Command to set how long members are timed out for by a blacklist")
+        .handler(blacklist_timeout);
+
+    let settings_invalid_account_action_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["invalid-account-action", "ia-action"])
+        .description(
+            "Command to set the action taken on members whose Roblox account is banned or deleted",
+        )
+        .handler(invalid_account_action);
+
     let settings_toggle_commands_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["commands", "command-channel", "command"])
@@ -57,6 +84,12 @@ pub fn settings_config(cmds: &mut Vec<Command>) {
         .description("Command to toggle the `Update On Join` setting in the server")
         .handler(update_on_join);
 
+    let update_on_role_change_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["update-on-role-change", "uorc"])
+        .description("Command to toggle the `Update On Role Change` setting in the server")
+        .handler(update_on_role_change);
+
     let settings_verification_add_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["add"])
@@ -69,12 +102,33 @@ pub fn settings_config(cmds: &mut Vec<Command>) {
         .description("Command to remove verification roles")
         .handler(settings_verification_remove);
 
+    let settings_unverified_nickname_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["nickname", "nick"])
+        .description("Command to set the nickname given to unverified members on join")
+        .handler(unverified_nickname);
+
+    let settings_verification_reminder_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["reminder", "reminders"])
+        .description("Command to set how often unverified members are reminded to verify")
+        .handler(verification_reminder);
+
+    let settings_unverified_kick_cmd = Command::builder()
+        .level(RoLevel::Admin)
+        .names(&["kick", "auto-kick"])
+        .description("Command to set after how many hours unverified members are kicked")
+        .handler(unverified_kick);
+
     let settings_verification_cmd = Command::builder()
         .level(RoLevel::Admin)
         .names(&["verification", "unverified"])
-        .description("Module to add or remove verification roles")
+        .description("Module to manage verification roles and the handling of unverified members")
         .sub_command(settings_verification_add_cmd)
         .sub_command(settings_verification_remove_cmd)
+        .sub_command(settings_unverified_nickname_cmd)
+        .sub_command(settings_verification_reminder_cmd)
+        .sub_command(settings_unverified_kick_cmd)
         .no_handler();
 
     let settings_verified_add_cmd = Command::builder()
@@ -258,9 +312,13 @@ pub fn settings_config(cmds: &mut Vec<Command>) {
         .group("Administration")
         .sub_command(settings_view_cmd)
         .sub_command(settings_blacklist_action_cmd)
+        .sub_command(settings_quarantine_role_cmd)
+        .sub_command(settings_blacklist_timeout_cmd)
+        .sub_command(settings_invalid_account_action_cmd)
         .sub_command(settings_toggle_commands_cmd)
         .sub_command(settings_prefix_cmd)
         .sub_command(update_on_join_cmd)
+        .sub_command(update_on_role_change_cmd)
         .sub_command(settings_verification_cmd)
         .sub_command(settings_verified_cmd)
         .sub_command(settings_admin_cmd)
@@ -302,9 +360,69 @@ pub async fn settings_view(ctx: CommandContext) -> CommandResult {
         .field(
             EmbedFieldBuilder::new("Blacklist Action", guild.blacklist_action.to_string()).inline(),
         )
+        .field(
+            EmbedFieldBuilder::new(
+                "Quarantine Role",
+                guild
+                    .quarantine_role
+                    .map_or_else(|| "None".into(), |r| format!("<@&{}>", r)),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                "Blacklist Timeout",
+                format!(
+                    "{} hours",
+                    guild.blacklist_timeout.unwrap_or(DEFAULT_BLACKLIST_TIMEOUT)
+                ),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                "Invalid Account Action",
+                guild.invalid_account_action.to_string(),
+            )
+            .inline(),
+        )
         .field(EmbedFieldBuilder::new("Update On Join", guild.update_on_join.to_string()).inline())
+        .field(
+            EmbedFieldBuilder::new(
+                "Update On Role Change",
+                guild.update_on_role_change.to_string(),
+            )
+            .inline(),
+        )
         .field(EmbedFieldBuilder::new("Verification Role", verification_roles).inline())
         .field(EmbedFieldBuilder::new("Verified Role", verified_roles).inline())
+        .field(
+            EmbedFieldBuilder::new(
+                "Unverified Nickname",
+                guild
+                    .unverified_template
+                    .map_or_else(|| "Disabled".into(), |t| t.to_string()),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                "Verification Reminder",
+                guild
+                    .verification_reminder
+                    .map_or_else(|| "Disabled".into(), |h| format!("Every {} hours", h)),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                "Unverified Kick",
+                guild
+                    .unverified_kick
+                    .map_or_else(|| "Disabled".into(), |h| format!("After {} hours", h)),
+            )
+            .inline(),
+        )
         .build()
         .unwrap();
 
@@ -0,0 +1,214 @@
+use rowifi_framework::prelude::*;
+use rowifi_models::bind::Template;
+
+#[derive(FromArgs)]
+pub struct UnverifiedNicknameArguments {
+    #[arg(
+        help = "The nickname template for unverified members. Use `disable` to stop changing their nicknames",
+        rest
+    )]
+    pub template: String,
+}
+
+pub async fn unverified_nickname(
+    ctx: CommandContext,
+    args: UnverifiedNicknameArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let template = args.template.trim();
+    let template = if template.eq_ignore_ascii_case("disable") || template.is_empty() {
+        None
+    } else {
+        if template.contains("{roblox-username}")
+            || template.contains("{roblox-id}")
+            || template.contains("{display-name}")
+        {
+            let embed = EmbedBuilder::new()
+                .default_data()
+                .color(Color::Red as u32)
+                .title("Settings Modification Failed")
+                .description("Unverified members have no Roblox account. Only the `{discord-name}` and `{discord-id}` slugs may be used")
+                .build()
+                .unwrap();
+            ctx.respond().embeds(&[embed])?.exec().await?;
+            return Ok(());
+        }
+        Some(Template(template.to_string()))
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET unverified_template = $1 WHERE guild_id = $2",
+            &[&template, &guild.guild_id],
+        )
+        .await?;
+
+    let desc = match &template {
+        Some(t) => format!(
+            "The unverified nickname has successfully been set to `{}`",
+            t
+        ),
+        None => "Unverified members will no longer have their nickname changed".into(),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Unverified Nickname - {} -> {}",
+            display_template(guild.unverified_template.as_ref()),
+            display_template(template.as_ref())
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct VerificationReminderArguments {
+    #[arg(help = "The number of hours between reminders to unverified members. Use 0 to disable")]
+    pub hours: i32,
+}
+
+pub async fn verification_reminder(
+    ctx: CommandContext,
+    args: VerificationReminderArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if args.hours < 0 {
+        return respond_failure(
+            &ctx,
+            "Settings Modification Failed",
+            "The number of hours cannot be negative",
+        )
+        .await;
+    }
+    let hours = if args.hours == 0 {
+        None
+    } else {
+        Some(args.hours)
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET verification_reminder = $1 WHERE guild_id = $2",
+            &[&hours, &guild.guild_id],
+        )
+        .await?;
+
+    let desc = match hours {
+        Some(h) => format!(
+            "Unverified members will now be reminded to verify every {} hours",
+            h
+        ),
+        None => "Unverified members will no longer be reminded to verify".into(),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Verification Reminder - {} -> {}",
+            display_hours(guild.verification_reminder),
+            display_hours(hours)
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+pub struct UnverifiedKickArguments {
+    #[arg(
+        help = "The number of hours after which unverified members are kicked. Use 0 to disable"
+    )]
+    pub hours: i32,
+}
+
+pub async fn unverified_kick(ctx: CommandContext, args: UnverifiedKickArguments) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    if args.hours < 0 {
+        return respond_failure(
+            &ctx,
+            "Settings Modification Failed",
+            "The number of hours cannot be negative",
+        )
+        .await;
+    }
+    let hours = if args.hours == 0 {
+        None
+    } else {
+        Some(args.hours)
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET unverified_kick = $1 WHERE guild_id = $2",
+            &[&hours, &guild.guild_id],
+        )
+        .await?;
+
+    let desc = match hours {
+        Some(h) => format!(
+            "Members who join and do not verify within {} hours will now be kicked",
+            h
+        ),
+        None => "Unverified members will no longer be kicked".into(),
+    };
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Unverified Kick - {} -> {}",
+            display_hours(guild.unverified_kick),
+            display_hours(hours)
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+fn display_template(template: Option<&Template>) -> String {
+    template.map_or_else(|| "Disabled".into(), ToString::to_string)
+}
+
+fn display_hours(hours: Option<i32>) -> String {
+    hours.map_or_else(|| "Disabled".into(), |h| format!("{} hours", h))
+}
@@ -47,3 +47,60 @@ pub async fn update_on_join(ctx: CommandContext, args: UpdateOnJoinArguments) ->
     ctx.log_guild(guild_id, log_embed).await;
     Ok(())
 }
+
+#[derive(FromArgs)]
+pub struct UpdateOnRoleChangeArguments {
+    #[arg(help = "Option to toggle the `Update on Role Change` setting")]
+    pub option: ToggleOption,
+}
+
+pub async fn update_on_role_change(
+    ctx: CommandContext,
+    args: UpdateOnRoleChangeArguments,
+) -> CommandResult {
+    let guild_id = ctx.guild_id.unwrap();
+    let guild = ctx.bot.database.get_guild(guild_id).await?;
+
+    let option = args.option;
+    let (option, desc) = match option {
+        ToggleOption::Enable => (true, "Update on Role Change has succesfully been enabled"),
+        ToggleOption::Disable => (
+            false,
+            "Update on Role Change has successfully been disabled",
+        ),
+    };
+
+    ctx.bot
+        .database
+        .execute(
+            "UPDATE guilds SET update_on_role_change = $1 WHERE guild_id = $2",
+            &[&option, &guild.guild_id],
+        )
+        .await?;
+    if option {
+        ctx.bot.role_update_guilds.insert(guild_id);
+    } else {
+        ctx.bot.role_update_guilds.remove(&guild_id);
+    }
+
+    let embed = EmbedBuilder::new()
+        .default_data()
+        .color(Color::DarkGreen as u32)
+        .title("Settings Modification Successful")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.respond().embeds(&[embed])?.exec().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Action by {}", ctx.author.name))
+        .description(format!(
+            "Settings Modification: Update On Role Change - {} -> {}",
+            guild.update_on_role_change, option
+        ))
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
@@ -3,6 +3,7 @@ use rowifi_framework::prelude::*;
 use rowifi_models::{
     bind::Bind,
     discord::channel::embed::Embed,
+    guild::BlacklistActionType,
     id::{RoleId, UserId},
 };
 use std::error::Error;
@@ -189,12 +190,14 @@ pub async fn update_func(
         .unique()
         .collect::<Vec<_>>();
     let guild_roles = ctx.bot.cache.roles(guild_id);
+    let subscribed_blacklists = ctx.bot.database.get_subscribed_blacklists(guild_id).await?;
     let update_user = UpdateUser {
         ctx: &ctx.bot,
         member: &member,
         user: &user,
         server: &server,
         guild: &guild,
+        subscribed_blacklists: &subscribed_blacklists,
         binds: &binds,
         guild_roles: &guild_roles,
         bypass_roblox_cache,
@@ -235,16 +238,28 @@ pub async fn update_func(
                 }
                 return Err(e);
             }
-            UpdateUserResult::Blacklist(reason) => {
+            UpdateUserResult::Blacklist(blacklist, action) => {
                 let embed = EmbedBuilder::new()
                     .default_data()
                     .title("Update Failed")
                     .description(format!(
                         "User was found on the server blacklist. Reason: {}",
-                        reason
+                        blacklist.reason
                     ))
                     .build()
                     .unwrap();
+                let log_embed = EmbedBuilder::new()
+                    .default_data()
+                    .title("Blacklist Match")
+                    .description(format!("<@{}> was found on the server blacklist", user_id))
+                    .blacklist_log(&blacklist, action)
+                    .build()
+                    .unwrap();
+                ctx.log_guild(guild_id, log_embed).await;
+                // The member has already been sent the blacklist's message
+                if action == BlacklistActionType::DmKick {
+                    return Ok(embed);
+                }
                 if let Ok(channel) = ctx
                     .bot
                     .http
@@ -260,7 +275,7 @@ pub async fn update_func(
                         .create_message(channel.id)
                         .content(&format!(
                             "You were found on the {} blacklist. Reason: {}",
-                            server.name, reason
+                            server.name, blacklist.reason
                         ))
                         .unwrap()
                         .exec()
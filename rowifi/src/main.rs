@@ -23,14 +23,14 @@ use axum::{
 };
 use commands::{
     analytics_config, assetbinds_config, backup_config, blacklists_config, custombinds_config,
-    events_config, group_config, groupbinds_config, premium_config, rankbinds_config,
-    settings_config, user_config,
+    events_config, group_config, groupbinds_config, premium_config, premiumbinds_config,
+    rankbinds_config, settings_config, user_config,
 };
 use deadpool_redis::{Manager as RedisManager, Pool as RedisPool, Runtime};
 use prometheus::{Encoder, TextEncoder};
 use roblox::Client as RobloxClient;
 use rowifi_cache::Cache;
-use rowifi_database::Database;
+use rowifi_database::{Database, Keyring, LEGACY_KEY_ID};
 use rowifi_framework::{context::BotContext, Framework};
 use rowifi_models::{
     discord::{gateway::Intents, guild::Permissions},
@@ -74,6 +74,7 @@ impl Service<(u64, Event)> for RoWifi {
     }
 
     fn call(&mut self, event: (u64, Event)) -> Self::Future {
+        self.event_handler.snapshot(&event.1);
         self.bot
             .cache
             .update(&event.1)
@@ -116,9 +117,35 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         .unwrap();
     let pod_ip = env::var("POD_IP").expect("Expected the pod ip in the environment");
     let primary_key = env::var("PRIMARY_KEY").expect("Expected the cipher key in the environment");
+    let primary_key_id = env::var("PRIMARY_KEY_ID")
+        .map_or(Ok(LEGACY_KEY_ID), |k| k.parse::<u8>())
+        .expect("Expected the cipher key id to be a number");
+    // Unlike the cipher keys the index key is never rotated. Changing it requires clearing the
+    // stored indexes, which searches then rebuild
+    let index_key = env::var("INDEX_KEY").expect("Expected the index key in the environment");
+    let mut keyring = Keyring::new(primary_key_id, &primary_key, &index_key)
+        .expect("Expected valid cipher and index keys in the environment");
+    // Keys that were rotated out are given as comma separated `id:key` pairs
+    if let Ok(retired_keys) = env::var("RETIRED_KEYS") {
+        for retired_key in retired_keys.split(',').filter(|k| !k.is_empty()) {
+            let (key_id, key) = retired_key
+                .split_once(':')
+                .expect("Expected the retired keys to be of the form id:key");
+            let key_id = key_id
+                .parse::<u8>()
+                .expect("Expected the retired key id to be a number");
+            keyring
+                .add_key(key_id, key)
+                .expect("Expected a valid retired cipher key in the environment");
+        }
+    }
     let redis_conn =
         env::var("REDIS_CONN").expect("Expected the redis connection in the environment");
     let proxy = env::var("PROXY").ok();
+    // Only the owners of a group may register it for analytics unless a lower rank is given
+    let analytics_min_rank = env::var("ANALYTICS_MIN_RANK")
+        .map_or(Ok(255), |r| r.parse::<u8>())
+        .expect("Expected the analytics minimum rank to be a number");
     sleep(Duration::from_secs(cluster_id * 60)).await;
 
     let mut webhooks = HashMap::new();
@@ -159,7 +186,8 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         Intents::GUILD_MESSAGES
             | Intents::GUILDS
             | Intents::GUILD_MEMBERS
-            | Intents::GUILD_MESSAGE_REACTIONS,
+            | Intents::GUILD_MESSAGE_REACTIONS
+            | Intents::GUILD_VOICE_STATES,
     )
     .shard_scheme(scheme)
     .http_client(http.clone())
@@ -181,7 +209,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         .unwrap();
     let _res = redis.get().await.expect("Redis Connection failed");
 
-    let database = Database::new(&connection_string, &primary_key).await;
+    let database = Database::new(&connection_string, keyring).await;
     let roblox = RobloxClient::new(redis.clone());
 
     let cluster_spawn = cluster.clone();
@@ -202,6 +230,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         roblox,
         stats,
         webhooks,
+        analytics_min_rank,
         cluster_id,
         total_shards,
         shards_per_cluster,
@@ -225,6 +254,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     .configure(group_config)
     // .configure(api_config)
     .configure(groupbinds_config)
+    .configure(premiumbinds_config)
     .configure(settings_config)
     .configure(premium_config);
 
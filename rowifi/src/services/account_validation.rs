@@ -0,0 +1,359 @@
+use itertools::Itertools;
+use roblox::error::ErrorKind as RobloxErrorKind;
+use rowifi_database::postgres::Row;
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    bind::Bind,
+    guild::{InvalidAccountAction, RoGuild},
+    id::{RoleId, UserId},
+    roblox::id::UserId as RobloxUserId,
+    user::{RoGuildUser, RoUser},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+use tokio::time::{interval, sleep, Duration};
+
+#[derive(Clone, Copy)]
+enum AccountStatus {
+    Banned,
+    Missing,
+}
+
+/// A member whose linked Roblox account failed the validation
+struct InvalidAccount {
+    discord_id: UserId,
+    roblox_id: i64,
+    /// Whether the account is linked through `linked_users` rather than the user's default account
+    guild_link: bool,
+    status: AccountStatus,
+}
+
+pub async fn account_validation(ctx: BotContext) {
+    tracing::info!("Account validation starting");
+    let mut interval = interval(Duration::from_secs(24 * 3600));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in account validation");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let guilds = ctx
+        .database
+        .query::<RoGuild>(
+            "SELECT * FROM guilds WHERE guild_id = ANY($1) AND invalid_account_action != $2",
+            &[&servers, &InvalidAccountAction::None],
+        )
+        .await?;
+    if guilds.is_empty() {
+        return Ok(());
+    }
+
+    let mut guild_accounts = Vec::new();
+    for guild in guilds {
+        let members = ctx
+            .cache
+            .members(guild.guild_id)
+            .into_iter()
+            .map(|m| m.0.get() as i64)
+            .collect::<Vec<_>>();
+        let users = ctx
+            .database
+            .query::<RoUser>(
+                "SELECT * FROM users WHERE discord_id = ANY($1)",
+                &[&members],
+            )
+            .await?;
+        let linked_users = ctx
+            .database
+            .query::<RoGuildUser>(
+                "SELECT * FROM linked_users WHERE guild_id = $1 AND discord_id = ANY($2)",
+                &[&guild.guild_id, &members],
+            )
+            .await?;
+        let mut accounts = users
+            .into_iter()
+            .map(|u| (u.discord_id, (u.default_roblox_id, false)))
+            .collect::<HashMap<_, _>>();
+        accounts.extend(
+            linked_users
+                .into_iter()
+                .map(|u| (u.discord_id, (u.roblox_id, true))),
+        );
+        guild_accounts.push((guild, accounts));
+    }
+
+    // Accounts shared by members of several servers are only checked once
+    let roblox_ids = guild_accounts
+        .iter()
+        .flat_map(|(_, accounts)| accounts.values().map(|(roblox_id, _)| *roblox_id))
+        .unique()
+        .collect::<Vec<_>>();
+    let statuses = check_accounts(ctx, &roblox_ids).await?;
+
+    for (guild, accounts) in guild_accounts {
+        let invalid = accounts
+            .into_iter()
+            .filter_map(|(discord_id, (roblox_id, guild_link))| {
+                statuses.get(&roblox_id).map(|status| InvalidAccount {
+                    discord_id,
+                    roblox_id,
+                    guild_link,
+                    status: *status,
+                })
+            })
+            .collect::<Vec<_>>();
+        let invalid = match unhandled(ctx, &guild, invalid).await {
+            Ok(i) => i,
+            Err(err) => {
+                tracing::error!(err = ?err, guild_id = ?guild.guild_id, "Error in account validation");
+                continue;
+            }
+        };
+        if invalid.is_empty() {
+            continue;
+        }
+        if let Err(err) = apply_action(ctx, &guild, &invalid).await {
+            tracing::error!(err = ?err, guild_id = ?guild.guild_id, "Error in account validation");
+        }
+    }
+    Ok(())
+}
+
+/// The action is only taken once for each invalid account, so the accounts the server already
+/// handled are left out. Accounts that are valid again are forgotten so that they are handled again
+/// if they become invalid again
+async fn unhandled(
+    ctx: &BotContext,
+    guild: &RoGuild,
+    invalid: Vec<InvalidAccount>,
+) -> Result<Vec<InvalidAccount>, Box<dyn Error>> {
+    let roblox_ids = invalid.iter().map(|a| a.roblox_id).collect::<Vec<_>>();
+    ctx.database
+        .execute(
+            "DELETE FROM invalid_account_notices WHERE guild_id = $1 AND NOT (roblox_id = ANY($2))",
+            &[&guild.guild_id, &roblox_ids],
+        )
+        .await?;
+    let notified = ctx
+        .database
+        .query::<Row>(
+            "SELECT roblox_id FROM invalid_account_notices WHERE guild_id = $1",
+            &[&guild.guild_id],
+        )
+        .await?
+        .iter()
+        .map(|r| r.get::<_, i64>("roblox_id"))
+        .collect::<HashSet<_>>();
+    let invalid = invalid
+        .into_iter()
+        .filter(|a| !notified.contains(&a.roblox_id))
+        .collect::<Vec<_>>();
+    let roblox_ids = invalid.iter().map(|a| a.roblox_id).collect::<Vec<_>>();
+    ctx.database
+        .execute(
+            "INSERT INTO invalid_account_notices(guild_id, roblox_id) SELECT $1, unnest($2::BIGINT[]) ON CONFLICT DO NOTHING",
+            &[&guild.guild_id, &roblox_ids],
+        )
+        .await?;
+    Ok(invalid)
+}
+
+/// Find the accounts that are banned or no longer exist. Active accounts are looked up in batches
+/// and only the ones missing from the batch are looked up individually
+async fn check_accounts(
+    ctx: &BotContext,
+    roblox_ids: &[i64],
+) -> Result<HashMap<i64, AccountStatus>, Box<dyn Error>> {
+    let mut statuses = HashMap::new();
+    for chunk in roblox_ids.chunks(100) {
+        let user_ids = chunk
+            .iter()
+            .map(|r| RobloxUserId(*r as u64))
+            .collect::<Vec<_>>();
+        let active = ctx
+            .roblox
+            .get_unbanned_users(&user_ids)
+            .await?
+            .into_iter()
+            .map(|u| u.id.0 as i64)
+            .collect::<HashSet<_>>();
+        for roblox_id in chunk.iter().filter(|r| !active.contains(*r)) {
+            match ctx
+                .roblox
+                .get_user_profile(RobloxUserId(*roblox_id as u64))
+                .await
+            {
+                Ok(user) => {
+                    if user.is_banned {
+                        statuses.insert(*roblox_id, AccountStatus::Banned);
+                    }
+                }
+                Err(err) => match err.kind() {
+                    RobloxErrorKind::Response { status, .. } if status.as_u16() == 404 => {
+                        statuses.insert(*roblox_id, AccountStatus::Missing);
+                    }
+                    // The account is left alone when its status could not be checked
+                    _ => {
+                        tracing::error!(err = ?err, roblox_id = roblox_id, "Error in checking a linked account");
+                    }
+                },
+            }
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+    Ok(statuses)
+}
+
+async fn apply_action(
+    ctx: &BotContext,
+    guild: &RoGuild,
+    invalid: &[InvalidAccount],
+) -> Result<(), Box<dyn Error>> {
+    let guild_id = guild.guild_id;
+    let server_name = ctx
+        .cache
+        .guild(guild_id)
+        .map_or_else(|| "the server".into(), |s| s.name.clone());
+
+    let mut removable_roles = guild.verified_roles.iter().copied().collect::<HashSet<_>>();
+    if matches!(
+        guild.invalid_account_action,
+        InvalidAccountAction::RemoveRoles | InvalidAccountAction::Unlink
+    ) {
+        let binds = ctx
+            .database
+            .query::<Bind>(
+                "SELECT * FROM binds WHERE guild_id = $1",
+                &[&guild.guild_id],
+            )
+            .await?;
+        removable_roles.extend(binds.iter().flat_map(|b| b.discord_roles().iter().copied()));
+    }
+
+    let mut lines = Vec::new();
+    for account in invalid {
+        let reason = match account.status {
+            AccountStatus::Banned => "banned",
+            AccountStatus::Missing => "deleted",
+        };
+        let mut line = format!(
+            "<@{}> - `{}` ({})",
+            account.discord_id, account.roblox_id, reason
+        );
+        match guild.invalid_account_action {
+            InvalidAccountAction::None => {}
+            InvalidAccountAction::Notify => {
+                let content = format!(
+                    "The Roblox account (`{}`) you are verified with in **{}** has been {}. Please use `verify` in the server to link a different account.",
+                    account.roblox_id, server_name, reason
+                );
+                notify(ctx, account.discord_id, &content).await;
+            }
+            InvalidAccountAction::RemoveRoles => {
+                remove_roles(ctx, guild, account.discord_id, &removable_roles).await?;
+            }
+            InvalidAccountAction::Unlink => {
+                if account.guild_link {
+                    ctx.database
+                        .execute(
+                            "DELETE FROM linked_users WHERE guild_id = $1 AND discord_id = $2",
+                            &[&guild_id, &account.discord_id],
+                        )
+                        .await?;
+                } else {
+                    line.push_str(" - default account, roles removed instead");
+                }
+                remove_roles(ctx, guild, account.discord_id, &removable_roles).await?;
+            }
+        }
+        lines.push(line);
+    }
+
+    let mut description = format!(
+        "Found {} members whose linked Roblox account is banned or deleted.\nAction: {}\n\n",
+        invalid.len(),
+        guild.invalid_account_action
+    );
+    for line in lines {
+        if description.len() + line.len() > 4000 {
+            description.push_str("...");
+            break;
+        }
+        description.push_str(&line);
+        description.push('\n');
+    }
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title("Linked Account Validation")
+        .description(description)
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+async fn remove_roles(
+    ctx: &BotContext,
+    guild: &RoGuild,
+    user_id: UserId,
+    removable_roles: &HashSet<RoleId>,
+) -> Result<(), Box<dyn Error>> {
+    let member = match ctx.cache.member(guild.guild_id, user_id) {
+        Some(m) => m,
+        None => return Ok(()),
+    };
+    let guild_roles = ctx.cache.roles(guild.guild_id);
+    let mut roles = member
+        .roles
+        .iter()
+        .filter(|r| !removable_roles.contains(*r))
+        .copied()
+        .collect::<Vec<_>>();
+    roles.extend(
+        guild
+            .verification_roles
+            .iter()
+            .filter(|r| guild_roles.contains(*r)),
+    );
+    let roles = roles.into_iter().unique().collect::<Vec<_>>();
+    if roles.iter().sorted().eq(member.roles.iter().sorted()) {
+        return Ok(());
+    }
+
+    let role_ids = roles.iter().map(|r| r.0).collect::<Vec<_>>();
+    ctx.http
+        .update_guild_member(guild.guild_id.0, user_id.0)
+        .roles(&role_ids)
+        .exec()
+        .await?;
+    if ctx.role_update_guilds.contains(&guild.guild_id) {
+        ctx.bot_role_updates
+            .insert((guild.guild_id, user_id), roles);
+    }
+    Ok(())
+}
+
+async fn notify(ctx: &BotContext, user_id: UserId, content: &str) {
+    if let Ok(response) = ctx.http.create_private_channel(user_id.0).exec().await {
+        if let Ok(channel) = response.model().await {
+            let _ = ctx
+                .http
+                .create_message(channel.id)
+                .content(content)
+                .unwrap()
+                .exec()
+                .await;
+        }
+    }
+}
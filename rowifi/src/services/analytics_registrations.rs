@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use rowifi_database::postgres::Row;
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    analytics::AnalyticsRegistration,
+    guild::{GuildType, RoGuild},
+    id::GuildId,
+};
+use std::error::Error;
+use tokio::time::{interval, Duration};
+
+use crate::utils::{group_link, GroupLink};
+
+/// How many days a group registered before registrations required a link is kept while the server
+/// is asked to register it again
+const LEGACY_GRACE_DAYS: i64 = 14;
+
+pub async fn analytics_registrations(ctx: BotContext) {
+    tracing::info!("Analytics registration verification starting");
+    let mut interval = interval(Duration::from_secs(24 * 3600));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in analytics registration verification");
+        }
+    }
+}
+
+/// Re-verify the link of every registered group and remove the registrations that have lost it
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let guilds = ctx
+        .database
+        .query::<RoGuild>(
+            "SELECT * FROM guilds WHERE guild_id = ANY($1) AND kind = $2 AND cardinality(registered_groups) > 0",
+            &[&servers, &GuildType::Beta],
+        )
+        .await?;
+    let registrations = ctx
+        .database
+        .query::<AnalyticsRegistration>(
+            "SELECT * FROM analytics_registrations WHERE guild_id = ANY($1)",
+            &[&servers],
+        )
+        .await?;
+
+    for guild in guilds {
+        for group_id in &guild.registered_groups {
+            let registration = registrations
+                .iter()
+                .find(|r| r.guild_id == guild.guild_id && r.group_id == *group_id);
+            if let Err(err) = verify(ctx, guild.guild_id, *group_id, registration).await {
+                tracing::error!(err = ?err, guild_id = ?guild.guild_id, group_id = group_id, "Error in analytics registration verification");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn verify(
+    ctx: &BotContext,
+    guild_id: GuildId,
+    group_id: i64,
+    registration: Option<&AnalyticsRegistration>,
+) -> Result<(), Box<dyn Error>> {
+    let registration = match registration {
+        Some(r) => r,
+        None => return legacy(ctx, guild_id, group_id).await,
+    };
+
+    // Lookup errors are returned rather than treated as a lost link so that an outage of the
+    // Roblox API does not remove every registration
+    match group_link(ctx, registration.discord_id, guild_id, group_id).await? {
+        GroupLink::Linked { roblox_id } => {
+            if roblox_id != registration.roblox_id {
+                ctx.database
+                    .execute(
+                        "UPDATE analytics_registrations SET roblox_id = $1 WHERE guild_id = $2 AND group_id = $3",
+                        &[&roblox_id, &guild_id, &group_id],
+                    )
+                    .await?;
+            }
+            Ok(())
+        }
+        GroupLink::Unverified => {
+            let reason = format!(
+                "<@{}> who registered the group is no longer verified",
+                registration.discord_id
+            );
+            remove(ctx, guild_id, group_id, &reason).await
+        }
+        GroupLink::Missing => {
+            let reason = format!(
+                "<@{}> who registered the group no longer owns it or holds a rank of at least {} in it",
+                registration.discord_id, ctx.analytics_min_rank
+            );
+            remove(ctx, guild_id, group_id, &reason).await
+        }
+    }
+}
+
+/// Remind the server to register a group from before registrations required a link, and remove
+/// it once the grace period has passed
+async fn legacy(ctx: &BotContext, guild_id: GuildId, group_id: i64) -> Result<(), Box<dyn Error>> {
+    // The existing row is kept on conflict so that the grace period starts at the first notice
+    let row = ctx
+        .database
+        .query_one::<Row>(
+            "INSERT INTO analytics_pending_registrations(guild_id, group_id, noticed_at) VALUES($1, $2, $3) ON CONFLICT (guild_id, group_id) DO UPDATE SET noticed_at = analytics_pending_registrations.noticed_at RETURNING noticed_at",
+            &[&guild_id, &group_id, &Utc::now()],
+        )
+        .await?;
+    let noticed_at: DateTime<Utc> = row.get("noticed_at");
+    let days_left = LEGACY_GRACE_DAYS - (Utc::now() - noticed_at).num_days();
+    if days_left <= 0 {
+        return remove(
+            ctx,
+            guild_id,
+            group_id,
+            "The group was registered before registrations required a link to the group and was not registered again",
+        )
+        .await;
+    }
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title("Analytics Registration Pending")
+        .field(
+            EmbedFieldBuilder::new(
+                format!("Group Id: {}", group_id),
+                format!(
+                    "The group was registered before registrations required a link to the group. Please register it again within {} days or it will be removed",
+                    days_left
+                ),
+            )
+            .build(),
+        )
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
+
+async fn remove(
+    ctx: &BotContext,
+    guild_id: GuildId,
+    group_id: i64,
+    reason: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut db = ctx.database.get().await?;
+    let transaction = db.transaction().await?;
+    let update_guild = transaction
+        .prepare_cached(
+            "UPDATE guilds SET registered_groups = array_remove(registered_groups, $1) WHERE guild_id = $2",
+        )
+        .await?;
+    transaction
+        .execute(&update_guild, &[&group_id, &guild_id])
+        .await?;
+    let delete_registration = transaction
+        .prepare_cached("DELETE FROM analytics_registrations WHERE guild_id = $1 AND group_id = $2")
+        .await?;
+    transaction
+        .execute(&delete_registration, &[&guild_id, &group_id])
+        .await?;
+    let delete_alerts = transaction
+        .prepare_cached("DELETE FROM analytics_alerts WHERE guild_id = $1 AND group_id = $2")
+        .await?;
+    transaction
+        .execute(&delete_alerts, &[&guild_id, &group_id])
+        .await?;
+    let delete_pending = transaction
+        .prepare_cached(
+            "DELETE FROM analytics_pending_registrations WHERE guild_id = $1 AND group_id = $2",
+        )
+        .await?;
+    transaction
+        .execute(&delete_pending, &[&guild_id, &group_id])
+        .await?;
+    transaction.commit().await?;
+
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title("Analytics Registration Removed")
+        .field(EmbedFieldBuilder::new(format!("Group Id: {}", group_id), reason).build())
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
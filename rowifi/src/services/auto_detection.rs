@@ -4,16 +4,17 @@ use rowifi_database::postgres::Row;
 use rowifi_framework::{context::BotContext, prelude::*};
 use rowifi_models::{
     bind::Bind,
+    blacklist::SubscribedBlacklist,
     discord::gateway::{event::Event, payload::outgoing::RequestGuildMembers},
-    guild::{GuildType, RoGuild},
-    id::{GuildId, RoleId},
+    guild::{BlacklistActionType, GuildType, RoGuild},
+    id::RoleId,
     roblox::id::UserId as RobloxUserId,
     user::RoGuildUser,
 };
 use std::{collections::HashSet, env, error::Error, sync::atomic::Ordering};
 use tokio::time::{interval, sleep, timeout, Duration};
 
-use crate::utils::{UpdateUser, UpdateUserResult};
+use crate::utils::{mass_update_user, UpdateUser, UpdateUserResult};
 
 pub async fn auto_detection(ctx: BotContext) {
     tracing::info!("Auto Detection starting");
@@ -106,6 +107,7 @@ async fn execute(ctx: &BotContext, chunk_size: usize) -> Result<(), Box<dyn Erro
         }
         tracing::trace!("got users: {:?}", users);
         let guild_roles = ctx.cache.roles(guild_id);
+        let subscribed_blacklists = ctx.database.get_subscribed_blacklists(guild_id).await?;
         let all_roles = binds
             .iter()
             .flat_map(|b| b.discord_roles())
@@ -126,6 +128,7 @@ async fn execute(ctx: &BotContext, chunk_size: usize) -> Result<(), Box<dyn Erro
                         ctx,
                         &server,
                         &guild,
+                        &subscribed_blacklists,
                         &guild_roles,
                         true,
                         None,
@@ -151,6 +154,7 @@ pub async fn execute_chunk(
     ctx: &BotContext,
     server: &CachedGuild,
     guild: &RoGuild,
+    subscribed_blacklists: &[SubscribedBlacklist],
     guild_roles: &HashSet<RoleId>,
     auto_detection: bool,
     role_filter: Option<RoleId>,
@@ -181,6 +185,7 @@ pub async fn execute_chunk(
                 user,
                 server,
                 guild,
+                subscribed_blacklists,
                 binds,
                 guild_roles,
                 bypass_roblox_cache: false,
@@ -198,6 +203,26 @@ pub async fn execute_chunk(
                         .unwrap();
                     ctx.log_guild(server.id, log_embed).await;
                 }
+            } else if let UpdateUserResult::Blacklist(blacklist, action) = res {
+                // Members who stay on the server would otherwise be logged on every run
+                if matches!(
+                    action,
+                    BlacklistActionType::Kick
+                        | BlacklistActionType::Ban
+                        | BlacklistActionType::DmKick
+                ) {
+                    let log_embed = EmbedBuilder::new()
+                        .default_data()
+                        .title(format!("{}: Blacklist Match", log))
+                        .description(format!(
+                            "<@{}> was found on the server blacklist",
+                            member.user.id
+                        ))
+                        .blacklist_log(&blacklist, action)
+                        .build()
+                        .unwrap();
+                    ctx.log_guild(server.id, log_embed).await;
+                }
             } else if let UpdateUserResult::Error(err) = res {
                 tracing::error!(err = ?err);
             }
@@ -205,16 +230,3 @@ pub async fn execute_chunk(
     }
     Ok(())
 }
-
-pub fn mass_update_user(row: &Row, guild_id: GuildId) -> Result<RoGuildUser, Box<dyn Error>> {
-    let guild_id = row.try_get("guild_id").unwrap_or(guild_id);
-    let discord_id = row.try_get("discord_id")?;
-    let roblox_id = row.try_get("roblox_id").ok();
-    let default_roblox_id = row.try_get("default_roblox_id")?;
-
-    Ok(RoGuildUser {
-        guild_id,
-        discord_id,
-        roblox_id: roblox_id.unwrap_or(default_roblox_id),
-    })
-}
@@ -0,0 +1,110 @@
+use chrono::Utc;
+use rowifi_database::postgres::Row;
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    blacklist::{Blacklist, BlacklistData},
+    guild::RoGuild,
+    id::{GuildId, UserId},
+};
+use std::error::Error;
+use tokio::time::{interval, Duration};
+
+pub async fn blacklist_expiry(ctx: BotContext) {
+    tracing::info!("Blacklist expiry starting");
+    let mut interval = interval(Duration::from_secs(5 * 60));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in blacklist expiry");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let now = Utc::now();
+    let guilds = ctx
+        .database
+        .query::<RoGuild>(
+            "SELECT * FROM guilds WHERE guild_id = ANY($1) AND EXISTS (SELECT 1 FROM unnest(blacklists) b WHERE b.expires_at <= $2)",
+            &[&servers, &now],
+        )
+        .await?;
+
+    for guild in guilds {
+        for blacklist in guild.blacklists.iter().filter(|b| b.is_expired()) {
+            if let Err(err) = lift_blacklist(ctx, guild.guild_id, blacklist).await {
+                tracing::error!(err = ?err, guild_id = ?guild.guild_id, "Error in blacklist expiry");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn lift_blacklist(
+    ctx: &BotContext,
+    guild_id: GuildId,
+    blacklist: &Blacklist,
+) -> Result<(), Box<dyn Error>> {
+    ctx.database
+        .execute(
+            "UPDATE guilds SET blacklists = array_remove(blacklists, $1) WHERE guild_id = $2",
+            &[blacklist, &guild_id],
+        )
+        .await?;
+
+    let bans = ctx
+        .database
+        .query::<Row>(
+            "DELETE FROM blacklist_bans WHERE list_guild_id = $1 AND blacklist_id = $2 RETURNING guild_id, user_id",
+            &[&guild_id, &blacklist.blacklist_id],
+        )
+        .await?;
+    // Bans of shared blacklists are lifted in the subscribing guilds that issued them as well
+    let mut lifted = 0;
+    for ban in bans {
+        let ban_guild_id: GuildId = ban.get("guild_id");
+        let user_id: UserId = ban.get("user_id");
+        if ctx
+            .http
+            .delete_ban(ban_guild_id.0, user_id.0)
+            .exec()
+            .await
+            .is_ok()
+        {
+            lifted += 1;
+        }
+    }
+
+    let target = match &blacklist.data {
+        BlacklistData::User(user) => format!("User Id: {}", user),
+        BlacklistData::Group(group) => format!("Group Id: {}", group),
+        BlacklistData::Custom(code) => format!("Code: {}", code),
+        BlacklistData::Discord(user) => format!("Discord User: <@{}>", user),
+    };
+    let mut desc = format!(
+        "Type: {}\n{}\nReason: {}",
+        blacklist.kind(),
+        target,
+        blacklist.reason
+    );
+    if let Some(created_by) = blacklist.created_by {
+        desc.push_str(&format!("\nCreated By: <@{}>", created_by));
+    }
+    if lifted > 0 {
+        desc.push_str(&format!("\nBans Lifted: {}", lifted));
+    }
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title("Blacklist Expired")
+        .field(EmbedFieldBuilder::new(format!("Id: {}", blacklist.blacklist_id), desc).build())
+        .build()
+        .unwrap();
+    ctx.log_guild(guild_id, log_embed).await;
+    Ok(())
+}
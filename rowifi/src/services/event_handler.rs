@@ -1,14 +1,18 @@
-use dashmap::DashSet;
+use chrono::{Duration as ChronoDuration, Utc};
+use dashmap::{DashMap, DashSet};
 use futures_util::future::{Future, FutureExt};
 use itertools::Itertools;
 use rowifi_framework::{context::BotContext, prelude::*};
 use rowifi_models::{
     bind::Bind,
-    discord::{channel::GuildChannel, guild::Permissions},
-    guild::{GuildType, RoGuild},
-    id::{ChannelId, GuildId, UserId},
+    blacklist::{Blacklist, BlacklistData},
+    discord::{channel::GuildChannel, guild::Permissions, id::Id},
+    events::{EventSession, SessionStatus},
+    guild::{BlacklistActionType, GuildType, RoGuild},
+    id::{ChannelId, GuildId, RoleId, UserId},
 };
 use std::{
+    collections::HashSet,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -21,14 +25,22 @@ use twilight_gateway::Event;
 
 use crate::{
     services::auto_detection,
-    utils::{UpdateUser, UpdateUserResult},
+    utils::{
+        handle_session_component, BlacklistEnforcer, UpdateUser, UpdateUserResult, SESSION_CHECKIN,
+        SESSION_RSVP,
+    },
 };
 
-use super::activity;
+use super::{
+    account_validation, activity, analytics_registrations, blacklist_expiry, event_quotas,
+    event_sessions, group_analytics, notes_reencryption, unverified,
+};
 
 pub struct EventHandlerRef {
     unavailable: DashSet<GuildId>,
     auto_detection_started: AtomicBool,
+    /// The roles of members before their first `MemberUpdate` in the debounce window
+    role_snapshots: DashMap<(GuildId, UserId), Vec<RoleId>>,
     bot: BotContext,
 }
 
@@ -41,8 +53,27 @@ impl EventHandler {
             bot: bot.clone(),
             unavailable: DashSet::new(),
             auto_detection_started: AtomicBool::new(false),
+            role_snapshots: DashMap::new(),
         }))
     }
+
+    /// Store the cached roles of a member before a `MemberUpdate` is applied to the cache so
+    /// that the handler can diff them once the debounce window is over
+    pub fn snapshot(&self, event: &Event) {
+        if let Event::MemberUpdate(m) = event {
+            let guild_id = GuildId(m.guild_id);
+            if !self.0.bot.role_update_guilds.contains(&guild_id) {
+                return;
+            }
+            let user_id = UserId(m.user.id);
+            if let Some(member) = self.0.bot.cache.member(guild_id, user_id) {
+                self.0
+                    .role_snapshots
+                    .entry((guild_id, user_id))
+                    .or_insert_with(|| member.roles.clone());
+            }
+        }
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -130,11 +161,51 @@ impl Service<(u64, Event)> for EventHandler {
                             tokio::time::sleep(Duration::from_secs(30)).await;
                             auto_detection::auto_detection(context_ad).await;
                         });
+                        let context_ga = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(4 * 60)).await;
+                            group_analytics(context_ga).await;
+                        });
                         let context_ac = eh.bot.clone();
                         tokio::spawn(async move {
                             tokio::time::sleep(Duration::from_secs(3 * 60)).await;
                             activity(context_ac).await;
                         });
+                        let context_uv = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(60)).await;
+                            unverified(context_uv).await;
+                        });
+                        let context_av = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(10 * 60)).await;
+                            account_validation(context_av).await;
+                        });
+                        let context_be = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(2 * 60)).await;
+                            blacklist_expiry(context_be).await;
+                        });
+                        let context_eq = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(15 * 60)).await;
+                            event_quotas(context_eq).await;
+                        });
+                        let context_es = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(20)).await;
+                            event_sessions(context_es).await;
+                        });
+                        let context_ar = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(6 * 60)).await;
+                            analytics_registrations(context_ar).await;
+                        });
+                        let context_nr = eh.bot.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(5 * 60)).await;
+                            notes_reencryption(context_nr).await;
+                        });
                     }
                     let guild_ids = ready
                         .guilds
@@ -160,6 +231,16 @@ impl Service<(u64, Event)> for EventHandler {
                         if let Some(log_channel) = guild.log_channel {
                             eh.bot.log_channels.insert(guild_id, log_channel);
                         }
+
+                        if guild.update_on_role_change {
+                            eh.bot.role_update_guilds.insert(guild_id);
+                        }
+                    }
+
+                    // The buttons of scheduled events outlive the command that posted them
+                    let sessions = eh.bot.database.query::<EventSession>("SELECT * FROM event_sessions WHERE guild_id = ANY($1) AND (status = $2 OR status = $3)", &[&guild_ids, &SessionStatus::Scheduled, &SessionStatus::CheckIn]).await?;
+                    for session in sessions.iter().filter(|s| s.message_id != 0) {
+                        eh.bot.ignore_message_components.insert(Id::new(session.message_id as u64));
                     }
                 }
                 Event::UnavailableGuild(g) => {
@@ -177,20 +258,86 @@ impl Service<(u64, Event)> for EventHandler {
                         None => return Ok(()),
                     };
                     let guild = eh.bot.database.get_guild(guild_id).await?;
-                    if !guild.update_on_join {
-                        return Ok(());
+
+                    let subscribed_blacklists = eh.bot.database.get_subscribed_blacklists(guild_id).await?;
+
+                    // Discord blacklists don't need the Roblox account, so they also catch unverified members
+                    let discord_blacklist = guild
+                        .blacklists
+                        .iter()
+                        .filter(|b| !b.is_expired())
+                        .find(|b| b.matches_discord(user_id))
+                        .map(|b| (b.clone(), b.effective_action(guild.blacklist_action), guild.guild_id))
+                        .or_else(|| {
+                            subscribed_blacklists
+                                .iter()
+                                .filter(|s| !s.blacklist.is_expired())
+                                .find(|s| s.blacklist.matches_discord(user_id))
+                                .map(|s| (s.to_match(), s.blacklist.effective_action(guild.blacklist_action), s.guild_id))
+                        });
+                    if let Some((blacklist, action, list_guild_id)) = discord_blacklist {
+                        let guild_roles = eh.bot.cache.roles(guild_id);
+                        let binds = eh.bot
+                            .database
+                            .query::<Bind>(
+                                "SELECT * FROM binds WHERE guild_id = $1",
+                                &[&guild.guild_id],
+                            )
+                            .await?;
+                        let all_roles = binds
+                            .iter()
+                            .flat_map(|b| b.discord_roles())
+                            .unique()
+                            .collect::<Vec<_>>();
+                        let enforcer = BlacklistEnforcer {
+                            ctx: &eh.bot,
+                            member: &member,
+                            server: &server,
+                            guild: &guild,
+                            guild_roles: &guild_roles,
+                            all_roles: &all_roles,
+                        };
+                        enforcer.apply(&blacklist, action, list_guild_id).await;
+                        return blacklist_matched(&eh.bot, guild_id, user_id, &blacklist, action, "Member Join").await;
                     }
+
                     let user = match eh.bot.database.get_linked_user(user_id, guild_id).await? {
                         Some(u) => u,
                         None => {
-                            for verification_role in guild.verification_roles {
-                                if let Some(role) = eh.bot.cache.role(verification_role) {
-                                    eh.bot.http.add_guild_member_role(m.guild_id, user_id.0, role.id.0).exec().await?;
+                            if guild.update_on_join {
+                                for verification_role in &guild.verification_roles {
+                                    if let Some(role) = eh.bot.cache.role(*verification_role) {
+                                        eh.bot.http.add_guild_member_role(m.guild_id, user_id.0, role.id.0).exec().await?;
+                                    }
+                                }
+                            }
+                            if let Some(template) = &guild.unverified_template {
+                                let nickname = template.unverified_nickname(&user_id.to_string(), &m.user.name);
+                                if nickname.chars().count() <= 32 {
+                                    let _ = eh.bot
+                                        .http
+                                        .update_guild_member(m.guild_id, user_id.0)
+                                        .nick(Some(&nickname))
+                                        .unwrap()
+                                        .exec()
+                                        .await;
                                 }
                             }
+                            let now = Utc::now();
+                            let next_reminder = guild.verification_reminder.map(|h| now + ChronoDuration::hours(i64::from(h)));
+                            let kick_at = guild.unverified_kick.map(|h| now + ChronoDuration::hours(i64::from(h)));
+                            if next_reminder.is_some() || kick_at.is_some() {
+                                eh.bot.database.execute(
+                                    "INSERT INTO unverified_members(guild_id, user_id, joined_at, next_reminder, kick_at) VALUES($1, $2, $3, $4, $5) ON CONFLICT (guild_id, user_id) DO UPDATE SET joined_at = $3, next_reminder = $4, kick_at = $5",
+                                    &[&guild_id, &user_id, &now, &next_reminder, &kick_at],
+                                ).await?;
+                            }
                             return Ok(());
                         },
                     };
+                    if !guild.update_on_join {
+                        return Ok(());
+                    }
 
                     let guild_roles = eh.bot.cache.roles(guild_id);
 
@@ -213,6 +360,7 @@ impl Service<(u64, Event)> for EventHandler {
                         user: &user,
                         server: &server,
                         guild: &guild,
+                        subscribed_blacklists: &subscribed_blacklists,
                         binds: &binds,
                         guild_roles: &guild_roles,
                         bypass_roblox_cache: false,
@@ -221,20 +369,8 @@ impl Service<(u64, Event)> for EventHandler {
                     let (added_roles, removed_roles, disc_nick) = match update_user.execute().await
                     {
                         UpdateUserResult::Success(a, r, n) => (a, r, n),
-                        UpdateUserResult::Blacklist(reason) => {
-                            if let Ok(channel) = eh.bot.http.create_private_channel(m.user.id).exec().await?.model().await {
-                                let _ = eh.bot
-                                    .http
-                                    .create_message(channel.id)
-                                    .content(&format!(
-                                        "You were found on the server blacklist. Reason: {}",
-                                        reason
-                                    ))
-                                    .unwrap()
-                                    .exec()
-                                    .await;
-                            }
-                            return Ok(());
+                        UpdateUserResult::Blacklist(blacklist, action) => {
+                            return blacklist_matched(&eh.bot, guild_id, user_id, &blacklist, action, "Update On Join").await;
                         },
                         UpdateUserResult::InvalidNickname(_) => return Ok(()),
                         UpdateUserResult::Error(err) => return Err(err)
@@ -247,9 +383,176 @@ impl Service<(u64, Event)> for EventHandler {
                         .unwrap();
                     eh.bot.log_guild(guild_id, log_embed).await;
                 }
+                Event::InteractionCreate(interaction) => {
+                    if let Interaction::MessageComponent(component) = &interaction.0 {
+                        let custom_id = component.data.custom_id.as_str();
+                        if custom_id == SESSION_RSVP || custom_id == SESSION_CHECKIN {
+                            handle_session_component(&eh.bot, component).await?;
+                        }
+                    }
+                }
+                Event::MemberRemove(m) => {
+                    let guild_id = GuildId(m.guild_id);
+                    let user_id = UserId(m.user.id);
+                    eh.bot.database.execute(
+                        "DELETE FROM unverified_members WHERE guild_id = $1 AND user_id = $2",
+                        &[&guild_id, &user_id],
+                    ).await?;
+                }
+                Event::MemberUpdate(m) => {
+                    let guild_id = GuildId(m.guild_id);
+                    let user_id = UserId(m.user.id);
+                    if m.user.bot || !eh.role_snapshots.contains_key(&(guild_id, user_id)) {
+                        return Ok(());
+                    }
+                    // Let any other role changes made in quick succession settle before diffing
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    let old_roles = match eh.role_snapshots.remove(&(guild_id, user_id)) {
+                        Some((_, r)) => r,
+                        None => return Ok(()),
+                    };
+                    let server = match eh.bot.cache.guild(guild_id) {
+                        Some(s) => s,
+                        None => return Ok(()),
+                    };
+                    let member = match eh.bot.cache.member(guild_id, user_id) {
+                        Some(m) => m,
+                        None => return Ok(()),
+                    };
+                    let changed_roles = member
+                        .roles
+                        .iter()
+                        .filter(|r| !old_roles.contains(*r))
+                        .chain(old_roles.iter().filter(|r| !member.roles.contains(*r)))
+                        .copied()
+                        .collect::<Vec<_>>();
+                    if changed_roles.is_empty() {
+                        return Ok(());
+                    }
+                    if let Some((_, bot_roles)) = eh.bot.bot_role_updates.remove(&(guild_id, user_id)) {
+                        if bot_roles.iter().sorted().eq(member.roles.iter().sorted()) {
+                            return Ok(());
+                        }
+                    }
+
+                    let guild = eh.bot.database.get_guild(guild_id).await?;
+                    if !guild.update_on_role_change {
+                        return Ok(());
+                    }
+                    let binds = eh.bot
+                        .database
+                        .query::<Bind>(
+                            "SELECT * FROM binds WHERE guild_id = $1",
+                            &[&guild.guild_id],
+                        )
+                        .await?;
+                    let bind_roles = binds.iter().filter_map(|b| match b {
+                        Bind::Custom(c) => Some(c.command.roles()),
+                        _ => None,
+                    });
+                    let subscribed_blacklists = eh.bot.database.get_subscribed_blacklists(guild_id).await?;
+                    let blacklist_roles = guild
+                        .blacklists
+                        .iter()
+                        .chain(subscribed_blacklists.iter().map(|s| &s.blacklist))
+                        .filter_map(|b| match &b.data {
+                            BlacklistData::Custom(c) => Some(c.roles()),
+                            _ => None,
+                        });
+                    let referenced_roles = bind_roles.chain(blacklist_roles).flatten().collect::<HashSet<_>>();
+                    if !changed_roles.iter().any(|r| referenced_roles.contains(r)) {
+                        return Ok(());
+                    }
+
+                    let user = match eh.bot.database.get_linked_user(user_id, guild_id).await? {
+                        Some(u) => u,
+                        None => return Ok(()),
+                    };
+                    let guild_roles = eh.bot.cache.roles(guild_id);
+                    let all_roles = binds
+                        .iter()
+                        .flat_map(|b| b.discord_roles())
+                        .unique()
+                        .collect::<Vec<_>>();
+
+                    let update_user = UpdateUser {
+                        ctx: &eh.bot,
+                        member: &member,
+                        user: &user,
+                        server: &server,
+                        guild: &guild,
+                        subscribed_blacklists: &subscribed_blacklists,
+                        binds: &binds,
+                        guild_roles: &guild_roles,
+                        bypass_roblox_cache: false,
+                        all_roles: &all_roles,
+                    };
+                    let (added_roles, removed_roles, disc_nick) = match update_user.execute().await
+                    {
+                        UpdateUserResult::Success(a, r, n) => (a, r, n),
+                        UpdateUserResult::Blacklist(blacklist, action) => {
+                            return blacklist_matched(&eh.bot, guild_id, user_id, &blacklist, action, "Update On Role Change").await;
+                        },
+                        UpdateUserResult::InvalidNickname(_) => return Ok(()),
+                        UpdateUserResult::Error(err) => return Err(err)
+                    };
+                    let original_nick = member.nick.as_ref().map_or_else(|| member.user.name.as_str(), String::as_str);
+                    if added_roles.is_empty() && removed_roles.is_empty() && disc_nick == original_nick {
+                        return Ok(());
+                    }
+                    let log_embed = EmbedBuilder::new()
+                        .default_data()
+                        .title("Update On Role Change")
+                        .update_log(&added_roles, &removed_roles, &disc_nick)
+                        .build()
+                        .unwrap();
+                    eh.bot.log_guild(guild_id, log_embed).await;
+                }
                 _ => {}
             }
             Ok(())
         }.boxed()
     }
 }
+
+/// Log a blacklist match and tell the member why, unless the blacklist's action already messaged them
+async fn blacklist_matched(
+    bot: &BotContext,
+    guild_id: GuildId,
+    user_id: UserId,
+    blacklist: &Blacklist,
+    action: BlacklistActionType,
+    title: &str,
+) -> Result<(), RoError> {
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("{}: Blacklist Match", title))
+        .description(format!("<@{}> was found on the server blacklist", user_id))
+        .blacklist_log(blacklist, action)
+        .build()
+        .unwrap();
+    bot.log_guild(guild_id, log_embed).await;
+    if action == BlacklistActionType::DmKick {
+        return Ok(());
+    }
+    if let Ok(channel) = bot
+        .http
+        .create_private_channel(user_id.0)
+        .exec()
+        .await?
+        .model()
+        .await
+    {
+        let _ = bot
+            .http
+            .create_message(channel.id)
+            .content(&format!(
+                "You were found on the server blacklist. Reason: {}",
+                blacklist.reason
+            ))
+            .unwrap()
+            .exec()
+            .await;
+    }
+    Ok(())
+}
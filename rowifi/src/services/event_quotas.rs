@@ -0,0 +1,90 @@
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    events::{EventQuota, EventType},
+    guild::{GuildType, RoGuild},
+};
+use std::error::Error;
+use tokio::time::{interval, Duration};
+use twilight_http::request::AttachmentFile;
+
+use crate::utils::{build_quota_report, quota_report_csv, quota_report_embed};
+
+pub async fn event_quotas(ctx: BotContext) {
+    tracing::info!("Event quota reports starting");
+    let mut interval = interval(Duration::from_secs(3600));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in event quota reports");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let guilds = ctx
+        .database
+        .query::<RoGuild>(
+            "SELECT * FROM guilds WHERE guild_id = ANY($1) AND kind = $2 AND quota_channel IS NOT NULL AND (quota_reported_at IS NULL OR quota_reported_at <= NOW() - INTERVAL '7 days')",
+            &[&servers, &GuildType::Beta],
+        )
+        .await?;
+
+    for guild in guilds {
+        // A report that failed to post is retried on the next run rather than skipped for a week
+        if let Err(err) = post_report(ctx, &guild).await {
+            tracing::error!(err = ?err, guild_id = ?guild.guild_id, "Error in event quota reports");
+            continue;
+        }
+        ctx.database
+            .execute(
+                "UPDATE guilds SET quota_reported_at = NOW() WHERE guild_id = $1",
+                &[&guild.guild_id],
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+async fn post_report(ctx: &BotContext, guild: &RoGuild) -> Result<(), Box<dyn Error>> {
+    let quota_channel = match guild.quota_channel {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let quotas = ctx
+        .database
+        .query::<EventQuota>(
+            "SELECT * FROM event_quotas WHERE guild_id = $1 ORDER BY quota_id",
+            &[&guild.guild_id],
+        )
+        .await?;
+    if quotas.is_empty() {
+        return Ok(());
+    }
+    let event_types = ctx
+        .database
+        .query::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1",
+            &[&guild.guild_id],
+        )
+        .await?;
+
+    let entries = build_quota_report(ctx, guild, &quotas).await?;
+    let embed = quota_report_embed(&quotas, &event_types, &entries).build()?;
+    let csv = quota_report_csv(&quotas, &entries);
+    ctx.http
+        .create_message(quota_channel.0)
+        .embeds(&[embed])?
+        .attach(&[AttachmentFile::from_bytes(
+            "quota_report.csv",
+            csv.as_bytes(),
+        )])
+        .exec()
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use chrono::Utc;
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    discord::id::Id,
+    events::{EventSession, EventType, SessionStatus},
+};
+use std::error::Error;
+use tokio::time::{interval, Duration};
+
+use crate::utils::{linked_users, session_components, session_embed};
+
+pub async fn event_sessions(ctx: BotContext) {
+    tracing::info!("Event sessions starting");
+    let mut interval = interval(Duration::from_secs(30));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in event sessions");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let now = Utc::now();
+
+    let starting = ctx
+        .database
+        .query::<EventSession>(
+            "UPDATE event_sessions SET status = $1 WHERE guild_id = ANY($2) AND status = $3 AND start_time <= $4 RETURNING *",
+            &[&SessionStatus::CheckIn, &servers, &SessionStatus::Scheduled, &now],
+        )
+        .await?;
+    for session in starting {
+        if let Err(err) = refresh_message(ctx, &session).await {
+            tracing::error!(err = ?err, session_id = session.session_id, "Error in opening event check-in");
+        }
+    }
+
+    let closing = ctx
+        .database
+        .query::<EventSession>(
+            "SELECT * FROM event_sessions WHERE guild_id = ANY($1) AND status = $2 AND start_time + checkin_minutes * INTERVAL '1 minute' <= $3",
+            &[&servers, &SessionStatus::CheckIn, &now],
+        )
+        .await?;
+    for session in closing {
+        if let Err(err) = close_session(ctx, session).await {
+            tracing::error!(err = ?err, "Error in closing event session");
+        }
+    }
+    Ok(())
+}
+
+/// Create the event log from the checked in members and mark the session as closed. Both happen
+/// in one transaction so that a restart mid-close cannot log the event twice
+async fn close_session(ctx: &BotContext, mut session: EventSession) -> Result<(), Box<dyn Error>> {
+    let members = session
+        .checked_in
+        .iter()
+        .map(|m| m.get() as i64)
+        .collect::<Vec<_>>();
+    let mut attendees = linked_users(ctx, session.guild_id, &members)
+        .await?
+        .into_iter()
+        .map(|u| u.roblox_id)
+        .collect::<Vec<_>>();
+    attendees.sort_unstable();
+    attendees.dedup();
+
+    let mut db = ctx.database.get().await?;
+    let transaction = db.transaction().await?;
+    let close = transaction
+        .prepare_cached(
+            "UPDATE event_sessions SET status = $1 WHERE session_id = $2 AND status = $3",
+        )
+        .await?;
+    let closed = transaction
+        .execute(
+            &close,
+            &[
+                &SessionStatus::Closed,
+                &session.session_id,
+                &SessionStatus::CheckIn,
+            ],
+        )
+        .await?;
+    if closed == 0 {
+        return Ok(());
+    }
+    let mut guild_event_id = None;
+    if !attendees.is_empty() {
+        let insert = transaction
+            .prepare_cached(
+                r#"INSERT INTO events(guild_id, event_type, guild_event_id, host_id, timestamp, attendees)
+                VALUES($1, $2, (SELECT COALESCE(max(guild_event_id) + 1, 1) FROM events WHERE guild_id = $1), $3, $4, $5)
+                RETURNING guild_event_id"#,
+            )
+            .await?;
+        let row = transaction
+            .query_one(
+                &insert,
+                &[
+                    &session.guild_id,
+                    &session.event_type,
+                    &session.host_roblox_id,
+                    &session.start_time,
+                    &attendees,
+                ],
+            )
+            .await?;
+        guild_event_id = Some(row.get::<'_, _, i64>("guild_event_id"));
+    }
+    transaction.commit().await?;
+
+    session.status = SessionStatus::Closed;
+    if session.message_id != 0 {
+        ctx.ignore_message_components
+            .remove(&Id::new(session.message_id as u64));
+    }
+    refresh_message(ctx, &session).await?;
+
+    let desc = match guild_event_id {
+        Some(id) => format!(
+            "Scheduled event `{}` was logged as event `{}` with {} attendees",
+            session.session_id,
+            id,
+            attendees.len()
+        ),
+        None => format!(
+            "Scheduled event `{}` closed without any verified members checking in",
+            session.session_id
+        ),
+    };
+    let log_embed = EmbedBuilder::new()
+        .default_data()
+        .title("Event Check-In Closed")
+        .description(desc)
+        .build()
+        .unwrap();
+    ctx.log_guild(session.guild_id, log_embed).await;
+    Ok(())
+}
+
+/// Update the message of a session to reflect its current status. Sessions whose message is still
+/// being posted have a message id of 0 and are skipped
+async fn refresh_message(ctx: &BotContext, session: &EventSession) -> Result<(), Box<dyn Error>> {
+    if session.message_id == 0 {
+        return Ok(());
+    }
+    let event_type = ctx
+        .database
+        .query_opt::<EventType>(
+            "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+            &[&session.guild_id, &session.event_type],
+        )
+        .await?
+        .map_or_else(|| session.event_type.to_string(), |e| e.name);
+    let embed = session_embed(session, &event_type)?;
+    let components = session_components(session.status);
+    ctx.http
+        .update_message(session.channel_id.0, Id::new(session.message_id as u64))
+        .embeds(Some(&[embed]))?
+        .components(Some(&components))?
+        .exec()
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use chrono::{Duration as CDuration, Utc};
+use rowifi_database::postgres::Row;
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    analytics::{
+        AnalyticsAlert, Group, Resolution, Role, HOURLY_RETENTION_DAYS, RAW_RETENTION_DAYS,
+    },
+    guild::GuildType,
+    roblox::id::GroupId,
+};
+use std::{collections::HashSet, error::Error};
+use tokio::time::{interval, Duration};
+
+pub async fn group_analytics(ctx: BotContext) {
+    tracing::info!("Group analytics starting");
+    let mut interval = interval(Duration::from_secs(3600));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in group analytics");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    // Every registered group is collected once no matter how many servers registered it, and each
+    // cluster takes its own share of the groups rather than only those of the servers it holds
+    let rows = ctx
+        .database
+        .query::<Row>(
+            "SELECT DISTINCT unnest(registered_groups) AS group_id FROM guilds WHERE kind = $1",
+            &[&GuildType::Beta],
+        )
+        .await?;
+    let clusters = ctx.total_shards.div_ceil(ctx.shards_per_cluster).max(1);
+    let groups = rows
+        .iter()
+        .map(|r| r.get::<_, i64>("group_id"))
+        .filter(|g| g.unsigned_abs() % clusters == ctx.cluster_id)
+        .collect::<Vec<_>>();
+    if !groups.is_empty() {
+        collect(ctx, groups).await?;
+    }
+    rollup(ctx, clusters).await?;
+    Ok(())
+}
+
+async fn collect(ctx: &BotContext, groups: Vec<i64>) -> Result<(), Box<dyn Error>> {
+    // Groups snapshotted recently are skipped so that restarts do not take extra snapshots
+    let recent = ctx
+        .database
+        .query::<Row>(
+            "SELECT DISTINCT group_id FROM group_analytics WHERE group_id = ANY($1) AND timestamp > $2",
+            &[&groups, &(Utc::now() - CDuration::minutes(50))],
+        )
+        .await?
+        .iter()
+        .map(|r| r.get::<_, i64>("group_id"))
+        .collect::<HashSet<_>>();
+
+    for group_id in groups.into_iter().filter(|g| !recent.contains(g)) {
+        let group = match ctx.roblox.get_group_ranks(GroupId(group_id as u64)).await {
+            Ok(Some(g)) => g,
+            Ok(None) => continue,
+            Err(err) => {
+                tracing::error!(err = ?err, group_id = group_id, "Error in group analytics");
+                continue;
+            }
+        };
+        let roles = group
+            .roles
+            .iter()
+            .map(|r| Role {
+                id: r.id.0 as i64,
+                rank: i64::from(r.rank),
+                member_count: r.member_count.unwrap_or_default() as i64,
+            })
+            .collect::<Vec<_>>();
+        let snapshot = Group {
+            group_id,
+            member_count: roles.iter().map(|r| r.member_count).sum::<i64>(),
+            roles,
+            timestamp: Utc::now(),
+        };
+        ctx.database
+            .execute(
+                "INSERT INTO group_analytics(group_id, roles, member_count, timestamp) VALUES($1, $2, $3, $4)",
+                &[&snapshot.group_id, &snapshot.roles, &snapshot.member_count, &snapshot.timestamp],
+            )
+            .await?;
+        if let Err(err) = evaluate_alerts(ctx, &snapshot).await {
+            tracing::error!(err = ?err, group_id = group_id, "Error in evaluating analytics alerts");
+        }
+    }
+    Ok(())
+}
+
+/// Compare a new snapshot against the oldest snapshot within the window of every alert on its
+/// group. An alert that has fired is not evaluated again until its window has passed
+async fn evaluate_alerts(ctx: &BotContext, snapshot: &Group) -> Result<(), Box<dyn Error>> {
+    let alerts = ctx
+        .database
+        .query::<AnalyticsAlert>(
+            "SELECT * FROM analytics_alerts WHERE group_id = $1",
+            &[&snapshot.group_id],
+        )
+        .await?;
+
+    for alert in alerts {
+        let window_start = snapshot.timestamp - CDuration::hours(i64::from(alert.window_hours));
+        if alert.last_triggered.map_or(false, |t| t > window_start) {
+            continue;
+        }
+        let baseline = match ctx
+            .database
+            .query_opt::<Group>(
+                "SELECT * FROM group_analytics WHERE group_id = $1 AND timestamp >= $2 ORDER BY timestamp LIMIT 1",
+                &[&snapshot.group_id, &window_start],
+            )
+            .await?
+        {
+            Some(b) if b.timestamp < snapshot.timestamp => b,
+            _ => continue,
+        };
+
+        let before = baseline.member_count_of(alert.rank_id);
+        let after = snapshot.member_count_of(alert.rank_id);
+        if !alert.is_triggered(before, after) {
+            continue;
+        }
+
+        let change = after - before;
+        let mut change_text = format!("{:+}", change);
+        if before > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let percent = change as f64 * 100.0 / before as f64;
+            change_text = format!("{}, {:+.1}%", change_text, percent);
+        }
+        let description = format!(
+            "{} went from {} to {} members within {} hours ({})",
+            alert.target(),
+            before,
+            after,
+            alert.window_hours,
+            change_text
+        );
+        let color = if change < 0 {
+            Color::Red
+        } else {
+            Color::DarkGreen
+        };
+        let embed = EmbedBuilder::new()
+            .default_data()
+            .color(color as u32)
+            .title("Analytics Alert")
+            .description(description)
+            .build()?;
+        // A deleted or inaccessible channel should not stop the other alerts from being sent
+        if let Err(err) = ctx
+            .http
+            .create_message(alert.channel_id.0)
+            .embeds(&[embed])?
+            .exec()
+            .await
+        {
+            tracing::error!(err = ?err, alert_id = alert.alert_id, "Error in sending analytics alert");
+            continue;
+        }
+
+        ctx.database
+            .execute(
+                "UPDATE analytics_alerts SET last_triggered = $1 WHERE alert_id = $2",
+                &[&snapshot.timestamp, &alert.alert_id],
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Roll snapshots past their retention up into the next coarser resolution. The last snapshot of
+/// every hour or day is kept, and the cutoff is truncated so that only whole buckets are rolled up
+async fn rollup(ctx: &BotContext, clusters: u64) -> Result<(), Box<dyn Error>> {
+    let steps = [
+        (Resolution::Raw, Resolution::Hourly, RAW_RETENTION_DAYS),
+        (Resolution::Hourly, Resolution::Daily, HOURLY_RETENTION_DAYS),
+    ];
+    for (from, to, retention_days) in steps {
+        let unit = to.unit().unwrap_or("day");
+        let insert = format!(
+            "INSERT INTO {1}(group_id, roles, member_count, timestamp) SELECT DISTINCT ON (g.group_id, date_trunc('{2}', g.timestamp)) g.group_id, g.roles, g.member_count, date_trunc('{2}', g.timestamp) FROM {0} AS g WHERE g.timestamp < date_trunc('{2}', $1::TIMESTAMPTZ) AND abs(g.group_id) % $2 = $3 ORDER BY g.group_id, date_trunc('{2}', g.timestamp), g.timestamp DESC",
+            from.table(),
+            to.table(),
+            unit
+        );
+        let delete = format!(
+            "DELETE FROM {} WHERE timestamp < date_trunc('{}', $1::TIMESTAMPTZ) AND abs(group_id) % $2 = $3",
+            from.table(),
+            unit
+        );
+        let cutoff = Utc::now() - CDuration::days(retention_days);
+        let clusters = clusters as i64;
+        let cluster_id = ctx.cluster_id as i64;
+
+        let mut db = ctx.database.get().await?;
+        let transaction = db.transaction().await?;
+        let insert = transaction.prepare_cached(&insert).await?;
+        transaction
+            .execute(&insert, &[&cutoff, &clusters, &cluster_id])
+            .await?;
+        let delete = transaction.prepare_cached(&delete).await?;
+        transaction
+            .execute(&delete, &[&cutoff, &clusters, &cluster_id])
+            .await?;
+        transaction.commit().await?;
+    }
+    Ok(())
+}
@@ -1,7 +1,23 @@
+pub mod account_validation;
 pub mod activity;
+pub mod analytics_registrations;
 pub mod auto_detection;
+pub mod blacklist_expiry;
 pub mod event_handler;
+pub mod event_quotas;
+pub mod event_sessions;
+pub mod group_analytics;
+pub mod notes_reencryption;
+pub mod unverified;
 
+pub use account_validation::account_validation;
 pub use activity::activity;
+pub use analytics_registrations::analytics_registrations;
 // pub use auto_detection::auto_detection;
+pub use blacklist_expiry::blacklist_expiry;
 pub use event_handler::EventHandler;
+pub use event_quotas::event_quotas;
+pub use event_sessions::event_sessions;
+pub use group_analytics::group_analytics;
+pub use notes_reencryption::notes_reencryption;
+pub use unverified::unverified;
@@ -0,0 +1,160 @@
+use rowifi_database::{
+    blind_index, decrypt_bytes, encrypt_bytes, is_current_ciphertext, postgres::Row,
+    CIPHERTEXT_VERSION,
+};
+use rowifi_framework::context::BotContext;
+use rowifi_models::events::EventLog;
+use std::error::Error;
+use tokio::time::{interval, Duration};
+
+/// The number of events re-encrypted in a single query
+const BATCH_SIZE: i64 = 100;
+
+pub async fn notes_reencryption(ctx: BotContext) {
+    tracing::info!("Event notes re-encryption starting");
+    let mut interval = interval(Duration::from_secs(6 * 3600));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in event notes re-encryption");
+        }
+        if let Err(err) = index_notes(&ctx).await {
+            tracing::error!(err = ?err, "Error in event notes indexing");
+        }
+    }
+}
+
+/// Re-encrypt the notes that are in the legacy format or were encrypted with a retired key, so
+/// that retired keys can eventually be removed from the keyring
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let keyring = &ctx.database.keyring;
+    let primary_id = i32::from(keyring.primary_id());
+    let version = i32::from(CIPHERTEXT_VERSION);
+
+    let mut last = (0_i64, 0_i64);
+    let mut reencrypted = 0_u64;
+    loop {
+        let events = ctx
+            .database
+            .query::<EventLog>(
+                "SELECT * FROM events WHERE guild_id = ANY($1) AND notes IS NOT NULL AND NOT (length(notes) > 14 AND get_byte(notes, 0) = $2 AND get_byte(notes, 1) = $3) AND (guild_id, guild_event_id) > ($4, $5) ORDER BY guild_id, guild_event_id LIMIT $6",
+                &[&servers, &version, &primary_id, &last.0, &last.1, &BATCH_SIZE],
+            )
+            .await?;
+        let final_event = match events.last() {
+            Some(e) => e,
+            None => break,
+        };
+        last = (
+            final_event.guild_id.get() as i64,
+            final_event.guild_event_id,
+        );
+
+        for event in &events {
+            let notes = match &event.notes {
+                Some(n) if !is_current_ciphertext(n, keyring) => n,
+                _ => continue,
+            };
+            let guild_id = event.guild_id.get();
+            let plaintext = match decrypt_bytes(
+                notes,
+                keyring,
+                guild_id,
+                event.host_id as u64,
+                event.notes_timestamp().timestamp() as u64,
+            ) {
+                Ok(p) => p,
+                Err(err) => {
+                    tracing::error!(err = ?err, guild_id = ?event.guild_id, event_id = event.guild_event_id, "Error in decrypting event notes");
+                    continue;
+                }
+            };
+            let updated = encrypt_bytes(&plaintext, keyring, guild_id)?;
+            // The notes are compared so that an edit made in the meantime is not overwritten
+            let row = ctx
+                .database
+                .query_opt::<Row>(
+                    "UPDATE events SET notes = $1 WHERE guild_id = $2 AND guild_event_id = $3 AND notes = $4 RETURNING guild_event_id",
+                    &[&updated, &event.guild_id, &event.guild_event_id, notes],
+                )
+                .await?;
+            if row.is_some() {
+                reencrypted += 1;
+            }
+        }
+    }
+
+    if reencrypted > 0 {
+        tracing::info!(count = reencrypted, "Re-encrypted event notes");
+    }
+    Ok(())
+}
+
+/// Index the notes of events logged before notes were indexed. Notes that cannot be decrypted are
+/// given an empty index so that they are not retried on every run
+async fn index_notes(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let keyring = &ctx.database.keyring;
+
+    let mut indexed = 0_u64;
+    loop {
+        // Every event in a batch is given an index, so the next batch never repeats one
+        let events = ctx
+            .database
+            .query::<EventLog>(
+                "SELECT * FROM events WHERE guild_id = ANY($1) AND notes IS NOT NULL AND notes_index IS NULL LIMIT $2",
+                &[&servers, &BATCH_SIZE],
+            )
+            .await?;
+        if events.is_empty() {
+            break;
+        }
+
+        for event in &events {
+            let guild_id = event.guild_id.get();
+            let notes_index = match event.notes.as_ref().map(|notes| {
+                decrypt_bytes(
+                    notes,
+                    keyring,
+                    guild_id,
+                    event.host_id as u64,
+                    event.notes_timestamp().timestamp() as u64,
+                )
+            }) {
+                Some(Ok(plaintext)) => blind_index(
+                    &String::from_utf8_lossy(&plaintext),
+                    keyring.index_key(),
+                    guild_id,
+                ),
+                Some(Err(err)) => {
+                    tracing::error!(err = ?err, guild_id = ?event.guild_id, event_id = event.guild_event_id, "Error in decrypting event notes");
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+            ctx.database
+                .execute(
+                    "UPDATE events SET notes_index = $1 WHERE guild_id = $2 AND guild_event_id = $3 AND notes_index IS NULL",
+                    &[&notes_index, &event.guild_id, &event.guild_event_id],
+                )
+                .await?;
+            indexed += 1;
+        }
+    }
+
+    if indexed > 0 {
+        tracing::info!(count = indexed, "Indexed event notes");
+    }
+    Ok(())
+}
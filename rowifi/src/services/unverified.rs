@@ -0,0 +1,154 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    guild::{unverified::UnverifiedMember, RoGuild},
+    id::{GuildId, UserId},
+};
+use std::{collections::HashMap, error::Error};
+use tokio::time::{interval, Duration};
+
+pub async fn unverified(ctx: BotContext) {
+    tracing::info!("Unverified member service starting");
+    let mut interval = interval(Duration::from_secs(60));
+    loop {
+        interval.tick().await;
+        if let Err(err) = execute(&ctx).await {
+            tracing::error!(err = ?err, "Error in unverified member service");
+        }
+    }
+}
+
+async fn execute(ctx: &BotContext) -> Result<(), Box<dyn Error>> {
+    let servers = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|s| s as i64)
+        .collect::<Vec<_>>();
+    let now = Utc::now();
+    let pending = ctx
+        .database
+        .query::<UnverifiedMember>(
+            "SELECT * FROM unverified_members WHERE guild_id = ANY($1) AND (next_reminder <= $2 OR kick_at <= $2)",
+            &[&servers, &now],
+        )
+        .await?;
+
+    let mut guilds = HashMap::<GuildId, RoGuild>::new();
+    for pending_member in pending {
+        let guild_id = pending_member.guild_id;
+        let user_id = pending_member.user_id;
+        if ctx
+            .database
+            .get_linked_user(user_id, guild_id)
+            .await?
+            .is_some()
+        {
+            remove_pending(ctx, guild_id, user_id).await?;
+            continue;
+        }
+        if !guilds.contains_key(&guild_id) {
+            let guild = ctx.database.get_guild(guild_id).await?;
+            guilds.insert(guild_id, guild);
+        }
+        let guild = &guilds[&guild_id];
+
+        // Drop the deadline if the server disabled the auto-kick after the member joined
+        let kick_at = guild.unverified_kick.and(pending_member.kick_at);
+        if let (Some(kick_at), Some(hours)) = (kick_at, guild.unverified_kick) {
+            if kick_at <= now {
+                let kicked = ctx
+                    .http
+                    .remove_guild_member(guild_id.0, user_id.0)
+                    .exec()
+                    .await
+                    .is_ok();
+                if kicked {
+                    let log_embed = EmbedBuilder::new()
+                        .default_data()
+                        .title("Unverified Kick")
+                        .description(format!(
+                            "<@{}> was kicked for not verifying within {} hours",
+                            user_id, hours
+                        ))
+                        .build()
+                        .unwrap();
+                    ctx.log_guild(guild_id, log_embed).await;
+                }
+                remove_pending(ctx, guild_id, user_id).await?;
+                continue;
+            }
+        }
+
+        let next_reminder = match (pending_member.next_reminder, guild.verification_reminder) {
+            (Some(next_reminder), Some(hours)) if next_reminder <= now => {
+                send_reminder(ctx, guild_id, user_id, kick_at.map(|k| k.timestamp())).await;
+                Some(now + ChronoDuration::hours(i64::from(hours)))
+            }
+            (next_reminder, Some(_)) => next_reminder,
+            (_, None) => None,
+        };
+        if next_reminder.is_none() && kick_at.is_none() {
+            remove_pending(ctx, guild_id, user_id).await?;
+        } else {
+            ctx.database
+                .execute(
+                    "UPDATE unverified_members SET next_reminder = $1, kick_at = $2 WHERE guild_id = $3 AND user_id = $4",
+                    &[&next_reminder, &kick_at, &guild_id, &user_id],
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn send_reminder(
+    ctx: &BotContext,
+    guild_id: GuildId,
+    user_id: UserId,
+    kick_timestamp: Option<i64>,
+) {
+    let server_name = match ctx.cache.guild(guild_id) {
+        Some(s) => s.name.clone(),
+        None => return,
+    };
+    let prefix = ctx
+        .prefixes
+        .get(&guild_id)
+        .map_or_else(|| ctx.default_prefix.clone(), |p| p.value().clone());
+    let mut content = format!(
+        "You have not verified in **{}** yet. Please use `{}verify` or `/verify` in the server to link your Roblox account.",
+        server_name, prefix
+    );
+    if let Some(kick_timestamp) = kick_timestamp {
+        content.push_str(&format!(
+            "\nYou will be kicked from the server if you have not verified by <t:{}:f>.",
+            kick_timestamp
+        ));
+    }
+    if let Ok(response) = ctx.http.create_private_channel(user_id.0).exec().await {
+        if let Ok(channel) = response.model().await {
+            let _ = ctx
+                .http
+                .create_message(channel.id)
+                .content(&content)
+                .unwrap()
+                .exec()
+                .await;
+        }
+    }
+}
+
+async fn remove_pending(
+    ctx: &BotContext,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Box<dyn Error>> {
+    ctx.database
+        .execute(
+            "DELETE FROM unverified_members WHERE guild_id = $1 AND user_id = $2",
+            &[&guild_id, &user_id],
+        )
+        .await?;
+    Ok(())
+}
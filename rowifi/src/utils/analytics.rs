@@ -0,0 +1,41 @@
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    id::{GuildId, UserId},
+    roblox::id::UserId as RobloxUserId,
+};
+
+/// The link between a member and a group that registering the group for analytics requires
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupLink {
+    /// The member has no linked Roblox account
+    Unverified,
+    /// The linked account of the member does not hold a high enough rank in the group
+    Missing,
+    Linked {
+        roblox_id: i64,
+    },
+}
+
+/// Check the rank the linked account of a member holds in a group against the configured minimum
+pub async fn group_link(
+    bot: &BotContext,
+    discord_id: UserId,
+    guild_id: GuildId,
+    group_id: i64,
+) -> Result<GroupLink, RoError> {
+    let roblox_id = match bot.database.get_linked_user(discord_id, guild_id).await? {
+        Some(u) => u.roblox_id,
+        None => return Ok(GroupLink::Unverified),
+    };
+    let rank = bot
+        .roblox
+        .get_user_roles(RobloxUserId(roblox_id as u64))
+        .await?
+        .into_iter()
+        .find(|r| r.group.id.0 as i64 == group_id)
+        .map(|r| r.role.rank);
+    match rank {
+        Some(rank) if rank >= bot.analytics_min_rank => Ok(GroupLink::Linked { roblox_id }),
+        _ => Ok(GroupLink::Missing),
+    }
+}
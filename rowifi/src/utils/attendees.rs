@@ -0,0 +1,139 @@
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    discord::channel::GuildChannel,
+    id::{GuildId, UserId},
+};
+use std::collections::HashSet;
+
+use super::linked_users;
+
+/// The outcome of resolving the attendees given to an event
+#[derive(Default)]
+pub struct ResolvedAttendees {
+    pub roblox_ids: Vec<i64>,
+    /// Discord members who were given but are not verified
+    pub unverified: Vec<UserId>,
+    /// Entries that did not match a roblox user, member, role or voice channel
+    pub invalid: Vec<String>,
+}
+
+/// Resolve a list of attendees to roblox ids. Each entry may be a roblox username, a member
+/// mention, a role mention (every member with the role), a voice channel mention (every member
+/// connected to it) or `vc` for the voice channel the author is connected to
+pub async fn resolve_attendees(
+    bot: &BotContext,
+    guild_id: GuildId,
+    author_id: UserId,
+    input: &str,
+) -> Result<ResolvedAttendees, RoError> {
+    let mut resolved = ResolvedAttendees::default();
+    let mut discord_ids = HashSet::new();
+    let mut usernames = Vec::new();
+    let is_bot = |user_id: UserId| {
+        bot.cache
+            .member(guild_id, user_id)
+            .map_or(false, |m| m.user.bot)
+    };
+
+    for entry in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|e| !e.is_empty())
+    {
+        if entry.starts_with("<@&") {
+            match parse_role(entry) {
+                Some(role_id) if bot.cache.roles(guild_id).contains(&role_id) => {
+                    for member_id in bot.cache.members(guild_id) {
+                        if let Some(member) = bot.cache.member(guild_id, member_id) {
+                            if member.roles.contains(&role_id) && !member.user.bot {
+                                discord_ids.insert(member_id);
+                            }
+                        }
+                    }
+                }
+                _ => resolved.invalid.push(entry.to_string()),
+            }
+        } else if entry.starts_with("<@") {
+            match parse_username(entry) {
+                Some(user_id) => {
+                    discord_ids.insert(user_id);
+                }
+                None => resolved.invalid.push(entry.to_string()),
+            }
+        } else if entry.starts_with("<#") || entry.eq_ignore_ascii_case("vc") {
+            let channel_id = if entry.starts_with("<#") {
+                parse_channel(entry).filter(|c| bot.cache.guild_channels(guild_id).contains(c))
+            } else {
+                bot.cache.voice_channel(guild_id, author_id)
+            };
+            match channel_id.and_then(|c| bot.cache.channel(c).map(|ch| (c, ch))) {
+                Some((channel_id, channel))
+                    if matches!(
+                        channel.as_ref(),
+                        GuildChannel::Voice(_) | GuildChannel::Stage(_)
+                    ) =>
+                {
+                    discord_ids.extend(
+                        bot.cache
+                            .voice_channel_members(channel_id)
+                            .into_iter()
+                            .filter(|m| !is_bot(*m)),
+                    );
+                }
+                _ => resolved.invalid.push(entry.to_string()),
+            }
+        } else {
+            usernames.push(entry);
+        }
+    }
+
+    if !discord_ids.is_empty() {
+        let member_ids = discord_ids
+            .iter()
+            .map(|m| m.get() as i64)
+            .collect::<Vec<_>>();
+        let mut verified = HashSet::new();
+        for user in linked_users(bot, guild_id, &member_ids).await? {
+            verified.insert(user.discord_id);
+            resolved.roblox_ids.push(user.roblox_id);
+        }
+        resolved.unverified = discord_ids
+            .into_iter()
+            .filter(|d| !verified.contains(d))
+            .collect();
+    }
+
+    for chunk in usernames.chunks(100) {
+        let users = bot.roblox.get_users_from_usernames(chunk).await?;
+        for username in chunk {
+            // Users are matched on the name they were requested by since it may be a previous name
+            let user = users.iter().find(|u| {
+                u.requested_username
+                    .as_ref()
+                    .unwrap_or(&u.name)
+                    .eq_ignore_ascii_case(username)
+            });
+            match user {
+                Some(user) => resolved.roblox_ids.push(user.id.0 as i64),
+                None => resolved.invalid.push((*username).to_string()),
+            }
+        }
+    }
+
+    resolved.roblox_ids.sort_unstable();
+    resolved.roblox_ids.dedup();
+    Ok(resolved)
+}
+
+/// Join a list for an embed field, leaving out the entries that would not fit
+pub fn truncated_list(items: &[String]) -> String {
+    let mut list = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if list.len() + item.len() > 950 {
+            list.push_str(&format!("and {} more", items.len() - i));
+            break;
+        }
+        list.push_str(item);
+        list.push('\n');
+    }
+    list
+}
@@ -0,0 +1,16 @@
+use chrono::Duration;
+
+/// Parse a duration made of a positive number and a unit such as `12h` or `2w`, given the length
+/// of every unit that is accepted. Durations longer than `max` are rejected
+pub fn parse_duration(arg: &str, units: &[(char, Duration)], max: Duration) -> Option<Duration> {
+    let mut chars = arg.chars();
+    let unit = chars.next_back()?;
+    let num = chars.as_str().parse::<i64>().ok()?;
+    let length = units.iter().find(|(u, _)| *u == unit)?.1;
+    let secs = num.checked_mul(length.num_seconds())?;
+    if secs > 0 && secs <= max.num_seconds() {
+        Some(Duration::seconds(secs))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,33 @@
+use rowifi_framework::prelude::*;
+
+/// The file formats data can be exported as
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Quote a value so that commas, quotes and new lines do not break the row
+pub fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+impl FromArg for ExportFormat {
+    type Error = ParseError;
+
+    fn from_arg(arg: &str) -> Result<Self, Self::Error> {
+        match arg.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(ParseError("one of `CSV` `JSON`")),
+        }
+    }
+
+    fn from_interaction(option: &CommandDataOption) -> Result<Self, Self::Error> {
+        let arg = match &option.value {
+            CommandOptionValue::String(value) => value.to_string(),
+            _ => unreachable!("ExportFormat unreached"),
+        };
+        Self::from_arg(&arg)
+    }
+}
@@ -0,0 +1,47 @@
+use rowifi_database::postgres::Row;
+use rowifi_framework::{context::BotContext, error::RoError};
+use rowifi_models::{id::GuildId, user::RoGuildUser};
+use std::error::Error;
+
+pub fn mass_update_user(row: &Row, guild_id: GuildId) -> Result<RoGuildUser, Box<dyn Error>> {
+    let guild_id = row.try_get("guild_id").unwrap_or(guild_id);
+    let discord_id = row.try_get("discord_id")?;
+    let roblox_id = row.try_get("roblox_id").ok();
+    let default_roblox_id = row.try_get("default_roblox_id")?;
+
+    Ok(RoGuildUser {
+        guild_id,
+        discord_id,
+        roblox_id: roblox_id.unwrap_or(default_roblox_id),
+    })
+}
+
+/// Get the account each of the given members uses in a guild, falling back to their default
+/// account. Members who are not verified are left out
+pub async fn linked_users(
+    bot: &BotContext,
+    guild_id: GuildId,
+    discord_ids: &[i64],
+) -> Result<Vec<RoGuildUser>, RoError> {
+    let rows = bot
+        .database
+        .query::<Row>(
+            r#"
+            SELECT users.discord_id, l.roblox_id, users.default_roblox_id FROM
+            (SELECT * FROM linked_users WHERE guild_id = $1) AS l
+            RIGHT JOIN users
+            ON users.discord_id = l.discord_id
+            WHERE users.discord_id = ANY($2)
+        "#,
+            &[&guild_id, &discord_ids],
+        )
+        .await?;
+    let mut users = Vec::new();
+    for row in rows {
+        match mass_update_user(&row, guild_id) {
+            Ok(u) => users.push(u),
+            Err(err) => tracing::error!("error in deserializing user: {}", err),
+        }
+    }
+    Ok(users)
+}
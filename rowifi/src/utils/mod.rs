@@ -1,3 +1,19 @@
+mod analytics;
+mod attendees;
+mod duration;
+mod export;
+mod linked_users;
+mod points;
+mod quota;
+mod session;
 mod update_user;
 
+pub use analytics::*;
+pub use attendees::*;
+pub use duration::*;
+pub use export::*;
+pub use linked_users::*;
+pub use points::*;
+pub use quota::*;
+pub use session::*;
 pub use update_user::*;
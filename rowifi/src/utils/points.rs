@@ -0,0 +1,33 @@
+use rowifi_framework::{context::BotContext, error::RoError};
+use rowifi_models::{events::EventPoints, id::GuildId};
+
+/// Get the event points of the members of a server, highest first. Hosts and attendees are
+/// awarded the points of the event's type and voided events are left out. Passing a roblox id
+/// limits the result to that member
+pub async fn event_points(
+    bot: &BotContext,
+    guild_id: GuildId,
+    roblox_id: Option<i64>,
+) -> Result<Vec<EventPoints>, RoError> {
+    let points = bot
+        .database
+        .query::<EventPoints>(
+            r#"
+            SELECT roblox_id, SUM(points) AS points FROM (
+                SELECT events.host_id AS roblox_id, event_types.host_points AS points FROM events
+                INNER JOIN event_types ON event_types.guild_id = events.guild_id AND event_types.event_type_guild_id = events.event_type
+                WHERE events.guild_id = $1 AND events.voided_at IS NULL
+                UNION ALL
+                SELECT attendee AS roblox_id, event_types.attend_points AS points FROM events
+                CROSS JOIN LATERAL unnest(events.attendees) AS attendee
+                INNER JOIN event_types ON event_types.guild_id = events.guild_id AND event_types.event_type_guild_id = events.event_type
+                WHERE events.guild_id = $1 AND events.voided_at IS NULL
+            ) AS p
+            WHERE $2::BIGINT IS NULL OR roblox_id = $2
+            GROUP BY roblox_id ORDER BY points DESC
+        "#,
+            &[&guild_id, &roblox_id],
+        )
+        .await?;
+    Ok(points)
+}
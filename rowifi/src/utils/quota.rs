@@ -0,0 +1,212 @@
+use chrono::{Duration as CDuration, Utc};
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    events::{EventLeaderboardEntry, EventQuota, EventType, QuotaKind},
+    guild::RoGuild,
+    id::UserId,
+};
+use std::collections::HashMap;
+
+use super::{csv_field, linked_users};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QuotaStatus {
+    Met,
+    NotMet,
+    Exempt,
+}
+
+/// The standing of a single member against a single quota
+pub struct QuotaEntry {
+    pub quota_id: i64,
+    pub discord_id: UserId,
+    pub username: String,
+    /// The linked Roblox account of the member. Unverified members count as not meeting the quota
+    pub roblox_id: Option<i64>,
+    pub count: i64,
+    pub status: QuotaStatus,
+}
+
+/// Check every member holding a quota's role against the events logged in the quota's window
+pub async fn build_quota_report(
+    bot: &BotContext,
+    guild: &RoGuild,
+    quotas: &[EventQuota],
+) -> Result<Vec<QuotaEntry>, RoError> {
+    let guild_id = guild.guild_id;
+    let members = bot
+        .cache
+        .members(guild_id)
+        .into_iter()
+        .filter_map(|m| bot.cache.member(guild_id, m).map(|c| (m, c)))
+        .filter(|(_, c)| quotas.iter().any(|q| c.roles.contains(&q.role_id)))
+        .collect::<Vec<_>>();
+
+    let member_ids = members
+        .iter()
+        .map(|(m, _)| m.0.get() as i64)
+        .collect::<Vec<_>>();
+    let linked = linked_users(bot, guild_id, &member_ids)
+        .await?
+        .into_iter()
+        .map(|u| (u.discord_id, u.roblox_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut entries = Vec::new();
+    for quota in quotas {
+        let since = Utc::now() - CDuration::days(i64::from(quota.window_days));
+        let statement = match quota.kind {
+            QuotaKind::Host => {
+                "SELECT host_id AS roblox_id, COUNT(*) AS count FROM events WHERE guild_id = $1 AND voided_at IS NULL AND ($2::INTEGER IS NULL OR event_type = $2) AND timestamp >= $3 GROUP BY host_id"
+            }
+            QuotaKind::Attend => {
+                "SELECT attendee AS roblox_id, COUNT(*) AS count FROM events, unnest(attendees) AS attendee WHERE guild_id = $1 AND voided_at IS NULL AND ($2::INTEGER IS NULL OR event_type = $2) AND timestamp >= $3 GROUP BY attendee"
+            }
+        };
+        let counts = bot
+            .database
+            .query::<EventLeaderboardEntry>(statement, &[&guild_id, &quota.event_type, &since])
+            .await?
+            .into_iter()
+            .map(|e| (e.roblox_id, e.count))
+            .collect::<HashMap<_, _>>();
+
+        for (member_id, member) in &members {
+            if !member.roles.contains(&quota.role_id) {
+                continue;
+            }
+            let roblox_id = linked.get(member_id).copied();
+            let count = roblox_id
+                .and_then(|r| counts.get(&r).copied())
+                .unwrap_or_default();
+            let status = if guild
+                .quota_exempt_roles
+                .iter()
+                .any(|r| member.roles.contains(r))
+            {
+                QuotaStatus::Exempt
+            } else if count >= i64::from(quota.required) {
+                QuotaStatus::Met
+            } else {
+                QuotaStatus::NotMet
+            };
+            entries.push(QuotaEntry {
+                quota_id: quota.quota_id,
+                discord_id: *member_id,
+                username: member.user.name.clone(),
+                roblox_id,
+                count,
+                status,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// The most characters the fields of a report may take up, leaving room for the title, footer and
+/// the rest of the embed under Discord's limit of 6000
+const MAX_REPORT_LENGTH: usize = 5500;
+
+/// The summary of a report with one field per quota
+pub fn quota_report_embed(
+    quotas: &[EventQuota],
+    event_types: &[EventType],
+    entries: &[QuotaEntry],
+) -> EmbedBuilder {
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title("Event Quota Report");
+    // Embeds hold at most 25 fields and 6000 characters. The CSV report covers every quota
+    let mut length = 0;
+    let mut shown = 0;
+    for quota in quotas.iter().take(25) {
+        let quota_entries = entries
+            .iter()
+            .filter(|e| e.quota_id == quota.quota_id)
+            .collect::<Vec<_>>();
+        let count = |status| quota_entries.iter().filter(|e| e.status == status).count();
+        let mut not_met = quota_entries
+            .iter()
+            .filter(|e| e.status == QuotaStatus::NotMet)
+            .map(|e| format!("<@{}> ({})", e.discord_id, e.count))
+            .take(15)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if count(QuotaStatus::NotMet) > 15 {
+            not_met.push_str(", ...");
+        }
+
+        let mut desc = format!(
+            "{}\nMet: {} | Not Met: {} | Exempt: {}",
+            quota_requirement(quota, event_types),
+            count(QuotaStatus::Met),
+            count(QuotaStatus::NotMet),
+            count(QuotaStatus::Exempt)
+        );
+        if !not_met.is_empty() {
+            desc.push_str(&format!("\nMissing: {}", not_met));
+        }
+        let name = format!("Quota Id: {}", quota.quota_id);
+        length += name.chars().count() + desc.chars().count();
+        if length > MAX_REPORT_LENGTH {
+            break;
+        }
+        embed = embed.field(EmbedFieldBuilder::new(name, desc));
+        shown += 1;
+    }
+    if shown < quotas.len() {
+        embed = embed.footer(EmbedFooterBuilder::new(format!(
+            "Showing {} of {} quotas",
+            shown,
+            quotas.len()
+        )));
+    }
+    embed
+}
+
+/// The full report with one row per member and quota
+pub fn quota_report_csv(quotas: &[EventQuota], entries: &[QuotaEntry]) -> String {
+    let mut csv = String::from(
+        "quota_id,role_id,kind,required,window_days,discord_id,username,roblox_id,count,status\n",
+    );
+    for entry in entries {
+        let quota = match quotas.iter().find(|q| q.quota_id == entry.quota_id) {
+            Some(q) => q,
+            None => continue,
+        };
+        let status = match entry.status {
+            QuotaStatus::Met => "met",
+            QuotaStatus::NotMet => "not met",
+            QuotaStatus::Exempt => "exempt",
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            quota.quota_id,
+            quota.role_id,
+            quota.kind,
+            quota.required,
+            quota.window_days,
+            entry.discord_id,
+            csv_field(&entry.username),
+            entry.roblox_id.map_or_else(String::new, |r| r.to_string()),
+            entry.count,
+            status
+        ));
+    }
+    csv
+}
+
+/// The human readable requirement of a quota such as `Host 3 Training events every 7 days`
+pub fn quota_requirement(quota: &EventQuota, event_types: &[EventType]) -> String {
+    let event_type = quota.event_type.map_or_else(String::new, |id| {
+        event_types
+            .iter()
+            .find(|e| e.event_type_guild_id == id)
+            .map_or_else(|| format!("{} ", id), |e| format!("{} ", e.name))
+    });
+    format!(
+        "<@&{}>: {} {} {}events every {} days",
+        quota.role_id, quota.kind, quota.required, event_type, quota.window_days
+    )
+}
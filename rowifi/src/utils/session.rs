@@ -0,0 +1,177 @@
+use rowifi_framework::{context::BotContext, prelude::*};
+use rowifi_models::{
+    discord::{application::interaction::MessageComponentInteraction, channel::embed::Embed},
+    events::{EventSession, EventType, SessionStatus},
+    id::UserId,
+};
+
+pub const SESSION_RSVP: &str = "event-session-rsvp";
+pub const SESSION_CHECKIN: &str = "event-session-checkin";
+
+/// The embed shown on the message of a scheduled event
+pub fn session_embed(session: &EventSession, event_type: &str) -> Result<Embed, EmbedError> {
+    let mut embed = EmbedBuilder::new()
+        .default_data()
+        .title(format!("Scheduled Event: {}", event_type))
+        .field(EmbedFieldBuilder::new("Host", format!("<@{}>", session.host_id)).inline())
+        .field(
+            EmbedFieldBuilder::new(
+                "Starts",
+                format!("<t:{}:R>", session.start_time.timestamp()),
+            )
+            .inline(),
+        )
+        .field(EmbedFieldBuilder::new("Status", session.status.to_string()).inline())
+        .field(EmbedFieldBuilder::new("RSVPs", session.rsvps.len().to_string()).inline())
+        .field(EmbedFieldBuilder::new("Checked In", session.checked_in.len().to_string()).inline())
+        .footer(EmbedFooterBuilder::new(format!(
+            "Session Id: {}",
+            session.session_id
+        )));
+    if session.status == SessionStatus::CheckIn {
+        embed = embed.description(format!(
+            "Check-in is open until <t:{}:t>. Press `Check In` to have your attendance logged",
+            session.checkin_closes_at().timestamp()
+        ));
+    }
+    embed.build()
+}
+
+/// The RSVP and check-in buttons of a scheduled event. Buttons are disabled in the states they
+/// cannot be used in
+pub fn session_components(status: SessionStatus) -> Vec<Component> {
+    let open = matches!(status, SessionStatus::Scheduled | SessionStatus::CheckIn);
+    vec![Component::ActionRow(ActionRow {
+        components: vec![
+            Component::Button(Button {
+                custom_id: Some(SESSION_RSVP.into()),
+                disabled: !open,
+                emoji: None,
+                label: Some("RSVP".into()),
+                style: ButtonStyle::Primary,
+                url: None,
+            }),
+            Component::Button(Button {
+                custom_id: Some(SESSION_CHECKIN.into()),
+                disabled: status != SessionStatus::CheckIn,
+                emoji: None,
+                label: Some("Check In".into()),
+                style: ButtonStyle::Success,
+                url: None,
+            }),
+        ],
+    })]
+}
+
+/// Handle a press of the RSVP or check-in button of a scheduled event
+pub async fn handle_session_component(
+    bot: &BotContext,
+    component: &MessageComponentInteraction,
+) -> Result<(), RoError> {
+    let custom_id = component.data.custom_id.as_str();
+    let user_id = match component.author_id() {
+        Some(u) => UserId(u),
+        None => return Ok(()),
+    };
+    let message_id = component.message.id.get() as i64;
+    let session = match bot
+        .database
+        .query_opt::<EventSession>(
+            "SELECT * FROM event_sessions WHERE message_id = $1",
+            &[&message_id],
+        )
+        .await?
+    {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let (updated, reply) = if custom_id == SESSION_RSVP {
+        if matches!(
+            session.status,
+            SessionStatus::Scheduled | SessionStatus::CheckIn
+        ) {
+            let updated = bot
+                .database
+                .query_opt::<EventSession>(
+                    "UPDATE event_sessions SET rsvps = CASE WHEN $2 = ANY(rsvps) THEN array_remove(rsvps, $2) ELSE array_append(rsvps, $2) END WHERE session_id = $1 RETURNING *",
+                    &[&session.session_id, &user_id],
+                )
+                .await?;
+            let reply = if session.rsvps.contains(&user_id) {
+                "Your RSVP has been withdrawn"
+            } else {
+                "You have RSVP'd to this event"
+            };
+            (updated, reply)
+        } else {
+            (None, "This event is no longer accepting RSVPs")
+        }
+    } else if session.status != SessionStatus::CheckIn {
+        (None, "Check-in for this event is not open")
+    } else if session.checked_in.contains(&user_id) {
+        (None, "You have already checked in to this event")
+    } else if bot
+        .database
+        .get_linked_user(user_id, session.guild_id)
+        .await?
+        .is_none()
+    {
+        (
+            None,
+            "You must be verified to check in so that your attendance can be logged",
+        )
+    } else {
+        let updated = bot
+            .database
+            .query_opt::<EventSession>(
+                "UPDATE event_sessions SET checked_in = array_append(checked_in, $2) WHERE session_id = $1 AND status = $3 AND NOT ($2 = ANY(checked_in)) RETURNING *",
+                &[&session.session_id, &user_id, &SessionStatus::CheckIn],
+            )
+            .await?;
+        (updated, "You have checked in to this event")
+    };
+
+    let interaction = bot.http.interaction(bot.application_id);
+    if let Some(updated) = updated {
+        let event_type = bot
+            .database
+            .query_opt::<EventType>(
+                "SELECT * FROM event_types WHERE guild_id = $1 AND event_type_guild_id = $2",
+                &[&updated.guild_id, &updated.event_type],
+            )
+            .await?
+            .map_or_else(|| updated.event_type.to_string(), |e| e.name);
+        interaction
+            .interaction_callback(
+                component.id,
+                &component.token,
+                &InteractionResponse::UpdateMessage(CallbackData {
+                    allowed_mentions: None,
+                    content: None,
+                    components: Some(session_components(updated.status)),
+                    embeds: Some(vec![session_embed(&updated, &event_type)?]),
+                    flags: None,
+                    tts: None,
+                }),
+            )
+            .exec()
+            .await?;
+    } else {
+        interaction
+            .interaction_callback(
+                component.id,
+                &component.token,
+                &InteractionResponse::DeferredUpdateMessage,
+            )
+            .exec()
+            .await?;
+    }
+    interaction
+        .create_followup_message(&component.token)
+        .ephemeral(true)
+        .content(reply)?
+        .exec()
+        .await?;
+    Ok(())
+}
@@ -1,10 +1,13 @@
+use chrono::{Duration as ChronoDuration, Utc};
 use itertools::Itertools;
 use rowifi_cache::{CachedGuild, CachedMember};
 use rowifi_framework::{context::BotContext, error::RoError};
 use rowifi_models::{
     bind::Bind,
+    blacklist::{Blacklist, SubscribedBlacklist},
+    discord::datetime::Timestamp,
     guild::{BlacklistActionType, RoGuild},
-    id::RoleId,
+    id::{GuildId, RoleId, UserId},
     roblox::id::{AssetId as RobloxAssetId, UserId as RobloxUserId},
     rolang::RoCommandUser,
     user::RoGuildUser,
@@ -17,15 +20,21 @@ pub struct UpdateUser<'u> {
     pub user: &'u RoGuildUser,
     pub server: &'u CachedGuild,
     pub guild: &'u RoGuild,
+    /// The blacklists of the shared lists the guild is subscribed to
+    pub subscribed_blacklists: &'u [SubscribedBlacklist],
     pub binds: &'u [Bind],
     pub guild_roles: &'u HashSet<RoleId>,
     pub bypass_roblox_cache: bool,
     pub all_roles: &'u [&'u RoleId],
 }
 
+/// The number of hours members are timed out for if the guild has not set a blacklist timeout
+pub const DEFAULT_BLACKLIST_TIMEOUT: i32 = 24;
+
 pub enum UpdateUserResult {
     Success(Vec<RoleId>, Vec<RoleId>, String),
-    Blacklist(String),
+    /// The blacklist the member matched and the action taken on them
+    Blacklist(Blacklist, BlacklistActionType),
     InvalidNickname(String),
     Error(RoError),
 }
@@ -76,38 +85,48 @@ impl UpdateUser<'_> {
             username: &roblox_user.name,
         };
 
-        if !self.guild.blacklists.is_empty() {
-            let success = self
-                .guild
-                .blacklists
-                .iter()
-                .find(|b| b.evaluate(&command_user).unwrap());
-            if let Some(success) = success {
-                match self.guild.blacklist_action {
-                    BlacklistActionType::None => {}
-                    BlacklistActionType::Kick => {
-                        let _ = self
-                            .ctx
-                            .http
-                            .remove_guild_member(self.server.id.0, self.member.user.id)
-                            .exec()
-                            .await;
-                    }
-                    BlacklistActionType::Ban => {
-                        let _ = self
-                            .ctx
-                            .http
-                            .create_ban(self.server.id.0, self.member.user.id)
-                            .exec()
-                            .await;
-                    }
-                };
-                return UpdateUserResult::Blacklist(success.reason.clone());
-            }
+        let success = self
+            .guild
+            .blacklists
+            .iter()
+            .filter(|b| !b.is_expired())
+            .find(|b| b.evaluate(&command_user).unwrap())
+            .map(|b| {
+                (
+                    b.clone(),
+                    b.effective_action(self.guild.blacklist_action),
+                    self.guild.guild_id,
+                )
+            })
+            .or_else(|| {
+                self.subscribed_blacklists
+                    .iter()
+                    .filter(|s| !s.blacklist.is_expired())
+                    .find(|s| s.blacklist.evaluate(&command_user).unwrap())
+                    .map(|s| {
+                        (
+                            s.to_match(),
+                            s.blacklist.effective_action(self.guild.blacklist_action),
+                            s.guild_id,
+                        )
+                    })
+            });
+        if let Some((success, action, list_guild_id)) = success {
+            let enforcer = BlacklistEnforcer {
+                ctx: self.ctx,
+                member: self.member,
+                server: self.server,
+                guild: self.guild,
+                guild_roles: self.guild_roles,
+                all_roles: self.all_roles,
+            };
+            enforcer.apply(&success, action, list_guild_id).await;
+            return UpdateUserResult::Blacklist(success, action);
         }
 
         let mut nick_bind: Option<&Bind> = None;
         let mut roles_to_add = Vec::new();
+        let mut premium = None;
 
         for bind in self.binds {
             match bind {
@@ -176,6 +195,33 @@ impl UpdateUser<'_> {
                         Err(err) => return UpdateUserResult::Error(err.into()),
                     }
                 }
+                Bind::Premium(p) => {
+                    let is_premium = match premium {
+                        Some(is_premium) => is_premium,
+                        None => match self
+                            .ctx
+                            .roblox
+                            .get_premium(user_id, self.bypass_roblox_cache)
+                            .await
+                        {
+                            Ok(is_premium) => {
+                                premium = Some(is_premium);
+                                is_premium
+                            }
+                            Err(err) => return UpdateUserResult::Error(err.into()),
+                        },
+                    };
+                    if is_premium {
+                        if let Some(highest) = nick_bind {
+                            if highest.priority() < p.priority {
+                                nick_bind = Some(bind);
+                            }
+                        } else {
+                            nick_bind = Some(bind);
+                        }
+                        roles_to_add.extend(p.discord_roles.iter().copied());
+                    }
+                }
             }
         }
 
@@ -218,13 +264,14 @@ impl UpdateUser<'_> {
         let mut roles = self.member.roles.clone();
         roles.extend_from_slice(&added_roles);
         roles.retain(|r| !removed_roles.contains(r));
-        let roles = roles.into_iter().unique().map(|r| r.0).collect::<Vec<_>>();
+        let roles = roles.into_iter().unique().collect::<Vec<_>>();
+        let role_ids = roles.iter().map(|r| r.0).collect::<Vec<_>>();
 
         let nick_changes = nickname != original_nick;
 
         if role_changes || nick_changes {
             if let Err(err) = update
-                .roles(&roles)
+                .roles(&role_ids)
                 .nick(Some(&nickname))
                 .unwrap()
                 .exec()
@@ -232,8 +279,143 @@ impl UpdateUser<'_> {
             {
                 return UpdateUserResult::Error(err.into());
             }
+            if role_changes && self.ctx.role_update_guilds.contains(&self.server.id) {
+                self.ctx
+                    .bot_role_updates
+                    .insert((self.server.id, UserId(self.member.user.id)), roles);
+            }
         }
 
         UpdateUserResult::Success(added_roles, removed_roles, nickname)
     }
 }
+
+/// Applies the action of a matched blacklist to a member
+pub struct BlacklistEnforcer<'u> {
+    pub ctx: &'u BotContext,
+    pub member: &'u CachedMember,
+    pub server: &'u CachedGuild,
+    pub guild: &'u RoGuild,
+    pub guild_roles: &'u HashSet<RoleId>,
+    pub all_roles: &'u [&'u RoleId],
+}
+
+impl BlacklistEnforcer<'_> {
+    /// Carry out the action of a matched blacklist. `list_guild_id` is the guild the blacklist
+    /// belongs to, which differs from the member's guild for shared blacklists
+    pub async fn apply(
+        &self,
+        blacklist: &Blacklist,
+        action: BlacklistActionType,
+        list_guild_id: GuildId,
+    ) {
+        let guild_id = self.server.id;
+        let user_id = self.member.user.id;
+        match action {
+            BlacklistActionType::None => {}
+            BlacklistActionType::Kick => {
+                let _ = self
+                    .ctx
+                    .http
+                    .remove_guild_member(guild_id.0, user_id)
+                    .exec()
+                    .await;
+            }
+            BlacklistActionType::Ban => {
+                let banned = self
+                    .ctx
+                    .http
+                    .create_ban(guild_id.0, user_id)
+                    .exec()
+                    .await
+                    .is_ok();
+                // Temporary blacklists lift the bans they issued once they expire
+                if banned && blacklist.expires_at.is_some() {
+                    let _ = self
+                        .ctx
+                        .database
+                        .execute(
+                            "INSERT INTO blacklist_bans(guild_id, user_id, blacklist_id, list_guild_id) VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+                            &[&guild_id, &UserId(user_id), &blacklist.blacklist_id, &list_guild_id],
+                        )
+                        .await;
+                }
+            }
+            BlacklistActionType::Quarantine => {
+                let mut roles = self
+                    .member
+                    .roles
+                    .iter()
+                    .filter(|r| {
+                        !self.all_roles.contains(r) && !self.guild.verified_roles.contains(*r)
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                if let Some(quarantine_role) = self.guild.quarantine_role {
+                    if self.guild_roles.contains(&quarantine_role)
+                        && !roles.contains(&quarantine_role)
+                    {
+                        roles.push(quarantine_role);
+                    }
+                }
+                if roles.iter().sorted().eq(self.member.roles.iter().sorted()) {
+                    return;
+                }
+                let role_ids = roles.iter().map(|r| r.0).collect::<Vec<_>>();
+                let updated = self
+                    .ctx
+                    .http
+                    .update_guild_member(guild_id.0, user_id)
+                    .roles(&role_ids)
+                    .exec()
+                    .await
+                    .is_ok();
+                if updated && self.ctx.role_update_guilds.contains(&guild_id) {
+                    self.ctx
+                        .bot_role_updates
+                        .insert((guild_id, UserId(user_id)), roles);
+                }
+            }
+            BlacklistActionType::Timeout => {
+                let hours = self
+                    .guild
+                    .blacklist_timeout
+                    .unwrap_or(DEFAULT_BLACKLIST_TIMEOUT);
+                let until = Utc::now() + ChronoDuration::hours(i64::from(hours));
+                let until = Timestamp::from_secs(until.timestamp()).unwrap();
+                if let Ok(update) = self
+                    .ctx
+                    .http
+                    .update_guild_member(guild_id.0, user_id)
+                    .communication_disabled_until(Some(until))
+                {
+                    let _ = update.exec().await;
+                }
+            }
+            BlacklistActionType::DmKick => {
+                let content = blacklist.message.clone().unwrap_or_else(|| {
+                    format!(
+                        "You were found on the {} blacklist. Reason: {}",
+                        self.server.name, blacklist.reason
+                    )
+                });
+                if let Ok(response) = self.ctx.http.create_private_channel(user_id).exec().await {
+                    if let Ok(channel) = response.model().await {
+                        // Messages that are too long for Discord are skipped rather than failing the kick
+                        if let Ok(message) =
+                            self.ctx.http.create_message(channel.id).content(&content)
+                        {
+                            let _ = message.exec().await;
+                        }
+                    }
+                }
+                let _ = self
+                    .ctx
+                    .http
+                    .remove_guild_member(guild_id.0, user_id)
+                    .exec()
+                    .await;
+            }
+        }
+    }
+}